@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::entities::attachment::{Column, Entity as AttachmentEntity, Model as Attachment};
+use crate::entities::chat::{Column as ChatCol, Entity as ChatEntity};
+use crate::media::store;
+
+use super::chat_room::rooms_of;
+use super::event::ChatEvent;
+use super::media;
+use super::retention;
+
+/// 클라이언트에 내려주는 첨부 정보 (저장 키는 노출하지 않음)
+#[derive(Serialize, Debug, Clone)]
+pub struct AttachmentView {
+    pub id: i32,
+    pub kind: String,
+    pub mime: String,
+    pub size: i64,
+    pub duration_ms: Option<i32>,
+    pub waveform: Vec<u8>,
+    /// 받을 때 `?username=`을 붙인다. 한 번 보기 첨부는 url로 바로 받을 수 없고 /open에서 토큰을 받아야 한다
+    pub url: String,
+    pub view_once: bool,
+    pub viewed_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<Attachment> for AttachmentView {
+    fn from(a: Attachment) -> Self {
+        let waveform = a
+            .waveform
+            .as_deref()
+            .and_then(|w| serde_json::from_str(w).ok())
+            .unwrap_or_default();
+        AttachmentView {
+            url: format!("/api/chat/attachment/{}", a.id),
+            id: a.id,
+            kind: a.kind,
+            mime: a.mime,
+            size: a.size,
+            duration_ms: a.duration_ms,
+            waveform,
+            view_once: a.view_once,
+            viewed_at: a.viewed_at,
+        }
+    }
+}
+
+/// 메시지 id 목록에 달린 첨부를 한 번에 불러와 메시지별로 묶는다.
+pub async fn load_for_chats(conn: &DatabaseConnection, chat_ids: &[i32]) -> HashMap<i32, Vec<AttachmentView>> {
+    let mut map: HashMap<i32, Vec<AttachmentView>> = HashMap::new();
+    if chat_ids.is_empty() {
+        return map;
+    }
+    let rows = AttachmentEntity::find()
+        .filter(Column::ChatId.is_in(chat_ids.to_vec()))
+        .all(conn)
+        .await
+        .unwrap_or_default();
+    for row in rows {
+        map.entry(row.chat_id).or_default().push(row.into());
+    }
+    map
+}
+
+pub async fn download(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    Path(id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Response, StatusCode> {
+    let attachment = match AttachmentEntity::find_by_id(id).one(&conn).await {
+        Ok(Some(a)) => a,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    if attachment.view_once {
+        return Ok(media::consume(&conn, &queue, attachment, params.get("token")).await);
+    }
+    let username = params.get("username").ok_or(StatusCode::FORBIDDEN)?;
+    match visible_to(&conn, attachment.chat_id, username).await {
+        Ok(true) => {}
+        Ok(false) => return Err(StatusCode::FORBIDDEN),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+    let bytes = store::load(&attachment.storage_key)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    Ok(([(header::CONTENT_TYPE, attachment.mime)], bytes).into_response())
+}
+
+// 첨부가 달린 메시지나 그 전달본이 username이 참가한 방에 남아 있는지
+async fn visible_to(conn: &DatabaseConnection, chat_id: i32, username: &str) -> Result<bool, DbErr> {
+    let rooms: Vec<i32> = rooms_of(conn, username).await?.into_iter().map(|room| room.id).collect();
+    if rooms.is_empty() {
+        return Ok(false);
+    }
+    let found = ChatEntity::find()
+        .filter(Condition::any().add(ChatCol::Id.eq(chat_id)).add(ChatCol::ForwardedFromId.eq(chat_id)))
+        .filter(ChatCol::RoomId.is_in(rooms))
+        .filter(retention::not_expired())
+        .count(conn)
+        .await?;
+    Ok(found > 0)
+}
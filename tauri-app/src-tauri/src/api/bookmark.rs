@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use sea_orm::{
+    sea_query::{Expr, OnConflict, Query as SeaQuery},
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{
+    bookmark::{ActiveModel, Column, Entity as BookmarkEntity, Model as Bookmark},
+    chat::{self, Entity as ChatEntity, Model as Chat},
+    room::Entity as RoomEntity,
+};
+
+use super::chat_room::participants_of;
+use super::retention;
+
+const DEFAULT_LIMIT: u64 = 20;
+const MAX_LIMIT: u64 = 100;
+const MAX_NOTE_CHARS: usize = 1000;
+const MAX_TAGS: usize = 10;
+const MAX_TAG_CHARS: usize = 30;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+fn fail<T>(error: impl Into<String>) -> Json<ApiResponse<T>> {
+    Json(ApiResponse { success: 0, error: Some(error.into()), data: None })
+}
+
+#[derive(Deserialize)]
+pub struct NewBookmark {
+    pub username: String,
+    pub chat_id: i32,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct BookmarkEdit {
+    pub username: String,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct BookmarkItem {
+    pub id: i32,
+    pub room_id: i32,
+    pub sender: String,
+    pub note: Option<String>,
+    pub tags: Vec<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    /// 메시지가 지워졌거나 보관 기간이 지나 더는 볼 수 없으면 true (chat은 null)
+    pub removed: bool,
+    /// 지금의 메시지 (수정되었으면 수정된 내용)
+    pub chat: Option<Chat>,
+}
+
+#[derive(Serialize)]
+pub struct BookmarkPage {
+    pub items: Vec<BookmarkItem>,
+    pub total: u64,
+}
+
+/// 태그 정리: 앞의 #과 공백을 떼고 소문자로, 중복과 빈 태그는 뺀다.
+fn normalize_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    let mut out: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').trim().to_lowercase();
+        if tag.is_empty() || out.contains(&tag) {
+            continue;
+        }
+        if tag.chars().count() > MAX_TAG_CHARS || tag.contains('"') {
+            return Err(format!("태그는 따옴표 없이 {}자 이내로 입력하세요.", MAX_TAG_CHARS));
+        }
+        out.push(tag);
+    }
+    if out.len() > MAX_TAGS {
+        return Err(format!("태그는 {}개까지 달 수 있습니다.", MAX_TAGS));
+    }
+    Ok(out)
+}
+
+fn normalize_note(note: Option<String>) -> Result<Option<String>, String> {
+    let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    if note.as_ref().is_some_and(|n| n.chars().count() > MAX_NOTE_CHARS) {
+        return Err(format!("메모는 {}자 이내여야 합니다.", MAX_NOTE_CHARS));
+    }
+    Ok(note)
+}
+
+fn item(bookmark: Bookmark, chat: Option<Chat>) -> BookmarkItem {
+    BookmarkItem {
+        id: bookmark.id,
+        room_id: bookmark.room_id,
+        sender: bookmark.sender,
+        note: bookmark.note,
+        tags: serde_json::from_str(&bookmark.tags).unwrap_or_default(),
+        created_at: bookmark.created_at,
+        updated_at: bookmark.updated_at,
+        removed: chat.is_none(),
+        chat,
+    }
+}
+
+// 참가 중인 방의, 아직 보관 기간이 남은 메시지만 북마크할 수 있다
+async fn readable_chat(conn: &DatabaseConnection, chat_id: i32, username: &str) -> Result<Chat, String> {
+    let chat = match ChatEntity::find_by_id(chat_id).filter(retention::not_expired()).one(conn).await {
+        Ok(Some(chat)) => chat,
+        Ok(None) => return Err("메시지를 찾을 수 없습니다.".to_string()),
+        Err(e) => return Err(format!("DB 오류: {}", e)),
+    };
+    match RoomEntity::find_by_id(chat.room_id).one(conn).await {
+        Ok(Some(room)) if participants_of(&room).iter().any(|p| p == username) => Ok(chat),
+        Ok(_) => Err("참가 중인 방의 메시지만 저장할 수 있습니다.".to_string()),
+        Err(e) => Err(format!("DB 오류: {}", e)),
+    }
+}
+
+async fn owned(conn: &DatabaseConnection, id: i32, username: &str) -> Result<Bookmark, String> {
+    match BookmarkEntity::find_by_id(id).one(conn).await {
+        Ok(Some(b)) if b.username == username => Ok(b),
+        Ok(_) => Err("북마크를 찾을 수 없습니다.".to_string()),
+        Err(e) => Err(format!("DB 오류: {}", e)),
+    }
+}
+
+/// POST /bookmark: 이미 저장한 메시지면 메모와 태그를 바꾼다.
+pub async fn add_bookmark(
+    State(conn): State<DatabaseConnection>,
+    Json(req): Json<NewBookmark>,
+) -> Json<ApiResponse<BookmarkItem>> {
+    let username = req.username.trim();
+    if username.is_empty() {
+        return fail("username이 필요합니다.");
+    }
+    let (note, tags) = match (normalize_note(req.note), normalize_tags(&req.tags)) {
+        (Ok(note), Ok(tags)) => (note, tags),
+        (Err(e), _) | (_, Err(e)) => return fail(e),
+    };
+    let chat = match readable_chat(&conn, req.chat_id, username).await {
+        Ok(chat) => chat,
+        Err(e) => return fail(e),
+    };
+
+    let now = chrono::Utc::now().naive_utc();
+    let row = ActiveModel {
+        id: ActiveValue::NotSet,
+        username: ActiveValue::Set(username.to_string()),
+        chat_id: ActiveValue::Set(Some(chat.id)),
+        room_id: ActiveValue::Set(chat.room_id),
+        sender: ActiveValue::Set(chat.sender.clone()),
+        note: ActiveValue::Set(note),
+        tags: ActiveValue::Set(serde_json::to_string(&tags).unwrap()),
+        created_at: ActiveValue::Set(now),
+        updated_at: ActiveValue::Set(now),
+    };
+    let saved = BookmarkEntity::insert(row)
+        .on_conflict(
+            OnConflict::columns([Column::Username, Column::ChatId])
+                .update_columns([Column::Note, Column::Tags, Column::UpdatedAt])
+                .to_owned(),
+        )
+        .exec_with_returning(&conn)
+        .await;
+    match saved {
+        Ok(bookmark) => Json(ApiResponse { success: 1, error: None, data: Some(item(bookmark, Some(chat))) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// PUT /bookmark/{id}: 메모와 태그 수정 (메시지가 지워진 뒤에도 가능)
+pub async fn edit_bookmark(
+    State(conn): State<DatabaseConnection>,
+    Path(id): Path<i32>,
+    Json(req): Json<BookmarkEdit>,
+) -> Json<ApiResponse<BookmarkItem>> {
+    let (note, tags) = match (normalize_note(req.note), normalize_tags(&req.tags)) {
+        (Ok(note), Ok(tags)) => (note, tags),
+        (Err(e), _) | (_, Err(e)) => return fail(e),
+    };
+    let bookmark = match owned(&conn, id, req.username.trim()).await {
+        Ok(b) => b,
+        Err(e) => return fail(e),
+    };
+    let mut active: ActiveModel = bookmark.into();
+    active.note = ActiveValue::Set(note);
+    active.tags = ActiveValue::Set(serde_json::to_string(&tags).unwrap());
+    active.updated_at = ActiveValue::Set(chrono::Utc::now().naive_utc());
+    let bookmark = match active.update(&conn).await {
+        Ok(b) => b,
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    };
+    let chat = match bookmark.chat_id {
+        Some(chat_id) => ChatEntity::find_by_id(chat_id)
+            .filter(retention::not_expired())
+            .one(&conn)
+            .await
+            .ok()
+            .flatten(),
+        None => None,
+    };
+    Json(ApiResponse { success: 1, error: None, data: Some(item(bookmark, chat)) })
+}
+
+/// DELETE /bookmark/{id}?username=
+pub async fn delete_bookmark(
+    State(conn): State<DatabaseConnection>,
+    Path(id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<i32>> {
+    let username = params.get("username").map(|u| u.trim()).unwrap_or_default();
+    if let Err(e) = owned(&conn, id, username).await {
+        return fail(e);
+    }
+    match BookmarkEntity::delete_by_id(id).exec(&conn).await {
+        Ok(_) => Json(ApiResponse { success: 1, error: None, data: Some(id) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// GET /bookmark?username=&q=&tag=&room_id=&limit=&offset=
+///
+/// q는 메모, 태그, 메시지 본문에서 찾는다. 최근에 저장한 것부터.
+pub async fn list_bookmarks(
+    State(conn): State<DatabaseConnection>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<BookmarkPage>> {
+    let username = params.get("username").map(|u| u.trim()).unwrap_or_default();
+    if username.is_empty() {
+        return fail("username이 필요합니다.");
+    }
+    let limit = params.get("limit").and_then(|v| v.parse::<u64>().ok()).unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = params.get("offset").and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+
+    let mut select = BookmarkEntity::find().filter(Column::Username.eq(username));
+    if let Some(room_id) = params.get("room_id").and_then(|v| v.parse::<i32>().ok()) {
+        select = select.filter(Column::RoomId.eq(room_id));
+    }
+    if let Some(tag) = params.get("tag").map(|t| t.trim().trim_start_matches('#').to_lowercase()).filter(|t| !t.is_empty()) {
+        // LIKE로 찾으면 태그 안의 `_`, `%`가 와일드카드가 되므로 배열 원소로 비교한다
+        select = select.filter(Expr::cust_with_values("bookmark.tags::jsonb @> jsonb_build_array($1::text)", [tag]));
+    }
+    if let Some(q) = params.get("q").map(|q| q.trim()).filter(|q| !q.is_empty()) {
+        let in_message = SeaQuery::select()
+            .column(chat::Column::Id)
+            .from(chat::Entity)
+            .and_where(Expr::cust_with_values("strpos(lower(chat.message), lower($1)) > 0", [q.to_string()]))
+            .cond_where(retention::not_expired())
+            .to_owned();
+        select = select.filter(
+            Condition::any()
+                .add(Expr::cust_with_values("strpos(lower(coalesce(bookmark.note, '')), lower($1)) > 0", [q.to_string()]))
+                .add(Expr::cust_with_values("strpos(lower(bookmark.tags), lower($1)) > 0", [q.to_string()]))
+                .add(Column::ChatId.in_subquery(in_message)),
+        );
+    }
+
+    let total = match select.clone().count(&conn).await {
+        Ok(total) => total,
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    };
+    let bookmarks = select
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(&conn)
+        .await;
+    let bookmarks = match bookmarks {
+        Ok(rows) => rows,
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    };
+
+    // 보관 기간이 지난 메시지도 지워진 것으로 보여준다
+    let chat_ids: Vec<i32> = bookmarks.iter().filter_map(|b| b.chat_id).collect();
+    let mut chats: HashMap<i32, Chat> = if chat_ids.is_empty() {
+        HashMap::new()
+    } else {
+        ChatEntity::find()
+            .filter(chat::Column::Id.is_in(chat_ids))
+            .filter(retention::not_expired())
+            .all(&conn)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| (c.id, c))
+            .collect()
+    };
+    let items = bookmarks
+        .into_iter()
+        .map(|b| {
+            let chat = b.chat_id.and_then(|id| chats.remove(&id));
+            item(b, chat)
+        })
+        .collect();
+    Json(ApiResponse { success: 1, error: None, data: Some(BookmarkPage { items, total }) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_normalized() {
+        let tags = vec![" #Rust ".to_string(), "rust".to_string(), "".to_string(), "회의".to_string()];
+        assert_eq!(normalize_tags(&tags).unwrap(), vec!["rust", "회의"]);
+        assert!(normalize_tags(&["a\"b".to_string()]).is_err());
+        let many: Vec<String> = (0..=MAX_TAGS).map(|i| format!("t{}", i)).collect();
+        assert!(normalize_tags(&many).is_err());
+    }
+}
@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    Json,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::broadcast;
+
+use crate::bot::{self, webhook, BotEvent};
+use crate::entities::{
+    bot::{ActiveModel, Column, Entity as BotEntity, Model as Bot},
+    room::Entity as RoomEntity,
+    users::{self, Entity as UsersEntity},
+};
+use crate::media::store;
+use crate::preview::fetch::FetchPolicy;
+
+use super::chat_room::participants_of;
+use super::event::ChatEvent;
+
+/// 봇 토큰 앞에 붙여 로그인 토큰(JWT)과 구분한다
+const TOKEN_PREFIX: &str = "bot_";
+const MIN_USERNAME_LEN: usize = 3;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+fn fail<T>(error: impl Into<String>) -> Json<ApiResponse<T>> {
+    Json(ApiResponse { success: 0, error: Some(error.into()), data: None })
+}
+
+#[derive(Deserialize)]
+pub struct NewBot {
+    pub owner: String,
+    pub username: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// 없으면 message만
+    #[serde(default)]
+    pub events: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct BotEdit {
+    pub owner: String,
+    /// 빈 문자열이면 webhook을 끈다
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    #[serde(default)]
+    pub events: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct BotOwner {
+    pub owner: String,
+}
+
+#[derive(Serialize)]
+pub struct BotView {
+    pub id: i32,
+    pub username: String,
+    pub owner: String,
+    pub webhook_url: Option<String>,
+    pub events: Vec<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<Bot> for BotView {
+    fn from(bot: Bot) -> Self {
+        BotView {
+            events: serde_json::from_str(&bot.events).unwrap_or_default(),
+            id: bot.id,
+            username: bot.username,
+            owner: bot.owner,
+            webhook_url: bot.webhook_url,
+            created_at: bot.created_at,
+        }
+    }
+}
+
+/// 토큰 원문은 만들거나 새로 발급할 때 이 응답에서 한 번만 보여준다
+#[derive(Serialize)]
+pub struct BotWithToken {
+    pub bot: BotView,
+    pub token: String,
+}
+
+/// 토큰 저장용 해시 (수신 webhook 토큰도 같이 쓴다)
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn new_token() -> (String, String) {
+    let token = format!("{}{}", TOKEN_PREFIX, store::random_hex(24));
+    let hash = hash_token(&token);
+    (token, hash)
+}
+
+fn bearer(headers: &HeaderMap) -> Option<&str> {
+    headers.get(header::AUTHORIZATION)?.to_str().ok()?.strip_prefix("Bearer ")
+}
+
+/// 구독 이벤트 목록을 검사하고 JSON 문자열로 만든다
+fn parse_events(events: &[String]) -> Result<String, String> {
+    let mut out: Vec<&str> = Vec::new();
+    for event in events.iter().map(|e| e.trim()) {
+        if !bot::EVENTS.contains(&event) {
+            return Err(format!("알 수 없는 이벤트 '{}' (가능: {})", event, bot::EVENTS.join(", ")));
+        }
+        if !out.contains(&event) {
+            out.push(event);
+        }
+    }
+    Ok(serde_json::to_string(&out).unwrap())
+}
+
+fn check_url(url: &str) -> Result<(), String> {
+    match url::Url::parse(url) {
+        Ok(u) if u.scheme() == "http" || u.scheme() == "https" => Ok(()),
+        _ => Err("webhook 주소는 http(s) URL이어야 합니다.".to_string()),
+    }
+}
+
+async fn owned(conn: &DatabaseConnection, id: i32, owner: &str) -> Result<Bot, String> {
+    match BotEntity::find_by_id(id).one(conn).await {
+        Ok(Some(bot)) if bot.owner == owner => Ok(bot),
+        Ok(_) => Err("봇을 찾을 수 없습니다.".to_string()),
+        Err(e) => Err(format!("DB 오류: {}", e)),
+    }
+}
+
+/// POST /bot: 봇 계정을 만들고 API 토큰을 발급한다. 사람 계정만 봇을 만들 수 있다.
+pub async fn create_bot(
+    State(conn): State<DatabaseConnection>,
+    Json(req): Json<NewBot>,
+) -> Json<ApiResponse<BotWithToken>> {
+    let username = req.username.trim().to_string();
+    if username.len() < MIN_USERNAME_LEN {
+        return fail(format!("봇 아이디는 {}자 이상이어야 합니다.", MIN_USERNAME_LEN));
+    }
+    let events = match parse_events(req.events.as_deref().unwrap_or(&["message".to_string()])) {
+        Ok(events) => events,
+        Err(e) => return fail(e),
+    };
+    let webhook_url = req.webhook_url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+    if let Some(Err(e)) = webhook_url.as_deref().map(check_url) {
+        return fail(e);
+    }
+    match UsersEntity::find().filter(users::Column::Username.eq(&req.owner)).one(&conn).await {
+        Ok(Some(owner)) if !owner.is_bot => {}
+        Ok(_) => return fail("봇을 만들 수 없는 사용자입니다."),
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    }
+    match UsersEntity::find().filter(users::Column::Username.eq(&username)).one(&conn).await {
+        Ok(None) => {}
+        Ok(Some(_)) => return fail("이미 존재하는 아이디입니다."),
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    }
+
+    let (token, token_hash) = new_token();
+    let created = async {
+        let txn = conn.begin().await?;
+        users::ActiveModel {
+            id: ActiveValue::NotSet,
+            username: ActiveValue::Set(username.clone()),
+            // 로그인에 쓰지 않는다 (login에서 봇은 거른다)
+            password: ActiveValue::Set(String::new()),
+            display_name: ActiveValue::Set(req.display_name.clone()),
+            status: ActiveValue::Set(None),
+            avatar: ActiveValue::Set(None),
+            is_bot: ActiveValue::Set(true),
+            owner: ActiveValue::Set(Some(req.owner.clone())),
+        }
+        .insert(&txn)
+        .await?;
+        let bot = ActiveModel {
+            id: ActiveValue::NotSet,
+            username: ActiveValue::Set(username.clone()),
+            owner: ActiveValue::Set(req.owner.clone()),
+            token_hash: ActiveValue::Set(token_hash),
+            webhook_url: ActiveValue::Set(webhook_url),
+            events: ActiveValue::Set(events),
+            created_at: ActiveValue::Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok::<_, sea_orm::DbErr>(bot)
+    }
+    .await;
+    match created {
+        Ok(bot) => Json(ApiResponse { success: 1, error: None, data: Some(BotWithToken { bot: bot.into(), token }) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// GET /bot?owner=: 내가 만든 봇 목록
+pub async fn list_bots(
+    State(conn): State<DatabaseConnection>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<Vec<BotView>>> {
+    let Some(owner) = params.get("owner") else {
+        return fail("owner가 필요합니다.");
+    };
+    match BotEntity::find().filter(Column::Owner.eq(owner)).order_by_asc(Column::Id).all(&conn).await {
+        Ok(bots) => Json(ApiResponse { success: 1, error: None, data: Some(bots.into_iter().map(Into::into).collect()) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// PUT /bot/{id}: webhook 주소와 구독 이벤트를 바꾼다
+pub async fn edit_bot(
+    State(conn): State<DatabaseConnection>,
+    Path(id): Path<i32>,
+    Json(req): Json<BotEdit>,
+) -> Json<ApiResponse<BotView>> {
+    let bot = match owned(&conn, id, &req.owner).await {
+        Ok(bot) => bot,
+        Err(e) => return fail(e),
+    };
+    let mut row: ActiveModel = bot.into();
+    if let Some(url) = req.webhook_url.map(|u| u.trim().to_string()) {
+        if !url.is_empty() {
+            if let Err(e) = check_url(&url) {
+                return fail(e);
+            }
+        }
+        row.webhook_url = ActiveValue::Set(Some(url).filter(|u| !u.is_empty()));
+    }
+    if let Some(events) = req.events {
+        match parse_events(&events) {
+            Ok(events) => row.events = ActiveValue::Set(events),
+            Err(e) => return fail(e),
+        }
+    }
+    match row.update(&conn).await {
+        Ok(bot) => Json(ApiResponse { success: 1, error: None, data: Some(bot.into()) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// POST /bot/{id}/token: 토큰을 새로 발급한다. 이전 토큰은 바로 못 쓰게 된다.
+pub async fn rotate_token(
+    State(conn): State<DatabaseConnection>,
+    Path(id): Path<i32>,
+    Json(req): Json<BotOwner>,
+) -> Json<ApiResponse<BotWithToken>> {
+    let bot = match owned(&conn, id, &req.owner).await {
+        Ok(bot) => bot,
+        Err(e) => return fail(e),
+    };
+    let (token, token_hash) = new_token();
+    let mut row: ActiveModel = bot.into();
+    row.token_hash = ActiveValue::Set(token_hash);
+    match row.update(&conn).await {
+        Ok(bot) => Json(ApiResponse { success: 1, error: None, data: Some(BotWithToken { bot: bot.into(), token }) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// DELETE /bot/{id}?owner=: 봇과 봇 계정을 지운다 (보낸 메시지는 남는다)
+pub async fn delete_bot(
+    State(conn): State<DatabaseConnection>,
+    Path(id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<i32>> {
+    let owner = params.get("owner").map(String::as_str).unwrap_or_default();
+    let bot = match owned(&conn, id, owner).await {
+        Ok(bot) => bot,
+        Err(e) => return fail(e),
+    };
+    let deleted = async {
+        let txn = conn.begin().await?;
+        UsersEntity::delete_many()
+            .filter(users::Column::Username.eq(&bot.username))
+            .filter(users::Column::IsBot.eq(true))
+            .exec(&txn)
+            .await?;
+        bot.delete(&txn).await?;
+        txn.commit().await
+    }
+    .await;
+    match deleted {
+        Ok(()) => Json(ApiResponse { success: 1, error: None, data: Some(id) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// 봇 계정으로 보내는 요청이면 `Authorization: Bearer <봇 토큰>`이 그 봇의 것인지 확인한다.
+/// 사람 계정은 지금처럼 그대로 통과한다.
+pub async fn authorize_sender(conn: &DatabaseConnection, headers: &HeaderMap, sender: &str) -> Result<(), String> {
+    let user = UsersEntity::find()
+        .filter(users::Column::Username.eq(sender))
+        .one(conn)
+        .await
+        .map_err(|e| format!("DB 오류: {}", e))?;
+    if !user.is_some_and(|u| u.is_bot) {
+        return Ok(());
+    }
+    let Some(token) = bearer(headers).filter(|t| t.starts_with(TOKEN_PREFIX)) else {
+        return Err("봇으로 보내려면 API 토큰이 필요합니다.".to_string());
+    };
+    let bot = BotEntity::find()
+        .filter(Column::TokenHash.eq(hash_token(token)))
+        .one(conn)
+        .await
+        .map_err(|e| format!("DB 오류: {}", e))?;
+    match bot {
+        Some(bot) if bot.username == sender => Ok(()),
+        _ => Err("봇 토큰이 올바르지 않습니다.".to_string()),
+    }
+}
+
+/// 이 방에 참여 중이고 이 이벤트를 구독한 봇
+async fn subscribers(conn: &DatabaseConnection, room_id: i32, event: &str) -> Result<Vec<Bot>, sea_orm::DbErr> {
+    let Some(room) = RoomEntity::find_by_id(room_id).one(conn).await? else {
+        return Ok(vec![]);
+    };
+    let bots = BotEntity::find()
+        .filter(Column::Username.is_in(participants_of(&room)))
+        .filter(Column::WebhookUrl.is_not_null())
+        .all(conn)
+        .await?;
+    Ok(bots
+        .into_iter()
+        .filter(|bot| serde_json::from_str::<Vec<String>>(&bot.events).is_ok_and(|events| events.iter().any(|e| e == event)))
+        .collect())
+}
+
+// 이 서버가 띄운 봇은 루프백에 있으므로 그 주소만 내부망 검사에서 뺀다
+fn policy_for(url: &str, policy: &webhook::RetryPolicy) -> webhook::RetryPolicy {
+    let hosted = url::Url::parse(url).is_ok_and(|u| u.as_str().starts_with(bot::HOSTED_WEBHOOK_BASE));
+    let mut policy = policy.clone();
+    if hosted {
+        policy.addresses.allow_hosts.push("127.0.0.1".to_string());
+    }
+    policy
+}
+
+/// 방 이벤트를 구독한 봇의 webhook으로 보내는 루프. run_async에서 서버와 함께 띄운다.
+/// 봇 하나가 느리거나 죽어 있어도 다른 봇이 밀리지 않도록 전달마다 따로 돌린다.
+pub async fn run_dispatcher(conn: DatabaseConnection, queue: broadcast::Sender<ChatEvent>) {
+    let mut events = queue.subscribe();
+    let policy = webhook::RetryPolicy { addresses: FetchPolicy::from_env_var("WEBHOOK_ALLOWLIST"), ..Default::default() };
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                eprintln!("bot dispatcher lagged, {n} events were not delivered");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        // 봇이 자기 행동으로 생긴 이벤트를 다시 받지 않도록 누가 일으켰는지 본다
+        let actor = match &event {
+            ChatEvent::Message(chat) => chat.sender.clone(),
+            ChatEvent::MemberJoined { username, .. } => username.clone(),
+            _ => continue,
+        };
+        let bots = match subscribers(&conn, event.room_id(), event.name()).await {
+            Ok(bots) => bots,
+            Err(e) => {
+                eprintln!("bot subscribers lookup failed: {e}");
+                continue;
+            }
+        };
+        for target in bots.into_iter().filter(|b| b.username != actor) {
+            let Some(url) = target.webhook_url else { continue };
+            // 봇은 자기 토큰으로 같은 키를 만들어 서명을 확인한다
+            let key = target.token_hash;
+            let payload = BotEvent {
+                delivery_id: store::random_hex(16),
+                event: event.name().to_string(),
+                bot: target.username,
+                room_id: event.room_id(),
+                data: event.data(),
+            };
+            let policy = policy_for(&url, &policy);
+            tokio::spawn(async move {
+                if let Err(e) = webhook::deliver(&url, &payload, &key, &policy).await {
+                    eprintln!("bot {} webhook delivery {} failed: {}", payload.bot, payload.delivery_id, e);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_events_and_hashes_tokens() {
+        assert_eq!(parse_events(&["message".to_string(), "message".to_string(), " member_joined".to_string()]).unwrap(), r#"["message","member_joined"]"#);
+        assert!(parse_events(&["unknown".to_string()]).is_err());
+        let (token, hash) = new_token();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_eq!(hash, hash_token(&token));
+        assert_eq!(hash.len(), 64);
+    }
+}
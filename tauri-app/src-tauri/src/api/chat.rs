@@ -1,7 +1,9 @@
 use std::collections::HashMap;
+use std::convert::Infallible;
 
 use axum::{
     extract::{Query, State},
+    http::HeaderMap,
     response::{
         sse::{Event, KeepAlive, Sse},
         IntoResponse,
@@ -9,46 +11,67 @@ use axum::{
     Json,
 };
 use futures_util::stream::StreamExt;
-use serde_json::json;
 use tokio::sync::broadcast;
-use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
 use sea_orm::{
-    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, TransactionTrait,
 };
 
 use crate::entities::{
     chat::{ActiveModel as ActiveChat, Column, Entity as ChatEntity, Model as Chat},
     room::{ActiveModel as ActiveRoom, Entity as RoomEntity},
+    room_read,
 };
 
 use serde::Serialize;
 
+use super::attachment::{self, AttachmentView};
+use super::bot;
+use super::chat_room::participants_of;
+use super::delivery::{self, DeliveryStatus};
+use super::draft;
+use super::event::ChatEvent;
+use super::outgoing;
+use super::poll::{self, PollView};
+use super::retention;
+use super::validation::{self, Rejection};
+use crate::content::{self, MessageContent};
+use crate::entities::link_preview::Model as LinkPreview;
+use crate::preview;
+use crate::command;
+use crate::search;
+use crate::validation::Violation;
+
+const MAX_CLIENT_MSG_ID_LEN: usize = 64;
+
 pub async fn subscribe(
-    State(queue): State<broadcast::Sender<Chat>>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
     Query(params): Query<HashMap<String, String>>,
 ) -> impl IntoResponse {
     let room_filter = params.get("room_id").and_then(|v| v.parse::<i32>().ok());
+    let me = params.get("username").cloned();
     let stream = BroadcastStream::new(queue.subscribe()).filter_map(move |msg| {
-        let room_filter = room_filter.clone();
+        let me = me.clone();
         async move {
             match msg {
-                Ok(chat) => {
-                    if room_filter.map(|rid| chat.room_id == rid).unwrap_or(true) {
-                        Some(Ok(Event::default()
-                            .event("message")
-                            .data(json!({
-                                "id": chat.id,
-                                "sender": chat.sender,
-                                "message": chat.message,
-                                "room_id": chat.room_id,
-                                "timestamp": chat.timestamp
-                            }).to_string())))
+                Ok(event) => {
+                    // 한 사람에게만 가는 이벤트는 그 사람으로 구독한 연결에만
+                    let for_me = event.recipient().is_none_or(|to| me.as_deref() == Some(to));
+                    if for_me && room_filter.map(|rid| event.room_id() == rid).unwrap_or(true) {
+                        Some(Ok::<_, Infallible>(Event::default()
+                            .event(event.name())
+                            .data(event.data().to_string())))
                     } else {
                         None
                     }
                 }
-                Err(e) => Some(Err(e)),
+                // 밀려서 놓친 이벤트가 있어도 연결은 유지한다 (끊으면 클라이언트가 나머지도 못 받는다)
+                Err(BroadcastStreamRecvError::Lagged(n)) => {
+                    eprintln!("sse subscriber lagged, {n} events skipped");
+                    None
+                }
             }
         }
     });
@@ -56,75 +79,338 @@ pub async fn subscribe(
     Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
-#[derive(serde::Deserialize)]
+/// 보낼 메시지. 요청 본문(`RawNewMessage`)을 검사해 만든다.
 pub struct NewMessage {
     pub sender: String,
-    pub message: String,
     pub room_id: i32,
+    pub content: MessageContent,
+    /// 클라이언트가 만든 메시지 id. 같은 id로 다시 보내면 새로 저장하지 않고 처음 저장한 메시지를 돌려준다.
+    pub client_msg_id: Option<String>,
 }
 
-#[derive(Serialize)]
+/// /chat/send 요청 본문. `{sender, room_id, kind, content}` 모양이고,
+/// kind 없이 `message`만 오면 예전 클라이언트로 보고 text로 받는다.
+/// 내용 검사는 `NewMessage::try_from`에서 해서 잘못된 요청도 SendResponse로 답한다.
+#[derive(serde::Deserialize)]
+pub struct RawNewMessage {
+    pub sender: String,
+    pub room_id: i32,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub content: Option<serde_json::Value>,
+    #[serde(default)]
+    pub client_msg_id: Option<String>,
+}
+
+impl TryFrom<RawNewMessage> for NewMessage {
+    type Error = String;
+
+    fn try_from(raw: RawNewMessage) -> Result<Self, String> {
+        let content = match (raw.kind, raw.message) {
+            (Some(kind), _) => {
+                let content = MessageContent::parse(&kind, raw.content.unwrap_or_default())?;
+                if !content::SENDABLE_KINDS.contains(&content.kind()) {
+                    return Err(format!(
+                        "'{}' 메시지는 이 API로 보낼 수 없습니다. ({} 중 하나)",
+                        kind,
+                        content::SENDABLE_KINDS.join(", ")
+                    ));
+                }
+                content
+            }
+            (None, Some(message)) => MessageContent::text(message),
+            (None, None) => return Err("kind와 content, 또는 message가 필요합니다.".to_string()),
+        };
+        Ok(NewMessage { sender: raw.sender, room_id: raw.room_id, content, client_msg_id: raw.client_msg_id })
+    }
+}
+
+#[derive(Serialize, Default)]
 pub struct SendResponse {
     pub success: i32,
     pub error: Option<String>,
     pub chat: Option<Chat>,
+    /// 검증에 실패한 항목 (필드와 구간). 클라이언트가 입력창에서 표시할 수 있다.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<Violation>,
+    /// 명령어 실행 결과처럼 보낸 사람에게만 보여줄 안내
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ephemeral: Option<String>,
+}
+
+impl SendResponse {
+    /// 검증 파이프라인에서 거부된 응답
+    pub fn rejected(rejection: Rejection) -> Self {
+        SendResponse {
+            success: 0,
+            error: Some(rejection.to_string()),
+            chat: None,
+            violations: rejection.violations(),
+            ..Default::default()
+        }
+    }
 }
 
 pub async fn send(
     State(conn): State<DatabaseConnection>,
-    State(queue): State<broadcast::Sender<Chat>>,
-    Json(new_message): Json<NewMessage>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    headers: HeaderMap,
+    Json(raw): Json<RawNewMessage>,
 ) -> Json<SendResponse> {
-    // 입력값 검증
-    if new_message.sender.trim().is_empty() || new_message.message.trim().is_empty() {
-        return Json(SendResponse { success: 0, error: Some("보내는 사람과 메시지를 모두 입력하세요.".to_string()), chat: None });
+    let mut new_message = match NewMessage::try_from(raw) {
+        Ok(new_message) => new_message,
+        Err(e) => return Json(SendResponse { success: 0, error: Some(e), chat: None, ..Default::default() }),
+    };
+    if let Err(e) = validate(&new_message) {
+        return Json(SendResponse { success: 0, error: Some(e), chat: None, ..Default::default() });
     }
-    if new_message.message.len() > 500 {
-        return Json(SendResponse { success: 0, error: Some("메시지는 500자 이내여야 합니다.".to_string()), chat: None });
+    // 봇 계정으로 보내려면 그 봇의 API 토큰이 있어야 한다
+    if let Err(e) = bot::authorize_sender(&conn, &headers, &new_message.sender).await {
+        return Json(SendResponse { success: 0, error: Some(e), chat: None, ..Default::default() });
+    }
+    // `/`로 시작하면 명령어로 처리하고, `//`는 앞의 `/` 하나를 떼고 그대로 보낸다
+    let invoked = match &new_message.content {
+        MessageContent::Text { text, .. } => command::parse(text).map(|(name, args)| (name, args.to_string())),
+        _ => None,
+    };
+    if let Some((name, args)) = invoked {
+        return Json(super::command::execute(&conn, &queue, &headers, &new_message, &name, &args).await);
     }
+    if let MessageContent::Text { text, .. } = &mut new_message.content {
+        if let Some(rest) = text.strip_prefix("//") {
+            *text = format!("/{}", rest);
+        }
+    }
+    Json(post_message(&conn, &queue, &new_message).await)
+}
+
+/// 메시지를 검증하고 저장한 뒤 방에 알린다. 명령어가 올리는 메시지도 여기를 거친다.
+pub async fn post_message(
+    conn: &DatabaseConnection,
+    queue: &broadcast::Sender<ChatEvent>,
+    new_message: &NewMessage,
+) -> SendResponse {
+    // 재전송이면 이미 저장(과 브로드캐스트)된 메시지를 그대로 돌려준다
+    match already_sent(conn, new_message).await {
+        Ok(Some(chat)) => return SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() },
+        Ok(None) => {}
+        Err(e) => return SendResponse { success: 0, error: Some(e), chat: None, ..Default::default() },
+    }
+    if let Err(rejection) = validation::check(conn, new_message, &[]).await {
+        return SendResponse::rejected(rejection);
+    }
+    let chat = match insert_and_clear_draft(conn, new_message).await {
+        Ok(chat) => chat,
+        Err(e) => {
+            // 같은 id의 요청이 동시에 들어와 유니크 제약에 걸린 경우
+            if let Ok(Some(chat)) = already_sent(conn, new_message).await {
+                return SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() };
+            }
+            return SendResponse { success: 0, error: Some(e), chat: None, ..Default::default() };
+        }
+    };
+    outgoing::publish(conn, queue, ChatEvent::Message(chat.clone())).await;
+    // 링크 미리보기는 응답을 막지 않도록 백그라운드에서 가져온다
+    preview::spawn_for_message(conn.clone(), queue.clone(), chat.clone());
+    SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() }
+}
+
+/// 형식 검증 (예약 메시지도 같은 규칙을 쓴다). 길이·금지어 같은 설정 가능한 규칙은 validation::check에서.
+pub fn validate(new_message: &NewMessage) -> Result<(), String> {
+    if new_message.sender.trim().is_empty() {
+        return Err("보내는 사람과 메시지를 모두 입력하세요.".to_string());
+    }
+    if let Some(id) = &new_message.client_msg_id {
+        if id.is_empty() || id.len() > MAX_CLIENT_MSG_ID_LEN || !id.chars().all(|c| c.is_ascii_graphic()) {
+            return Err(format!("client_msg_id는 공백 없는 {}자 이내의 영문/숫자/기호여야 합니다.", MAX_CLIENT_MSG_ID_LEN));
+        }
+    }
+    new_message.content.validate()
+}
+
+// 보낸 메시지와 그 방의 임시 저장 삭제를 한 트랜잭션으로
+async fn insert_and_clear_draft(conn: &DatabaseConnection, new_message: &NewMessage) -> Result<Chat, String> {
+    let txn = conn.begin().await.map_err(|e| format!("DB 오류: {}", e))?;
+    let chat = insert_message(&txn, new_message).await?;
+    draft::clear(&txn, &new_message.sender, new_message.room_id)
+        .await
+        .map_err(|e| format!("DB 오류: {}", e))?;
+    txn.commit().await.map_err(|_| "메시지 저장에 실패했습니다.".to_string())?;
+    Ok(chat)
+}
+
+/// (sender, client_msg_id)로 이미 저장된 메시지. 다른 방에 쓴 id를 다시 쓰면 거부한다.
+pub async fn already_sent(conn: &DatabaseConnection, new_message: &NewMessage) -> Result<Option<Chat>, String> {
+    let Some(client_msg_id) = &new_message.client_msg_id else {
+        return Ok(None);
+    };
+    let found = ChatEntity::find()
+        .filter(Column::Sender.eq(new_message.sender.as_str()))
+        .filter(Column::ClientMsgId.eq(client_msg_id.as_str()))
+        .one(conn)
+        .await
+        .map_err(|e| format!("DB 오류: {}", e))?;
+    match found {
+        Some(chat) if chat.room_id != new_message.room_id => {
+            Err("이미 다른 방에서 쓴 client_msg_id입니다.".to_string())
+        }
+        found => Ok(found),
+    }
+}
+
+/// 방 확인, 참가자 갱신, 메시지 저장까지 처리한다. 브로드캐스트는 호출하는 쪽에서 한다.
+/// 트랜잭션 안에서도 호출할 수 있다.
+pub async fn insert_message<C: ConnectionTrait + TransactionTrait>(conn: &C, new_message: &NewMessage) -> Result<Chat, String> {
+    store_message(conn, new_message, true).await
+}
+
+/// 수신 webhook처럼 방 참가자가 아닌 이름으로 남기는 메시지. 보낸 사람을 참가자에 넣지 않는다.
+pub async fn insert_external_message<C: ConnectionTrait + TransactionTrait>(conn: &C, new_message: &NewMessage) -> Result<Chat, String> {
+    store_message(conn, new_message, false).await
+}
+
+async fn store_message<C: ConnectionTrait + TransactionTrait>(conn: &C, new_message: &NewMessage, join: bool) -> Result<Chat, String> {
     // 방 존재 확인
-    let room = match RoomEntity::find_by_id(new_message.room_id).one(&conn).await {
+    let room = match RoomEntity::find_by_id(new_message.room_id).one(conn).await {
         Ok(Some(room)) => room,
-        _ => return Json(SendResponse { success: 0, error: Some("존재하지 않는 방입니다.".to_string()), chat: None }),
+        _ => return Err("존재하지 않는 방입니다.".to_string()),
     };
     // 참가자 목록 업데이트
     let mut participants: Vec<String> = serde_json::from_str(&room.participants).unwrap_or_default();
-    if !participants.contains(&new_message.sender) {
+    if join && !participants.contains(&new_message.sender) {
         participants.push(new_message.sender.clone());
+        let room_update = ActiveRoom {
+            id: ActiveValue::set(room.id),
+            participants: ActiveValue::set(serde_json::to_string(&participants).unwrap()),
+            ..Default::default()
+        };
+        room_update.update(conn).await.map_err(|e| format!("DB 오류: {}", e))?;
     }
-    let participants = serde_json::to_string(&participants).unwrap();
-    let room_update = ActiveRoom {
-        id: ActiveValue::set(room.id),
-        participants: ActiveValue::set(participants),
-    };
-    let _ = room_update.update(&conn).await;
     // 메시지 저장
+    let now = chrono::Utc::now().naive_utc();
+    // markdown은 여기서 정리된 HTML을 만들어 두므로 브로드캐스트되는 내용도 이미 안전하다
+    let content = new_message.content.rendered();
     let chat_model = ActiveChat {
         id: ActiveValue::not_set(),
         sender: ActiveValue::set(new_message.sender.clone()),
-        message: ActiveValue::set(new_message.message.clone()),
+        message: ActiveValue::set(content.summary()),
+        content: ActiveValue::set(content),
         room_id: ActiveValue::set(new_message.room_id),
-        timestamp: ActiveValue::set(chrono::Utc::now().naive_utc()),
+        timestamp: ActiveValue::set(now),
+        expires_at: ActiveValue::set(retention::expires_at(&room, now)),
+        client_msg_id: ActiveValue::set(new_message.client_msg_id.clone()),
+        ..Default::default()
     };
-    let chat = match chat_model.insert(&conn).await {
-        Ok(chat) => chat,
-        Err(_) => return Json(SendResponse { success: 0, error: Some("메시지 저장에 실패했습니다.".to_string()), chat: None }),
-    };
-    let _ = queue.send(chat.clone());
-    Json(SendResponse { success: 1, error: None, chat: Some(chat) })
+    let chat = chat_model.insert(conn).await.map_err(|_| "메시지 저장에 실패했습니다.".to_string())?;
+    // 색인 실패는 검색에서만 빠질 뿐이므로 전송은 성공으로 둔다
+    if let Err(e) = index_in_savepoint(conn, &chat).await {
+        eprintln!("search index failed for chat {}: {}", chat.id, e);
+    }
+    Ok(chat)
+}
+
+// 트랜잭션 안에서 실패한 문장은 그 트랜잭션의 나머지를 모두 실패하게 만든다.
+// 색인은 savepoint 안에서 하고 실패하면 그 부분만 되돌려 메시지 저장은 살린다.
+async fn index_in_savepoint<C: TransactionTrait>(conn: &C, chat: &Chat) -> Result<(), DbErr> {
+    let savepoint = conn.begin().await?;
+    match search::index_message(&savepoint, chat.id, &chat.message).await {
+        Ok(()) => savepoint.commit().await,
+        Err(e) => {
+            let _ = savepoint.rollback().await;
+            Err(e)
+        }
+    }
+}
+
+/// get_chat 응답 항목. 기존 필드는 그대로 펼치고 첨부와 링크 미리보기를 덧붙인다.
+#[derive(Serialize)]
+pub struct ChatItem {
+    #[serde(flatten)]
+    pub chat: Chat,
+    pub attachments: Vec<AttachmentView>,
+    pub link_previews: Vec<LinkPreview>,
+    /// 이 메시지를 읽은 참가자 (보낸 사람 제외)
+    pub read_by: Vec<String>,
+    /// 아직 읽지 않은 참가자 수 (카카오톡의 숫자)
+    pub unread_count: usize,
+    /// 기기에 도착이 확인된 참가자 (읽은 사람 포함)
+    pub delivered_to: Vec<String>,
+    pub status: DeliveryStatus,
+    /// 투표 메시지면 현재 집계
+    pub poll: Option<PollView>,
 }
 
 pub async fn get_chat(
     State(conn): State<DatabaseConnection>,
     Query(params): Query<HashMap<String, String>>,
-) -> Json<Vec<Chat>> {
-    let room_id = params.get("room_id").unwrap();
+) -> Json<Vec<ChatItem>> {
+    let room_id = params.get("room_id").unwrap().parse::<i32>().unwrap();
+
+    // 보관 기간이 지난 메시지는 정리 전이라도 보여주지 않는다
+    let chats = ChatEntity::find()
+        .filter(Column::RoomId.eq(room_id))
+        .filter(retention::not_expired())
+        .all(&conn)
+        .await
+        .unwrap();
+    let ids: Vec<i32> = chats.iter().map(|c| c.id).collect();
+    // 전달된 메시지의 첨부는 원본에 달려 있다
+    let attachment_ids: Vec<i32> = chats.iter().map(|c| c.forwarded_from_id.unwrap_or(c.id)).collect();
+    let attachments = attachment::load_for_chats(&conn, &attachment_ids).await;
+    let mut link_previews = preview::load_for_chats(&conn, &chats).await;
+    let mut deliveries = delivery::load_for_chats(&conn, &ids).await;
+    let mut polls = poll::load_for_chats(&conn, &ids, params.get("username").map(String::as_str)).await;
+    let (participants, positions) = read_positions(&conn, room_id).await;
 
     Json(
-        ChatEntity::find()
-            .filter(Column::RoomId.eq(room_id.parse::<i32>().unwrap()))
-            .all(&conn)
-            .await
-            .unwrap(),
+        chats
+            .into_iter()
+            .map(|chat| {
+                let others: Vec<&String> = participants.iter().filter(|p| **p != chat.sender).collect();
+                let read_by: Vec<String> = others
+                    .iter()
+                    .filter(|p| positions.get(**p).is_some_and(|last| *last >= chat.id))
+                    .map(|p| (*p).clone())
+                    .collect();
+                // 읽었다면 도착한 것이므로 합쳐서 센다
+                let mut delivered_to = deliveries.remove(&chat.id).unwrap_or_default();
+                for reader in &read_by {
+                    if !delivered_to.contains(reader) {
+                        delivered_to.push(reader.clone());
+                    }
+                }
+                delivered_to.retain(|p| others.contains(&p));
+                ChatItem {
+                    status: delivery::status(others.len(), delivered_to.len(), read_by.len()),
+                    delivered_to,
+                    attachments: attachments.get(&chat.forwarded_from_id.unwrap_or(chat.id)).cloned().unwrap_or_default(),
+                    link_previews: link_previews.remove(&chat.id).unwrap_or_default(),
+                    unread_count: others.len() - read_by.len(),
+                    poll: polls.remove(&chat.id),
+                    read_by,
+                    chat,
+                }
+            })
+            .collect(),
     )
 }
+
+/// 방 참가자와 참가자별 마지막으로 읽은 메시지 id (room_read 기준)
+async fn read_positions(conn: &DatabaseConnection, room_id: i32) -> (Vec<String>, HashMap<String, i32>) {
+    let participants = match RoomEntity::find_by_id(room_id).one(conn).await {
+        Ok(Some(room)) => participants_of(&room),
+        _ => vec![],
+    };
+    let positions = room_read::Entity::find()
+        .filter(room_read::Column::RoomId.eq(room_id))
+        .all(conn)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|r| r.last_read_id.map(|id| (r.username, id)))
+        .collect();
+    (participants, positions)
+}
@@ -9,6 +9,12 @@ use sea_orm::{
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+use super::draft::{self, DraftView};
+use super::event::ChatEvent;
+use super::outgoing;
+use super::retention;
 
 use crate::entities::{
     chat::{Column as ChatCol, Entity as ChatEntity},
@@ -20,6 +26,8 @@ use crate::entities::{
 pub struct NewRoom {
     pub id: Option<i32>,
     pub participants: Vec<String>,
+    #[serde(default)]
+    pub name: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -27,6 +35,10 @@ pub struct RoomWithUnread {
     pub id: i32,
     pub participants: Vec<String>,
     pub unread_count: i64,
+    /// 이 방에 쓰다 만 메시지 (다른 기기에서 이어 쓰기)
+    pub draft: Option<DraftView>,
+    /// /mute로 알림을 끈 기한 (지난 기한은 null)
+    pub muted_until: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, FromQueryResult, Serialize)]
@@ -40,6 +52,34 @@ pub struct ReadUpdate {
     pub last_read_id: Option<i32>,
 }
 
+/// 방의 참가자 목록 (participants 컬럼은 JSON 배열 문자열)
+pub fn participants_of(room: &Model) -> Vec<String> {
+    serde_json::from_str(&room.participants).unwrap_or_default()
+}
+
+/// username이 참가 중인 방. 방마다의 권한 확인에 같이 쓴다.
+pub async fn member_room<C: ConnectionTrait>(db: &C, room_id: i32, username: &str) -> Result<Model, String> {
+    match RoomEntity::find_by_id(room_id).one(db).await {
+        Ok(Some(room)) if participants_of(&room).iter().any(|p| p == username) => Ok(room),
+        Ok(Some(_)) => Err("참여 중인 방이 아닙니다.".to_string()),
+        Ok(None) => Err("존재하지 않는 방입니다.".to_string()),
+        Err(e) => Err(format!("DB 오류: {}", e)),
+    }
+}
+
+/// username이 참가 중인 방 목록
+pub async fn rooms_of(db: &DatabaseConnection, username: &str) -> Result<Vec<Model>, sea_orm::DbErr> {
+    // LIKE로 후보를 줄인 뒤 JSON을 풀어 정확히 비교
+    let rooms = RoomEntity::find()
+        .filter(crate::entities::room::Column::Participants.contains(username))
+        .all(db)
+        .await?;
+    Ok(rooms
+        .into_iter()
+        .filter(|room| participants_of(room).iter().any(|p| p == username))
+        .collect())
+}
+
 pub async fn create_room(
     State(db): State<DatabaseConnection>,
     Json(new_room): Json<NewRoom>,
@@ -57,6 +97,7 @@ pub async fn create_room(
     
     let room = ActiveModel {
         participants: Set(participants),
+        name: Set(new_room.name.clone()),
         ..Default::default()
     };
 
@@ -100,6 +141,7 @@ pub async fn find_or_create_room(
     // Create new room if not found
     let room = ActiveModel {
         participants: Set(key),
+        name: Set(room.name.clone()),
         ..Default::default()
     };
     
@@ -111,10 +153,11 @@ pub async fn find_or_create_room(
 
 pub async fn put_room(
     State(db): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
     Json(room): Json<NewRoom>,
 ) -> Result<Json<Model>, StatusCode> {
     if let Some(id) = room.id {
-        update_room(Path(id), State(db), Json(room)).await
+        update_room(Path(id), State(db), State(queue), Json(room)).await
     } else {
         create_room(State(db), Json(room)).await
     }
@@ -150,7 +193,8 @@ pub async fn get_room(
         let participants: Vec<String> = serde_json::from_str(&room.participants).unwrap_or_default();
         resp.push(NewRoom { 
             id: Some(room.id), 
-            participants 
+            participants,
+            name: room.name,
         });
     }
     
@@ -171,6 +215,7 @@ pub async fn get_room_by_id(
 pub async fn update_room(
     Path(id): Path<i32>,
     State(db): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
     Json(room_data): Json<NewRoom>,
 ) -> Result<Json<Model>, StatusCode> {
     let room = match RoomEntity::find_by_id(id).one(&db).await {
@@ -182,13 +227,27 @@ pub async fn update_room(
     let mut parts = room_data.participants.clone();
     parts.sort();
     parts.dedup();
+    let before = participants_of(&room);
+    let joined: Vec<String> = parts.iter().filter(|p| !before.contains(p)).cloned().collect();
+    let left: Vec<String> = before.into_iter().filter(|p| !parts.contains(p)).collect();
     let participants = serde_json::to_string(&parts).unwrap();
 
     let mut room: ActiveModel = room.into();
     room.participants = ActiveValue::Set(participants);
+    if room_data.name.is_some() {
+        room.name = ActiveValue::Set(room_data.name.clone());
+    }
 
     match room.update(&db).await {
-        Ok(model) => Ok(Json(model)),
+        Ok(model) => {
+            for username in joined {
+                outgoing::publish(&db, &queue, ChatEvent::MemberJoined { room_id: model.id, username }).await;
+            }
+            for username in left {
+                outgoing::publish(&db, &queue, ChatEvent::MemberLeft { room_id: model.id, username }).await;
+            }
+            Ok(Json(model))
+        }
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -237,6 +296,7 @@ pub async fn list_rooms_with_unread(
         Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
     };
 
+    let mut drafts = draft::load_for_user(&db, &username).await;
     let mut rooms_with_unread = Vec::new();
 
     for room in rooms {
@@ -251,6 +311,12 @@ pub async fn list_rooms_with_unread(
             .filter(room_read::Column::Username.eq(&username))
             .one(&db)
             .await;
+        let muted_until = last_read_result
+            .as_ref()
+            .ok()
+            .and_then(|r| r.as_ref())
+            .and_then(|r| r.muted_until)
+            .filter(|until| *until > chrono::Utc::now());
 
         let unread_count = match last_read_result {
             Ok(Some(last_read)) => {
@@ -258,18 +324,19 @@ pub async fn list_rooms_with_unread(
                     // Count messages after the last read ID
                     let query = ChatEntity::find()
                         .filter(ChatCol::RoomId.eq(room.id))
-                        .filter(ChatCol::Id.gt(lid));
+                        .filter(ChatCol::Id.gt(lid))
+                        .filter(retention::not_expired());
                     
                     query.count(&db).await.unwrap_or(0) as i64
                 } else {
                     // No last read ID, count all messages
-                    let query = ChatEntity::find().filter(ChatCol::RoomId.eq(room.id));
+                    let query = ChatEntity::find().filter(ChatCol::RoomId.eq(room.id)).filter(retention::not_expired());
                     query.count(&db).await.unwrap_or(0) as i64
                 }
             }
             Ok(None) => {
                 // No record for this user in this room, count all messages
-                let query = ChatEntity::find().filter(ChatCol::RoomId.eq(room.id));
+                let query = ChatEntity::find().filter(ChatCol::RoomId.eq(room.id)).filter(retention::not_expired());
                 query.count(&db).await.unwrap_or(0) as i64
             }
             Err(_) => {
@@ -282,6 +349,8 @@ pub async fn list_rooms_with_unread(
             id: room.id,
             participants,
             unread_count,
+            draft: drafts.remove(&room.id),
+            muted_until,
         });
     }
 
@@ -290,6 +359,7 @@ pub async fn list_rooms_with_unread(
 
 pub async fn mark_read(
     State(db): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
     Path(room_id): Path<i32>,
     Json(read_data): Json<ReadUpdate>,
 ) -> Result<Json<LastRead>, (StatusCode, String)> {
@@ -311,6 +381,7 @@ pub async fn mark_read(
     }
 
     // 기존 record가 있는지 확인
+    let mut last_read_id = read_data.last_read_id;
     match room_read::Entity::find()
         .filter(room_read::Column::RoomId.eq(room_id))
         .filter(room_read::Column::Username.eq(&read_data.username))
@@ -319,9 +390,12 @@ pub async fn mark_read(
     {
         Ok(Some(existing)) => {
             println!("Updating existing room_read record with id: {}", existing.id);
-            // 기존 record 업데이트
+            // 기존 record 업데이트 (늦게 도착한 요청이 읽은 위치를 되돌리지 않도록)
+            if let (Some(prev), Some(next)) = (existing.last_read_id, last_read_id) {
+                last_read_id = Some(prev.max(next));
+            }
             let mut active_model: room_read::ActiveModel = existing.into();
-            active_model.last_read_id = Set(read_data.last_read_id);
+            active_model.last_read_id = Set(last_read_id);
             active_model.updated_at = Set(chrono::Utc::now());
             
             active_model.update(&db).await
@@ -339,6 +413,7 @@ pub async fn mark_read(
                 username: Set(read_data.username.clone()),
                 last_read_id: Set(read_data.last_read_id),
                 updated_at: Set(chrono::Utc::now()),
+                muted_until: NotSet,
             };
             
             new_record.insert(&db).await
@@ -354,7 +429,10 @@ pub async fn mark_read(
     };
 
     println!("Successfully updated room_read for room: {}, user: {}", room_id, read_data.username);
-    Ok(Json(LastRead {
-        last_read_id: read_data.last_read_id,
-    }))
+    let _ = queue.send(ChatEvent::Read {
+        room_id,
+        username: read_data.username.clone(),
+        last_read_id,
+    });
+    Ok(Json(LastRead { last_read_id }))
 }
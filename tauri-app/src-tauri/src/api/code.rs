@@ -0,0 +1,177 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Json,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseConnection, TransactionTrait};
+use serde::Deserialize;
+use tokio::sync::broadcast;
+
+use crate::content::{MessageContent, CODE_PREVIEW_BYTES, CODE_PREVIEW_LINES};
+use crate::entities::attachment::ActiveModel as ActiveAttachment;
+use crate::highlight;
+use crate::media::store;
+use crate::validation::AttachmentMeta;
+
+use super::bot;
+use super::chat::{insert_message, validate, NewMessage, SendResponse};
+use super::event::ChatEvent;
+use super::outgoing;
+use super::validation;
+
+/// 코드 조각 하나의 최대 크기 (일반 메시지 500바이트보다 훨씬 크게)
+const MAX_CODE_BYTES: usize = 200 * 1024;
+const CODE_MIME: &str = "text/plain; charset=utf-8";
+
+#[derive(Deserialize)]
+pub struct NewCode {
+    pub sender: String,
+    pub room_id: i32,
+    #[serde(default)]
+    pub language: Option<String>,
+    pub code: String,
+}
+
+fn fail(error: impl ToString) -> Json<SendResponse> {
+    Json(SendResponse { success: 0, error: Some(error.to_string()), chat: None, ..Default::default() })
+}
+
+/// 본문에 실을 앞부분. 줄 단위로 자르고, 한 줄이 한도를 넘으면 글자 경계에서 자른다.
+fn preview(code: &str) -> (&str, bool) {
+    let mut end = 0;
+    for (i, line) in code.split_inclusive('\n').enumerate() {
+        if i == CODE_PREVIEW_LINES || end + line.len() > CODE_PREVIEW_BYTES {
+            break;
+        }
+        end += line.len();
+    }
+    if end == 0 && !code.is_empty() {
+        end = CODE_PREVIEW_BYTES.min(code.len());
+        while !code.is_char_boundary(end) {
+            end -= 1;
+        }
+    }
+    (&code[..end], end < code.len())
+}
+
+/// POST /chat/code
+///
+/// 길이가 미리보기 한도를 넘으면 전체 코드를 첨부(kind: code)로 저장하고 본문에는 앞부분만 싣는다.
+pub async fn send_code(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    headers: HeaderMap,
+    Json(payload): Json<NewCode>,
+) -> Json<SendResponse> {
+    if payload.code.len() > MAX_CODE_BYTES {
+        return fail(format!("코드는 {}KB 이내여야 합니다.", MAX_CODE_BYTES / 1024));
+    }
+    let language = payload.language.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    if let Some(l) = language.as_deref() {
+        if !highlight::is_known(l) {
+            return fail(format!("'{}' 언어는 강조를 지원하지 않습니다. 언어를 비워 두면 일반 텍스트로 보냅니다.", l));
+        }
+    }
+
+    let (head, truncated) = preview(&payload.code);
+    let new_message = NewMessage {
+        sender: payload.sender,
+        room_id: payload.room_id,
+        content: MessageContent::Code {
+            language,
+            code: head.to_string(),
+            line_count: payload.code.lines().count() as i64,
+            truncated,
+            html: None,
+        },
+        client_msg_id: None,
+    };
+    if let Err(e) = validate(&new_message) {
+        return fail(e);
+    }
+    // 봇 계정으로 보내려면 그 봇의 API 토큰이 있어야 한다
+    if let Err(e) = bot::authorize_sender(&conn, &headers, &new_message.sender).await {
+        return fail(e);
+    }
+    let attachments: Vec<AttachmentMeta> = truncated
+        .then(|| AttachmentMeta { kind: "code".to_string(), size: payload.code.len() as u64 })
+        .into_iter()
+        .collect();
+    if let Err(rejection) = validation::check(&conn, &new_message, &attachments).await {
+        return Json(SendResponse::rejected(rejection));
+    }
+
+    let storage_key = if truncated {
+        match store::save(payload.code.as_bytes(), "txt").await {
+            Ok(key) => Some(key),
+            Err(_) => return fail("코드 파일 저장에 실패했습니다."),
+        }
+    } else {
+        None
+    };
+
+    // 전체 코드를 받을 수 없는 잘린 메시지가 남지 않도록 메시지와 첨부를 한 트랜잭션에서 저장
+    let created = async {
+        let txn = conn.begin().await.map_err(|e| format!("DB 오류: {}", e))?;
+        let chat = insert_message(&txn, &new_message).await?;
+        if let Some(storage_key) = &storage_key {
+            ActiveAttachment {
+                id: ActiveValue::NotSet,
+                chat_id: ActiveValue::Set(chat.id),
+                kind: ActiveValue::Set("code".to_string()),
+                mime: ActiveValue::Set(CODE_MIME.to_string()),
+                storage_key: ActiveValue::Set(storage_key.clone()),
+                size: ActiveValue::Set(payload.code.len() as i64),
+                duration_ms: ActiveValue::Set(None),
+                waveform: ActiveValue::Set(None),
+                created_at: ActiveValue::Set(chrono::Utc::now().naive_utc()),
+                view_once: ActiveValue::Set(false),
+                viewed_at: ActiveValue::Set(None),
+                viewed_by: ActiveValue::Set(None),
+            }
+            .insert(&txn)
+            .await
+            .map_err(|_| "코드 메시지 저장에 실패했습니다.".to_string())?;
+        }
+        txn.commit().await.map_err(|e| format!("DB 오류: {}", e))?;
+        Ok::<_, String>(chat)
+    }
+    .await;
+    let chat = match created {
+        Ok(chat) => chat,
+        Err(e) => {
+            if let Some(key) = &storage_key {
+                let _ = store::remove(key).await;
+            }
+            return fail(e);
+        }
+    };
+
+    outgoing::publish(&conn, &queue, ChatEvent::Message(chat.clone())).await;
+    Json(SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() })
+}
+
+/// GET /chat/code/style.css — 강조 HTML의 hl-* class에 맞춘 색
+pub async fn stylesheet() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/css; charset=utf-8")], highlight::stylesheet())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_cuts_on_lines_and_bytes() {
+        assert_eq!(preview("a\nb\n"), ("a\nb\n", false));
+
+        let many: String = (0..50).map(|i| format!("line {}\n", i)).collect();
+        let (head, truncated) = preview(&many);
+        assert!(truncated);
+        assert_eq!(head.lines().count(), CODE_PREVIEW_LINES);
+
+        let wide = "가".repeat(CODE_PREVIEW_BYTES);
+        let (head, truncated) = preview(&wide);
+        assert!(truncated && head.len() <= CODE_PREVIEW_BYTES && head.chars().all(|c| c == '가'));
+    }
+}
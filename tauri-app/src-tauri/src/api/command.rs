@@ -0,0 +1,228 @@
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{TimeZone, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::command::{self, Action, CommandInfo, Invocation, MuteSpan};
+use crate::content::MessageContent;
+use crate::entities::{
+    room::{ActiveModel as ActiveRoom, Model as Room},
+    room_read::{ActiveModel as ActiveRoomRead, Column as RoomReadCol, Entity as RoomReadEntity},
+};
+
+use super::chat::{already_sent, insert_message, post_message, NewMessage, SendResponse};
+use super::chat_room::member_room;
+use super::draft;
+use super::event::ChatEvent;
+use super::outgoing;
+use super::poll::{self, NewPoll};
+use super::schedule::{self, NewSchedule};
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+#[derive(Deserialize)]
+pub struct CompleteQuery {
+    pub room_id: i32,
+    pub username: String,
+    /// 입력 중인 명령어 이름 (`/re` 또는 `re`). 없으면 전체 목록.
+    #[serde(default)]
+    pub q: String,
+}
+
+fn fail(error: impl ToString) -> SendResponse {
+    SendResponse { success: 0, error: Some(error.to_string()), chat: None, ..Default::default() }
+}
+
+/// 실행한 사람에게만 보이는 답. SSE로도 보내서 다른 기기에도 뜨게 한다.
+fn reply(queue: &broadcast::Sender<ChatEvent>, new_message: &NewMessage, text: String) -> SendResponse {
+    let _ = queue.send(ChatEvent::Ephemeral {
+        room_id: new_message.room_id,
+        username: new_message.sender.clone(),
+        text: text.clone(),
+    });
+    SendResponse { success: 1, error: None, chat: None, ephemeral: Some(text), ..Default::default() }
+}
+
+/// `/이름 인자` 메시지를 실행한다. 사용법이 틀리거나 모르는 명령어면 실패 대신 안내를 답한다.
+pub async fn execute(
+    conn: &DatabaseConnection,
+    queue: &broadcast::Sender<ChatEvent>,
+    headers: &HeaderMap,
+    new_message: &NewMessage,
+    name: &str,
+    args: &str,
+) -> SendResponse {
+    let room = match member_room(conn, new_message.room_id, &new_message.sender).await {
+        Ok(room) => room,
+        Err(e) => return fail(e),
+    };
+    // 재전송이면 처음 실행에서 남긴 메시지(본문, 투표, 주제 변경 안내)를 그대로 돌려준다.
+    // 예약은 메시지가 아니라 예약 행에 같은 id를 남기고 schedule::create가 걸러낸다.
+    match already_sent(conn, new_message).await {
+        Ok(Some(chat)) => return SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() },
+        Ok(None) => {}
+        Err(e) => return fail(e),
+    }
+    let found = command::registry().find(name).filter(|c| c.available(&room));
+    let Some(found) = found else {
+        return reply(queue, new_message, format!("/{}: 알 수 없는 명령어입니다. 메시지로 보내려면 //{}처럼 쓰세요.", name, name));
+    };
+    let invocation = Invocation { username: &new_message.sender, room: &room, args };
+    let action = match found.run(&invocation) {
+        Ok(action) => action,
+        Err(e) => return reply(queue, new_message, e),
+    };
+
+    let response = match action {
+        // 일반 메시지와 같은 길로 보내므로 검증, 재전송 처리, 임시 저장 삭제도 그대로 적용된다
+        Action::Post(content) => {
+            let message = NewMessage {
+                sender: new_message.sender.clone(),
+                room_id: new_message.room_id,
+                content,
+                client_msg_id: new_message.client_msg_id.clone(),
+            };
+            return post_message(conn, queue, &message).await;
+        }
+        Action::Reply(text) => reply(queue, new_message, text),
+        Action::Poll { question, options } => {
+            let poll = NewPoll {
+                sender: new_message.sender.clone(),
+                room_id: new_message.room_id,
+                question,
+                options,
+                multiple: false,
+                anonymous: false,
+                closes_at: None,
+                client_msg_id: new_message.client_msg_id.clone(),
+            };
+            poll::create_poll(State(conn.clone()), State(queue.clone()), headers.clone(), Json(poll)).await.0
+        }
+        Action::Remind { after, text } => {
+            let Some(send_at) = Utc::now().checked_add_signed(after) else {
+                return fail("알림 시각을 계산할 수 없습니다.");
+            };
+            let schedule = NewSchedule {
+                sender: new_message.sender.clone(),
+                room_id: new_message.room_id,
+                message: format!("⏰ {}", text),
+                send_at: send_at.fixed_offset(),
+                client_msg_id: new_message.client_msg_id.clone(),
+            };
+            let created = schedule::create(State(conn.clone()), headers.clone(), Json(schedule)).await.0;
+            // 재전송이면 처음 예약이 돌아오므로 그 시각으로 답한다
+            match (created.error, created.data) {
+                (Some(e), _) => reply(queue, new_message, e),
+                (None, saved) => {
+                    let send_at = saved.map(|s| s.send_at.and_utc()).unwrap_or(send_at);
+                    reply(queue, new_message, format!("{}에 알려드릴게요: {}", send_at.format("%Y-%m-%d %H:%M UTC"), text))
+                }
+            }
+        }
+        Action::Mute(span) => match set_mute(conn, &new_message.sender, room.id, span).await {
+            Ok(text) => reply(queue, new_message, text),
+            Err(e) => fail(e),
+        },
+        Action::Topic(topic) => match set_topic(conn, new_message, room, topic).await {
+            Ok(notice) => {
+                outgoing::publish(conn, queue, ChatEvent::Message(notice.clone())).await;
+                SendResponse { success: 1, error: None, chat: Some(notice), ..Default::default() }
+            }
+            Err(e) => fail(e),
+        },
+    };
+    // 명령어도 보낸 것으로 보고 입력창 임시 저장을 지운다
+    if response.success == 1 {
+        let _ = draft::clear(conn, &new_message.sender, new_message.room_id).await;
+    }
+    response
+}
+
+/// 내 room_read 행에 알림 끄기 기한을 적는다 (없으면 만든다)
+async fn set_mute(conn: &DatabaseConnection, username: &str, room_id: i32, span: MuteSpan) -> Result<String, String> {
+    let now = Utc::now();
+    let (until, text) = match span {
+        MuteSpan::For(duration) => {
+            let until = now.checked_add_signed(duration).ok_or("알림을 끌 기간이 너무 깁니다.")?;
+            (Some(until), format!("{}까지 이 방 알림을 끕니다.", until.format("%Y-%m-%d %H:%M UTC")))
+        }
+        // 기한 없는 끄기는 먼 미래로 저장해서 조회 쪽이 한 가지 비교만 하게 한다
+        MuteSpan::Forever => (Utc.with_ymd_and_hms(9999, 12, 31, 0, 0, 0).single(), "이 방 알림을 끕니다.".to_string()),
+        MuteSpan::Off => (None, "이 방 알림을 다시 켭니다.".to_string()),
+    };
+    let existing = RoomReadEntity::find()
+        .filter(RoomReadCol::RoomId.eq(room_id))
+        .filter(RoomReadCol::Username.eq(username))
+        .one(conn)
+        .await
+        .map_err(|e| format!("DB 오류: {}", e))?;
+    let saved = match existing {
+        Some(row) => {
+            let mut row: ActiveRoomRead = row.into();
+            row.muted_until = ActiveValue::Set(until);
+            row.update(conn).await.map(|_| ())
+        }
+        None => ActiveRoomRead {
+            id: ActiveValue::NotSet,
+            room_id: ActiveValue::Set(room_id),
+            username: ActiveValue::Set(username.to_string()),
+            last_read_id: ActiveValue::Set(None),
+            updated_at: ActiveValue::Set(now),
+            muted_until: ActiveValue::Set(until),
+        }
+        .insert(conn)
+        .await
+        .map(|_| ()),
+    };
+    saved.map(|_| text).map_err(|e| format!("DB 오류: {}", e))
+}
+
+/// 방 주제를 바꾸고 모두에게 보이는 시스템 메시지를 남긴다
+async fn set_topic(
+    conn: &DatabaseConnection,
+    new_message: &NewMessage,
+    room: Room,
+    topic: Option<String>,
+) -> Result<crate::entities::chat::Model, String> {
+    let username = &new_message.sender;
+    let text = match &topic {
+        Some(topic) => format!("{}님이 주제를 '{}'(으)로 바꿨습니다.", username, topic),
+        None => format!("{}님이 주제를 지웠습니다.", username),
+    };
+    let notice = NewMessage {
+        sender: username.to_string(),
+        room_id: room.id,
+        content: MessageContent::System { text },
+        client_msg_id: new_message.client_msg_id.clone(),
+    };
+    let txn = conn.begin().await.map_err(|e| format!("DB 오류: {}", e))?;
+    let mut room: ActiveRoom = room.into();
+    room.topic = ActiveValue::Set(topic);
+    room.update(&txn).await.map_err(|e| format!("DB 오류: {}", e))?;
+    let chat = insert_message(&txn, &notice).await?;
+    txn.commit().await.map_err(|e| format!("DB 오류: {}", e))?;
+    Ok(chat)
+}
+
+/// GET /chat/commands?room_id=&username=&q=: 입력창 자동완성용 명령어 목록
+pub async fn autocomplete(
+    State(conn): State<DatabaseConnection>,
+    Query(query): Query<CompleteQuery>,
+) -> Json<ApiResponse<Vec<CommandInfo>>> {
+    match member_room(&conn, query.room_id, &query.username).await {
+        Ok(room) => Json(ApiResponse { success: 1, error: None, data: Some(command::registry().complete(&room, &query.q)) }),
+        Err(e) => Json(ApiResponse { success: 0, error: Some(e), data: None }),
+    }
+}
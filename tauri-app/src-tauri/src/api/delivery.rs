@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, Json};
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::entities::{
+    chat::{Column as ChatCol, Entity as ChatEntity},
+    chat_delivery::{ActiveModel, Column, Entity as DeliveryEntity},
+    room::{Column as RoomCol, Entity as RoomEntity},
+};
+
+use super::chat_room::participants_of;
+use super::event::ChatEvent;
+
+// 한 번에 ack할 수 있는 메시지 수
+const MAX_ACK_BATCH: usize = 500;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+#[derive(Deserialize)]
+pub struct DeliveryAck {
+    pub username: String,
+    pub chat_ids: Vec<i32>,
+}
+
+/// 보낸 사람 입장에서 본 메시지 상태
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Sent,
+    Delivered,
+    Read,
+}
+
+/// 다른 참가자 전원이 읽었으면 read, 전원에게 도착했으면 delivered
+pub fn status(recipients: usize, delivered: usize, read: usize) -> DeliveryStatus {
+    if recipients > 0 && read >= recipients {
+        DeliveryStatus::Read
+    } else if recipients > 0 && delivered >= recipients {
+        DeliveryStatus::Delivered
+    } else {
+        DeliveryStatus::Sent
+    }
+}
+
+/// 메시지별로 도착이 확인된 수신자 목록
+pub async fn load_for_chats(conn: &DatabaseConnection, chat_ids: &[i32]) -> HashMap<i32, Vec<String>> {
+    let mut map: HashMap<i32, Vec<String>> = HashMap::new();
+    if chat_ids.is_empty() {
+        return map;
+    }
+    let rows = DeliveryEntity::find()
+        .filter(Column::ChatId.is_in(chat_ids.to_vec()))
+        .all(conn)
+        .await
+        .unwrap_or_default();
+    for row in rows {
+        map.entry(row.chat_id).or_default().push(row.username);
+    }
+    map
+}
+
+/// POST /chat/ack: 클라이언트가 SSE로 받은 메시지의 도착을 알린다. 새로 기록된 id를 돌려준다.
+pub async fn ack(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    Json(ack): Json<DeliveryAck>,
+) -> Json<ApiResponse<Vec<i32>>> {
+    if ack.username.trim().is_empty() || ack.chat_ids.is_empty() {
+        return Json(ApiResponse { success: 0, error: Some("username과 chat_ids가 필요합니다.".to_string()), data: None });
+    }
+    if ack.chat_ids.len() > MAX_ACK_BATCH {
+        return Json(ApiResponse { success: 0, error: Some(format!("한 번에 {}개까지 확인할 수 있습니다.", MAX_ACK_BATCH)), data: None });
+    }
+
+    let chats = match ChatEntity::find().filter(ChatCol::Id.is_in(ack.chat_ids.clone())).all(&conn).await {
+        Ok(chats) => chats,
+        Err(e) => return Json(ApiResponse { success: 0, error: Some(format!("DB 오류: {}", e)), data: None }),
+    };
+    let room_ids: Vec<i32> = chats.iter().map(|c| c.room_id).collect();
+    let participants: HashMap<i32, Vec<String>> = RoomEntity::find()
+        .filter(RoomCol::Id.is_in(room_ids))
+        .all(&conn)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|room| (room.id, participants_of(room)))
+        .collect();
+
+    let mut recorded = Vec::new();
+    for chat in chats {
+        // 내 메시지이거나 내가 속하지 않은 방의 메시지는 무시
+        let is_member = participants.get(&chat.room_id).is_some_and(|ps| ps.contains(&ack.username));
+        if chat.sender == ack.username || !is_member {
+            continue;
+        }
+        let delivered_at = chrono::Utc::now().naive_utc();
+        let row = ActiveModel {
+            id: ActiveValue::NotSet,
+            chat_id: ActiveValue::Set(chat.id),
+            username: ActiveValue::Set(ack.username.clone()),
+            delivered_at: ActiveValue::Set(delivered_at),
+        };
+        let inserted = DeliveryEntity::insert(row)
+            .on_conflict(OnConflict::columns([Column::ChatId, Column::Username]).do_nothing().to_owned())
+            .exec(&conn)
+            .await;
+        match inserted {
+            Ok(_) => {
+                recorded.push(chat.id);
+                let _ = queue.send(ChatEvent::Delivered {
+                    room_id: chat.room_id,
+                    chat_id: chat.id,
+                    username: ack.username.clone(),
+                    delivered_at,
+                });
+            }
+            // 이미 기록된 ack
+            Err(DbErr::RecordNotInserted) => {}
+            Err(e) => return Json(ApiResponse { success: 0, error: Some(format!("DB 오류: {}", e)), data: None }),
+        }
+    }
+    Json(ApiResponse { success: 1, error: None, data: Some(recorded) })
+}
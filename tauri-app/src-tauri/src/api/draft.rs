@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::draft::{ActiveModel, Column, Entity as DraftEntity, Model as Draft};
+
+use super::chat_room::member_room;
+
+/// 임시 저장할 수 있는 최대 길이 (보낼 때는 메시지 규칙이 다시 적용된다)
+const MAX_DRAFT_BYTES: usize = 10_000;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+fn fail<T>(error: impl Into<String>) -> Json<ApiResponse<T>> {
+    Json(ApiResponse { success: 0, error: Some(error.into()), data: None })
+}
+
+/// 클라이언트는 입력이 멈춘 뒤(디바운스) 한 번씩 보낸다. 빈 text는 임시 저장을 지운다.
+#[derive(Deserialize)]
+pub struct DraftUpdate {
+    pub username: String,
+    pub text: String,
+    /// 입력을 고친 시각. 이보다 나중에 저장되거나 지워진 적이 있으면 늦게 도착한 요청으로 보고 무시한다.
+    /// 없으면 받은 시각.
+    #[serde(default)]
+    pub edited_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DraftView {
+    pub text: String,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl From<Draft> for DraftView {
+    fn from(d: Draft) -> Self {
+        DraftView { text: d.text, updated_at: d.updated_at }
+    }
+}
+
+/// PUT /room/{id}/draft
+pub async fn save_draft(
+    State(conn): State<DatabaseConnection>,
+    Path(room_id): Path<i32>,
+    Json(req): Json<DraftUpdate>,
+) -> Json<ApiResponse<DraftView>> {
+    let username = req.username.trim();
+    if username.is_empty() {
+        return fail("username이 필요합니다.");
+    }
+    if req.text.len() > MAX_DRAFT_BYTES {
+        return fail(format!("임시 저장은 {}바이트 이내여야 합니다.", MAX_DRAFT_BYTES));
+    }
+    if let Err(e) = member_room(&conn, room_id, username).await {
+        return fail(e);
+    }
+
+    // 미래 시각으로 다른 저장을 모두 막지 못하게 받은 시각을 넘지 않게 한다
+    let now = chrono::Utc::now().naive_utc();
+    let edited_at = req.edited_at.map(|t| t.naive_utc().min(now)).unwrap_or(now);
+    let text = if req.text.trim().is_empty() { String::new() } else { req.text };
+    let saved = DraftEntity::insert(row(username, room_id, text, edited_at))
+        .on_conflict(
+            OnConflict::columns([Column::Username, Column::RoomId])
+                .update_columns([Column::Text, Column::UpdatedAt])
+                .action_and_where(Expr::col((DraftEntity, Column::UpdatedAt)).lt(Expr::cust("excluded.updated_at")))
+                .to_owned(),
+        )
+        .exec_with_returning(&conn)
+        .await;
+    match saved {
+        Ok(draft) => Json(ApiResponse { success: 1, error: None, data: visible(draft) }),
+        // 더 최근의 저장(또는 전송으로 지운 기록)이 있어 바꾸지 않았다
+        Err(DbErr::RecordNotInserted | DbErr::RecordNotFound(_)) => Json(ApiResponse { success: 1, error: None, data: None }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+fn row(username: &str, room_id: i32, text: String, updated_at: chrono::NaiveDateTime) -> ActiveModel {
+    ActiveModel {
+        id: ActiveValue::NotSet,
+        username: ActiveValue::Set(username.to_string()),
+        room_id: ActiveValue::Set(room_id),
+        text: ActiveValue::Set(text),
+        updated_at: ActiveValue::Set(updated_at),
+    }
+}
+
+/// 지운 임시 저장은 빈 text로 남아 있다 (늦게 도착한 저장 요청을 걸러내는 기준 시각)
+fn visible(draft: Draft) -> Option<DraftView> {
+    (!draft.text.is_empty()).then(|| draft.into())
+}
+
+/// GET /room/{id}/draft?username=
+pub async fn get_draft(
+    State(conn): State<DatabaseConnection>,
+    Path(room_id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<DraftView>> {
+    let username = params.get("username").map(|u| u.trim()).unwrap_or_default();
+    if username.is_empty() {
+        return fail("username이 필요합니다.");
+    }
+    let found = DraftEntity::find()
+        .filter(Column::Username.eq(username))
+        .filter(Column::RoomId.eq(room_id))
+        .one(&conn)
+        .await;
+    match found {
+        Ok(draft) => Json(ApiResponse { success: 1, error: None, data: draft.and_then(visible) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// 임시 저장을 지운다. 메시지 전송과 같은 트랜잭션에서 호출해 둘이 함께 반영되게 한다.
+/// 행은 빈 text와 지운 시각으로 남겨, 전송 전에 입력한 내용의 저장 요청이 늦게 와도 되살아나지 않게 한다.
+pub async fn clear<C: ConnectionTrait>(conn: &C, username: &str, room_id: i32) -> Result<(), DbErr> {
+    DraftEntity::insert(row(username, room_id, String::new(), chrono::Utc::now().naive_utc()))
+        .on_conflict(
+            OnConflict::columns([Column::Username, Column::RoomId])
+                .update_columns([Column::Text, Column::UpdatedAt])
+                .to_owned(),
+        )
+        .exec_without_returning(conn)
+        .await?;
+    Ok(())
+}
+
+/// 방 목록에 붙일 사용자의 임시 저장 (방 id별)
+pub async fn load_for_user(conn: &DatabaseConnection, username: &str) -> HashMap<i32, DraftView> {
+    DraftEntity::find()
+        .filter(Column::Username.eq(username))
+        .filter(Column::Text.ne(""))
+        .all(conn)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|d| (d.room_id, d.into()))
+        .collect()
+}
@@ -0,0 +1,145 @@
+use serde_json::{json, Value};
+
+use crate::entities::{chat::Model as Chat, link_preview::Model as LinkPreview};
+
+use super::poll::PollView;
+
+/// 브로드캐스트 채널이 쌓아 둘 수 있는 이벤트 수. 입력 중·읽음·전달 이벤트가 몰려도 구독자가 밀리지 않을 만큼.
+pub const QUEUE_CAPACITY: usize = 4096;
+
+/// 브로드캐스트 채널로 흘려보내는 실시간 이벤트. SSE의 event 이름은 `name()`을 따른다.
+#[derive(Clone, Debug)]
+pub enum ChatEvent {
+    Message(Chat),
+    LinkPreview { room_id: i32, chat_id: i32, preview: LinkPreview },
+    /// 누군가 읽은 위치를 옮김 (열린 채팅창의 안 읽음 숫자 갱신용)
+    Read { room_id: i32, username: String, last_read_id: Option<i32> },
+    /// 수신자 기기에 메시지가 도착함 (보낸 사람의 전송 상태 갱신용)
+    Delivered { room_id: i32, chat_id: i32, username: String, delivered_at: chrono::NaiveDateTime },
+    /// 입력 중 표시. 저장하지 않으며 클라이언트는 ttl_ms가 지나면 지운다.
+    Typing { room_id: i32, username: String, active: bool, ttl_ms: u64 },
+    /// 메시지가 삭제됨 (보관 기간 만료 등)
+    Deleted { room_id: i32, chat_ids: Vec<i32> },
+    /// 한 번 보기 첨부를 수신자가 열었음 (보낸 사람에게 알림)
+    Viewed { room_id: i32, chat_id: i32, attachment_id: i32, username: String, viewed_at: chrono::NaiveDateTime },
+    /// 투표 집계가 바뀜 (익명 여부에 맞춰 투표자는 숨겨져 있음)
+    Poll { room_id: i32, poll: PollView },
+    /// 명령어에 대한 답 등 한 사람에게만 보이는 안내 (username으로 구독한 연결에만 간다)
+    Ephemeral { room_id: i32, username: String, text: String },
+    /// 방에 새 참가자가 들어옴
+    MemberJoined { room_id: i32, username: String },
+    /// 참가자가 방에서 빠짐
+    MemberLeft { room_id: i32, username: String },
+}
+
+impl ChatEvent {
+    pub fn room_id(&self) -> i32 {
+        match self {
+            ChatEvent::Message(chat) => chat.room_id,
+            ChatEvent::LinkPreview { room_id, .. } => *room_id,
+            ChatEvent::Read { room_id, .. } => *room_id,
+            ChatEvent::Delivered { room_id, .. } => *room_id,
+            ChatEvent::Typing { room_id, .. } => *room_id,
+            ChatEvent::Deleted { room_id, .. } => *room_id,
+            ChatEvent::Viewed { room_id, .. } => *room_id,
+            ChatEvent::Poll { room_id, .. } => *room_id,
+            ChatEvent::Ephemeral { room_id, .. } => *room_id,
+            ChatEvent::MemberJoined { room_id, .. } => *room_id,
+            ChatEvent::MemberLeft { room_id, .. } => *room_id,
+        }
+    }
+
+    /// 특정 사용자에게만 보내야 하는 이벤트면 그 사용자
+    pub fn recipient(&self) -> Option<&str> {
+        match self {
+            ChatEvent::Ephemeral { username, .. } => Some(username),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChatEvent::Message(_) => "message",
+            ChatEvent::LinkPreview { .. } => "link_preview",
+            ChatEvent::Read { .. } => "read",
+            ChatEvent::Delivered { .. } => "delivered",
+            ChatEvent::Typing { .. } => "typing",
+            ChatEvent::Deleted { .. } => "deleted",
+            ChatEvent::Viewed { .. } => "viewed",
+            ChatEvent::Poll { .. } => "poll",
+            ChatEvent::Ephemeral { .. } => "ephemeral",
+            ChatEvent::MemberJoined { .. } => "member_joined",
+            ChatEvent::MemberLeft { .. } => "member_left",
+        }
+    }
+
+    pub fn data(&self) -> Value {
+        match self {
+            ChatEvent::Message(chat) => json!({
+                "id": chat.id,
+                "sender": chat.sender,
+                "message": chat.message,
+                "kind": chat.content.kind(),
+                "content": serde_json::to_value(&chat.content).ok().and_then(|mut v| v.get_mut("content").map(Value::take)),
+                "room_id": chat.room_id,
+                "timestamp": chat.timestamp,
+                "expires_at": chat.expires_at,
+                // 낙관적으로 먼저 그린 메시지를 찾아 바꿔 끼울 수 있게 돌려준다
+                "client_msg_id": chat.client_msg_id,
+                // 수신 webhook이 남긴 메시지 (sender는 연동 이름)
+                "webhook_id": chat.webhook_id,
+                "forwarded_from": chat.forwarded_sender.as_ref().map(|sender| json!({
+                    "chat_id": chat.forwarded_from_id,
+                    "sender": sender,
+                    "timestamp": chat.forwarded_at
+                }))
+            }),
+            ChatEvent::LinkPreview { room_id, chat_id, preview } => json!({
+                "room_id": room_id,
+                "chat_id": chat_id,
+                "preview": preview
+            }),
+            ChatEvent::Read { room_id, username, last_read_id } => json!({
+                "room_id": room_id,
+                "username": username,
+                "last_read_id": last_read_id
+            }),
+            ChatEvent::Delivered { room_id, chat_id, username, delivered_at } => json!({
+                "room_id": room_id,
+                "chat_id": chat_id,
+                "username": username,
+                "delivered_at": delivered_at
+            }),
+            ChatEvent::Typing { room_id, username, active, ttl_ms } => json!({
+                "room_id": room_id,
+                "username": username,
+                "active": active,
+                "ttl_ms": ttl_ms
+            }),
+            ChatEvent::Deleted { room_id, chat_ids } => json!({
+                "room_id": room_id,
+                "chat_ids": chat_ids
+            }),
+            ChatEvent::Viewed { room_id, chat_id, attachment_id, username, viewed_at } => json!({
+                "room_id": room_id,
+                "chat_id": chat_id,
+                "attachment_id": attachment_id,
+                "username": username,
+                "viewed_at": viewed_at
+            }),
+            ChatEvent::Poll { room_id, poll } => json!({
+                "room_id": room_id,
+                "poll": poll
+            }),
+            ChatEvent::Ephemeral { room_id, username, text } => json!({
+                "room_id": room_id,
+                "username": username,
+                "text": text
+            }),
+            ChatEvent::MemberJoined { room_id, username } | ChatEvent::MemberLeft { room_id, username } => json!({
+                "room_id": room_id,
+                "username": username
+            }),
+        }
+    }
+}
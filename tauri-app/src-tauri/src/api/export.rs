@@ -0,0 +1,604 @@
+//! 대화 내보내기: `GET /room/{id}/export?format=json|html|txt`.
+//!
+//! 기록을 페이지 단위로 읽어 바로 응답 본문으로 흘려보내므로 방이 커도 메모리에 전부 올리지 않는다.
+//! html은 index.html과 첨부 파일을 묶은 ZIP으로 내려준다. 한 번 보기 첨부는 내보내지 않는다.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{format::{Item, StrftimeItems}, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use sea_orm::{
+    sea_query::{Expr, Query as SeaQuery, SelectStatement},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use serde_json::json;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::content::{MessageContent, TextFormat};
+use crate::entities::{
+    attachment::{self, Entity as AttachmentEntity, Model as Attachment},
+    chat::{self, Entity as ChatEntity, Model as Chat},
+    room::{Entity as RoomEntity, Model as Room},
+    users::{self, Entity as UserEntity},
+};
+use crate::highlight;
+use crate::media::store;
+
+use super::chat_room::participants_of;
+use super::retention;
+
+/// 한 번에 읽는 메시지 수
+const PAGE: u64 = 500;
+const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const MAX_TIME_FORMAT_CHARS: usize = 64;
+/// 첨부 파일을 ZIP에 옮길 때 읽는 크기
+const FILE_CHUNK: usize = 64 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Html,
+    Txt,
+}
+
+impl Format {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "json" => Some(Format::Json),
+            "html" => Some(Format::Html),
+            "txt" => Some(Format::Txt),
+            _ => None,
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json; charset=utf-8",
+            Format::Html => "application/zip",
+            Format::Txt => "text/plain; charset=utf-8",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Html => "zip",
+            Format::Txt => "txt",
+        }
+    }
+}
+
+/// 시각 표시 방법. 시간대는 IANA 이름(Asia/Seoul 등), 형식은 strftime.
+#[derive(Clone, Debug)]
+pub struct TimeStyle {
+    pub tz: Tz,
+    pub format: String,
+}
+
+impl TimeStyle {
+    pub fn parse(tz: Option<&str>, format: Option<&str>) -> Result<Self, String> {
+        let tz = match tz.map(str::trim).filter(|t| !t.is_empty()) {
+            Some(name) => name.parse::<Tz>().map_err(|_| format!("알 수 없는 시간대 '{}'입니다. (예: Asia/Seoul, UTC)", name))?,
+            None => Tz::UTC,
+        };
+        let format = format.filter(|f| !f.is_empty()).unwrap_or(DEFAULT_TIME_FORMAT);
+        if format.chars().count() > MAX_TIME_FORMAT_CHARS || StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+            return Err(format!("시각 형식 '{}'을 쓸 수 없습니다. (예: {})", format, DEFAULT_TIME_FORMAT));
+        }
+        Ok(TimeStyle { tz, format: format.to_string() })
+    }
+
+    /// DB의 UTC 시각을 고른 시간대와 형식으로
+    pub fn show(&self, at: NaiveDateTime) -> String {
+        self.tz.from_utc_datetime(&at).format(&self.format).to_string()
+    }
+}
+
+/// 내보내는 방과 표시 방법
+struct Export {
+    room: Room,
+    /// username → 표시 이름 (없으면 username)
+    names: HashMap<String, String>,
+    time: TimeStyle,
+}
+
+impl Export {
+    fn name<'a>(&'a self, username: &'a str) -> &'a str {
+        self.names.get(username).map(String::as_str).unwrap_or(username)
+    }
+
+    fn title(&self) -> String {
+        self.room.name.clone().unwrap_or_else(|| format!("대화방 {}", self.room.id))
+    }
+
+    fn txt_header(&self, w: &mut dyn Write) -> io::Result<()> {
+        writeln!(w, "{}", self.title())?;
+        writeln!(w, "내보낸 시각: {} ({})", self.time.show(Utc::now().naive_utc()), self.time.tz.name())?;
+        writeln!(w)
+    }
+
+    fn txt_line(&self, w: &mut dyn Write, chat: &Chat, files: &[Attachment]) -> io::Result<()> {
+        let when = self.time.show(chat.timestamp);
+        match &chat.content {
+            MessageContent::System { text } => writeln!(w, "[{}] * {}", when, text)?,
+            MessageContent::Text { text, .. } => writeln!(w, "[{}] {}: {}", when, self.name(&chat.sender), text)?,
+            MessageContent::Code { code, .. } => writeln!(w, "[{}] {}: {}\n{}", when, self.name(&chat.sender), chat.message, code)?,
+            _ => writeln!(w, "[{}] {}: {}", when, self.name(&chat.sender), chat.message)?,
+        }
+        if let Some(from) = &chat.forwarded_sender {
+            writeln!(w, "    (전달됨: {})", self.name(from))?;
+        }
+        for file in files {
+            writeln!(w, "    첨부: {} ({}, {} bytes)", file_name(file), file.mime, file.size)?;
+        }
+        Ok(())
+    }
+
+    fn json_header(&self, w: &mut dyn Write) -> io::Result<()> {
+        let room = json!({ "id": self.room.id, "name": self.room.name, "topic": self.room.topic });
+        write!(
+            w,
+            "{{\"room\":{},\"exported_at\":{},\"timezone\":{},\"participants\":{},\"messages\":[",
+            room,
+            json!(self.time.show(Utc::now().naive_utc())),
+            json!(self.time.tz.name()),
+            json!(self.names),
+        )
+    }
+
+    fn json_item(&self, w: &mut dyn Write, first: bool, chat: &Chat, files: &[Attachment]) -> io::Result<()> {
+        let files: Vec<_> = files
+            .iter()
+            .map(|f| json!({ "id": f.id, "kind": f.kind, "mime": f.mime, "size": f.size, "file": file_name(f), "view_once": f.view_once }))
+            .collect();
+        let item = json!({
+            "id": chat.id,
+            "time": self.time.show(chat.timestamp),
+            "timestamp": chat.timestamp,
+            "sender": chat.sender,
+            "sender_name": self.name(&chat.sender),
+            "message": chat.message,
+            "kind": chat.content.kind(),
+            "content": chat.content,
+            "forwarded_sender": chat.forwarded_sender,
+            "attachments": files,
+        });
+        if !first {
+            w.write_all(b",")?;
+        }
+        write!(w, "{}", item)
+    }
+
+    fn html_header(&self, w: &mut dyn Write) -> io::Result<()> {
+        write!(
+            w,
+            "<!DOCTYPE html>\n<html lang=\"ko\"><head><meta charset=\"utf-8\"><title>{title}</title><style>{style}\n{code}</style></head><body>\n<h1>{title}</h1>\n<p class=\"meta\">내보낸 시각: {now} ({tz})</p>\n",
+            title = escape(&self.title()),
+            style = HTML_STYLE,
+            code = highlight::stylesheet(),
+            now = self.time.show(Utc::now().naive_utc()),
+            tz = self.time.tz.name(),
+        )
+    }
+
+    fn html_item(&self, w: &mut dyn Write, chat: &Chat, files: &[Attachment]) -> io::Result<()> {
+        let when = escape(&self.time.show(chat.timestamp));
+        if let MessageContent::System { text } = &chat.content {
+            return writeln!(w, "<div class=\"system\"><time>{}</time> {}</div>", when, escape(text));
+        }
+        let body = match &chat.content {
+            MessageContent::Text { format: TextFormat::Markdown, html: Some(html), .. } => html.clone(),
+            MessageContent::Text { text, .. } => format!("<p class=\"plain\">{}</p>", escape(text)),
+            MessageContent::Code { html: Some(html), .. } => html.clone(),
+            _ => format!("<p>{}</p>", escape(&chat.message)),
+        };
+        write!(
+            w,
+            "<div class=\"msg\" id=\"m{}\"><div class=\"meta\"><b>{}</b> <time>{}</time></div>",
+            chat.id,
+            escape(self.name(&chat.sender)),
+            when
+        )?;
+        if let Some(from) = &chat.forwarded_sender {
+            write!(w, "<div class=\"forwarded\">전달됨: {}</div>", escape(self.name(from)))?;
+        }
+        w.write_all(body.as_bytes())?;
+        for file in files {
+            let path = format!("attachments/{}", file_name(file));
+            match file.kind.as_str() {
+                _ if file.view_once => write!(w, "<p class=\"meta\">(한 번 보기 첨부는 내보내지 않습니다)</p>")?,
+                "image" => write!(w, "<img src=\"{}\" alt=\"\">", path)?,
+                "video" => write!(w, "<video controls src=\"{}\"></video>", path)?,
+                "voice" => write!(w, "<audio controls src=\"{}\"></audio>", path)?,
+                _ => write!(w, "<p><a href=\"{0}\">{0}</a></p>", path)?,
+            }
+        }
+        writeln!(w, "</div>")
+    }
+}
+
+const HTML_STYLE: &str = "body{font-family:sans-serif;max-width:800px;margin:auto;padding:1em}\
+.msg{margin:.8em 0}.meta{color:#888;font-size:.85em}.system{color:#888;text-align:center;font-size:.85em;margin:.8em 0}\
+.forwarded{color:#888;font-style:italic;font-size:.85em}.plain{white-space:pre-wrap;margin:.2em 0}img,video{max-width:100%}";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// ZIP 안의 첨부 파일 이름: 첨부 id + 저장 키의 확장자
+fn file_name(file: &Attachment) -> String {
+    match file.storage_key.rsplit_once('.') {
+        Some((_, ext)) => format!("{}.{}", file.id, ext),
+        None => file.id.to_string(),
+    }
+}
+
+/// 쓰인 바이트를 모아 두는 버퍼. 한 덩어리를 쓸 때마다 `Out::drain`으로 응답에 보낸다.
+#[derive(Clone, Default)]
+struct Spool(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Spool {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Out {
+    spool: Spool,
+    tx: mpsc::Sender<io::Result<Bytes>>,
+}
+
+impl Out {
+    /// 모인 바이트를 응답으로 보낸다. 받는 쪽이 끊었으면 에러로 멈춘다.
+    async fn drain(&self) -> io::Result<()> {
+        let bytes = std::mem::take(&mut *self.spool.0.lock().unwrap());
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        self.tx
+            .send(Ok(Bytes::from(bytes)))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "download cancelled"))
+    }
+}
+
+/// 방의 메시지를 id 순서로 PAGE개씩, 첨부와 함께 읽는다
+struct Pages<'a> {
+    conn: &'a DatabaseConnection,
+    room_id: i32,
+    after: i32,
+}
+
+impl Pages<'_> {
+    async fn next(&mut self) -> io::Result<Option<Vec<(Chat, Vec<Attachment>)>>> {
+        let chats = ChatEntity::find()
+            .filter(chat::Column::RoomId.eq(self.room_id))
+            .filter(chat::Column::Id.gt(self.after))
+            .filter(retention::not_expired())
+            .order_by_asc(chat::Column::Id)
+            .limit(PAGE)
+            .all(self.conn)
+            .await
+            .map_err(io::Error::other)?;
+        let Some(last) = chats.last() else {
+            return Ok(None);
+        };
+        self.after = last.id;
+        // 전달된 메시지의 첨부는 원본 메시지에 달려 있다
+        let mut files: HashMap<i32, Vec<Attachment>> = HashMap::new();
+        for file in AttachmentEntity::find()
+            .filter(attachment::Column::ChatId.is_in(chats.iter().map(|c| c.forwarded_from_id.unwrap_or(c.id))))
+            .order_by_asc(attachment::Column::Id)
+            .all(self.conn)
+            .await
+            .map_err(io::Error::other)?
+        {
+            files.entry(file.chat_id).or_default().push(file);
+        }
+        Ok(Some(
+            chats
+                .into_iter()
+                .map(|chat| {
+                    let files = files.get(&chat.forwarded_from_id.unwrap_or(chat.id)).cloned().unwrap_or_default();
+                    (chat, files)
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// 방에 메시지를 남긴 사람들의 표시 이름
+async fn display_names(conn: &DatabaseConnection, room: &Room) -> Result<HashMap<String, String>, sea_orm::DbErr> {
+    let mut senders: Vec<String> = ChatEntity::find()
+        .select_only()
+        .column(chat::Column::Sender)
+        .distinct()
+        .filter(chat::Column::RoomId.eq(room.id))
+        .into_tuple()
+        .all(conn)
+        .await?;
+    senders.extend(participants_of(room));
+    let users = UserEntity::find().filter(users::Column::Username.is_in(senders.clone())).all(conn).await?;
+    let mut names: HashMap<String, String> = senders.into_iter().map(|s| (s.clone(), s)).collect();
+    for user in users {
+        if let Some(display) = user.display_name.filter(|d| !d.trim().is_empty()) {
+            names.insert(user.username, display);
+        }
+    }
+    Ok(names)
+}
+
+async fn write_export(conn: &DatabaseConnection, export: &Export, format: Format, out: &Out) -> io::Result<()> {
+    let mut pages = Pages { conn, room_id: export.room.id, after: 0 };
+    match format {
+        Format::Txt => {
+            let mut w = out.spool.clone();
+            export.txt_header(&mut w)?;
+            while let Some(page) = pages.next().await? {
+                for (chat, files) in &page {
+                    export.txt_line(&mut w, chat, files)?;
+                }
+                out.drain().await?;
+            }
+        }
+        Format::Json => {
+            let mut w = out.spool.clone();
+            export.json_header(&mut w)?;
+            let mut first = true;
+            while let Some(page) = pages.next().await? {
+                for (chat, files) in &page {
+                    export.json_item(&mut w, first, chat, files)?;
+                    first = false;
+                }
+                out.drain().await?;
+            }
+            w.write_all(b"]}")?;
+        }
+        Format::Html => {
+            let mut zip = ZipWriter::new_stream(out.spool.clone());
+            // ZIP은 항목을 하나씩만 쓸 수 있으므로 첨부를 먼저 다 옮기고 index.html을 쓴다
+            write_attachments(conn, export.room.id, &mut zip, out).await?;
+            zip.start_file("index.html", SimpleFileOptions::default().compression_method(CompressionMethod::Deflated))
+                .map_err(io::Error::other)?;
+            export.html_header(&mut zip)?;
+            while let Some(page) = pages.next().await? {
+                for (chat, files) in &page {
+                    export.html_item(&mut zip, chat, files)?;
+                }
+                out.drain().await?;
+            }
+            zip.write_all(b"</body></html>\n")?;
+            zip.finish().map_err(io::Error::other)?;
+        }
+    }
+    out.drain().await
+}
+
+/// 방의 첨부 파일을 `attachments/` 아래로 옮긴다. 전달받은 메시지는 원본의 첨부를 담고, 파일이 없어진 첨부는 건너뛴다.
+async fn write_attachments<W: Write + io::Seek>(
+    conn: &DatabaseConnection,
+    room_id: i32,
+    zip: &mut ZipWriter<W>,
+    out: &Out,
+) -> io::Result<()> {
+    let mut after = 0;
+    let mut buf = vec![0u8; FILE_CHUNK];
+    loop {
+        let files = AttachmentEntity::find()
+            .filter(attachment::Column::ChatId.in_subquery(attachment_owners(room_id)))
+            .filter(attachment::Column::ViewOnce.eq(false))
+            .filter(attachment::Column::Id.gt(after))
+            .order_by_asc(attachment::Column::Id)
+            .limit(PAGE)
+            .all(conn)
+            .await
+            .map_err(io::Error::other)?;
+        let Some(last) = files.last() else {
+            return Ok(());
+        };
+        after = last.id;
+        for file in files {
+            let mut source = match store::open(&file.storage_key).await {
+                Ok(source) => source,
+                Err(e) => {
+                    eprintln!("export: attachment {} skipped: {}", file.id, e);
+                    continue;
+                }
+            };
+            // 사진, 동영상, 음성은 이미 압축되어 있으므로 그대로 담는다
+            let options = SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Stored)
+                .large_file(file.size >= u32::MAX as i64);
+            zip.start_file(format!("attachments/{}", file_name(&file)), options).map_err(io::Error::other)?;
+            loop {
+                let n = source.read(&mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                zip.write_all(&buf[..n])?;
+                out.drain().await?;
+            }
+        }
+    }
+}
+
+// 방에 남아 있는 메시지의 첨부가 달린 chat id (전달본이면 원본 id)
+fn attachment_owners(room_id: i32) -> SelectStatement {
+    SeaQuery::select()
+        .expr(Expr::cust("coalesce(chat.forwarded_from_id, chat.id)"))
+        .from(ChatEntity)
+        .and_where(chat::Column::RoomId.eq(room_id))
+        .cond_where(retention::not_expired())
+        .to_owned()
+}
+
+/// GET /room/{id}/export?username=&format=json|html|txt&tz=Asia/Seoul&time_format=%Y-%m-%d %H:%M
+pub async fn export(
+    State(conn): State<DatabaseConnection>,
+    Path(room_id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let Some(format) = Format::parse(params.get("format").map(String::as_str).unwrap_or("json")) else {
+        return (StatusCode::BAD_REQUEST, "format은 json, html, txt 중 하나입니다.").into_response();
+    };
+    let time = match TimeStyle::parse(params.get("tz").map(String::as_str), params.get("time_format").map(String::as_str)) {
+        Ok(time) => time,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let username = params.get("username").map(String::as_str).unwrap_or_default();
+    let room = match RoomEntity::find_by_id(room_id).one(&conn).await {
+        Ok(Some(room)) => room,
+        Ok(None) => return (StatusCode::NOT_FOUND, "존재하지 않는 방입니다.").into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, format!("DB 오류: {}", e)).into_response(),
+    };
+    if !participants_of(&room).iter().any(|p| p == username) {
+        return (StatusCode::FORBIDDEN, "참여 중인 방이 아닙니다.").into_response();
+    }
+    let names = match display_names(&conn, &room).await {
+        Ok(names) => names,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, format!("DB 오류: {}", e)).into_response(),
+    };
+
+    let file = format!("room-{}-{}.{}", room_id, Utc::now().format("%Y%m%d"), format.extension());
+    let export = Export { room, names, time };
+    let (tx, rx) = mpsc::channel(8);
+    tokio::spawn(async move {
+        let out = Out { spool: Spool::default(), tx };
+        if let Err(e) = write_export(&conn, &export, format, &out).await {
+            // 이미 응답을 보내기 시작했으므로 연결을 끊어 받는 쪽이 불완전한 파일임을 알게 한다
+            if e.kind() != io::ErrorKind::BrokenPipe {
+                eprintln!("export of room {} failed: {}", export.room.id, e);
+            }
+            let _ = out.tx.send(Err(e)).await;
+        }
+    });
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file)),
+        ],
+        Body::from_stream(ReceiverStream::new(rx)),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{Database, DbBackend, DbErr, ProxyDatabaseTrait, ProxyExecResult, ProxyRow, Statement, Value};
+
+    // 원본(7번) 메시지에 달린 사진을 다른 방으로 전달한 10번 메시지만 있는 DB. 받은 조회문을 기록한다.
+    #[derive(Debug, Default)]
+    struct ForwardedPhoto(Arc<Mutex<Vec<Statement>>>);
+
+    fn row(values: Vec<(&str, Value)>) -> ProxyRow {
+        ProxyRow { values: values.into_iter().map(|(k, v)| (k.to_string(), v)).collect() }
+    }
+
+    #[async_trait::async_trait]
+    impl ProxyDatabaseTrait for ForwardedPhoto {
+        async fn query(&self, statement: Statement) -> Result<Vec<ProxyRow>, DbErr> {
+            let from_attachment = statement.sql.contains(r#"FROM "attachment""#);
+            self.0.lock().unwrap().push(statement);
+            let at = Value::from(NaiveDateTime::default());
+            if from_attachment {
+                return Ok(vec![row(vec![
+                    ("id", Value::from(3)),
+                    ("chat_id", Value::from(7)),
+                    ("kind", Value::from("image")),
+                    ("mime", Value::from("image/png")),
+                    ("storage_key", Value::from("photo.png")),
+                    ("size", Value::from(9i64)),
+                    ("duration_ms", Value::Int(None)),
+                    ("waveform", Value::String(None)),
+                    ("created_at", at),
+                    ("view_once", Value::from(false)),
+                    ("viewed_at", Value::ChronoDateTime(None)),
+                    ("viewed_by", Value::String(None)),
+                ])]);
+            }
+            Ok(vec![row(vec![
+                ("id", Value::from(10)),
+                ("timestamp", at.clone()),
+                ("sender", Value::from("bob")),
+                ("message", Value::from("📷 사진")),
+                ("content", Value::from(json!({ "kind": "image", "content": { "view_once": false } }))),
+                ("room_id", Value::from(2)),
+                ("expires_at", Value::ChronoDateTime(None)),
+                ("forwarded_from_id", Value::from(7)),
+                ("forwarded_sender", Value::from("alice")),
+                ("forwarded_at", at),
+                ("client_msg_id", Value::String(None)),
+                ("webhook_id", Value::Int(None)),
+            ])])
+        }
+
+        async fn execute(&self, _: Statement) -> Result<ProxyExecResult, DbErr> {
+            Err(DbErr::Custom("read only".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn forwarded_message_exports_the_source_attachment() {
+        let db = ForwardedPhoto::default();
+        let seen = db.0.clone();
+        let conn = Database::connect_proxy(DbBackend::Postgres, Arc::new(Box::new(db))).await.unwrap();
+
+        let page = Pages { conn: &conn, room_id: 2, after: 0 }.next().await.unwrap().unwrap();
+        assert_eq!(page.len(), 1);
+        let (chat, files) = &page[0];
+        assert_eq!(chat.id, 10);
+        assert_eq!(files.iter().map(|f| f.id).collect::<Vec<_>>(), vec![3]);
+        // 첨부는 전달본(10)이 아니라 원본(7) id로 찾는다
+        let statements = seen.lock().unwrap();
+        assert_eq!(statements[1].values.as_ref().unwrap().0, vec![Value::from(7)]);
+
+        // ZIP에 담을 첨부도 원본 id로 찾는다
+        let owners = attachment_owners(2).to_string(sea_orm::sea_query::PostgresQueryBuilder);
+        assert!(owners.contains("coalesce(chat.forwarded_from_id, chat.id)"), "{}", owners);
+    }
+
+    #[test]
+    fn formats_time_in_chosen_zone() {
+        let at = NaiveDateTime::parse_from_str("2025-01-01 15:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(TimeStyle::parse(None, None).unwrap().show(at), "2025-01-01 15:30:00");
+        let seoul = TimeStyle::parse(Some("Asia/Seoul"), Some("%m/%d %H:%M")).unwrap();
+        assert_eq!(seoul.show(at), "01/02 00:30");
+        assert!(TimeStyle::parse(Some("Mars/Olympus"), None).is_err());
+        assert!(TimeStyle::parse(None, Some("%Q")).is_err());
+    }
+
+    #[test]
+    fn streamed_zip_reads_back() {
+        let spool = Spool::default();
+        let mut zip = ZipWriter::new_stream(spool.clone());
+        zip.start_file("attachments/1.png", SimpleFileOptions::default().compression_method(CompressionMethod::Stored)).unwrap();
+        zip.write_all(b"png bytes").unwrap();
+        zip.start_file("index.html", SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)).unwrap();
+        zip.write_all(b"<html></html>").unwrap();
+        zip.finish().unwrap();
+
+        let bytes = spool.0.lock().unwrap().clone();
+        let mut archive = zip::ZipArchive::new(io::Cursor::new(bytes)).unwrap();
+        let mut html = String::new();
+        io::Read::read_to_string(&mut archive.by_name("index.html").unwrap(), &mut html).unwrap();
+        assert_eq!(html, "<html></html>");
+        assert_eq!(archive.by_name("attachments/1.png").unwrap().size(), 9);
+    }
+}
@@ -0,0 +1,170 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::content::MessageContent;
+use crate::entities::{
+    attachment::{Column as AttachmentCol, Entity as AttachmentEntity},
+    chat::{ActiveModel as ActiveChat, Entity as ChatEntity, Model as Chat},
+    room::{Column as RoomCol, Entity as RoomEntity},
+};
+use crate::preview;
+use crate::validation::AttachmentMeta;
+
+use super::bot;
+use super::chat::{insert_message, NewMessage};
+use super::chat_room::participants_of;
+use super::event::ChatEvent;
+use super::outgoing;
+use super::retention;
+use super::validation;
+
+/// 한 번에 전달할 수 있는 방 수
+const MAX_TARGETS: usize = 10;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+#[derive(Deserialize)]
+pub struct ForwardRequest {
+    pub username: String,
+    pub room_ids: Vec<i32>,
+}
+
+fn fail<T>(error: impl Into<String>) -> Json<ApiResponse<T>> {
+    Json(ApiResponse { success: 0, error: Some(error.into()), data: None })
+}
+
+/// POST /chat/{id}/forward: 메시지를 다른 방들로 전달한다. 모든 방에 저장되거나 하나도 저장되지 않는다.
+pub async fn forward(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+    Json(req): Json<ForwardRequest>,
+) -> Json<ApiResponse<Vec<Chat>>> {
+    let mut room_ids = req.room_ids;
+    room_ids.sort();
+    room_ids.dedup();
+    if req.username.trim().is_empty() || room_ids.is_empty() {
+        return fail("username과 room_ids가 필요합니다.");
+    }
+    // 봇 계정으로 보내려면 그 봇의 API 토큰이 있어야 한다
+    if let Err(e) = bot::authorize_sender(&conn, &headers, &req.username).await {
+        return fail(e);
+    }
+    if room_ids.len() > MAX_TARGETS {
+        return fail(format!("한 번에 {}개 방까지 전달할 수 있습니다.", MAX_TARGETS));
+    }
+
+    // 원본을 읽을 수 있는지 확인 (만료된 메시지는 없는 것으로 본다)
+    let source = match ChatEntity::find_by_id(id).filter(retention::not_expired()).one(&conn).await {
+        Ok(Some(chat)) => chat,
+        Ok(None) => return fail("메시지를 찾을 수 없습니다."),
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    };
+    let can_read = match RoomEntity::find_by_id(source.room_id).one(&conn).await {
+        Ok(Some(room)) => participants_of(&room).contains(&req.username),
+        _ => false,
+    };
+    if !can_read {
+        return fail("메시지를 찾을 수 없습니다.");
+    }
+
+    // 전달의 전달은 처음 원본을 가리킨다
+    let (origin_id, origin_sender, origin_at) = match &source.forwarded_sender {
+        Some(sender) => (source.forwarded_from_id, sender.clone(), source.forwarded_at),
+        None => (Some(source.id), source.sender.clone(), Some(source.timestamp)),
+    };
+
+    // 첨부는 복사하지 않고 원본을 참조하므로, 원본과 함께 사라질 첨부는 전달하지 않는다
+    let attachments = match origin_id {
+        Some(origin_id) => AttachmentEntity::find()
+            .filter(AttachmentCol::ChatId.eq(origin_id))
+            .all(&conn)
+            .await
+            .unwrap_or_default(),
+        None => vec![],
+    };
+    if attachments.iter().any(|a| a.view_once) {
+        return fail("한 번만 볼 수 있는 미디어는 전달할 수 없습니다.");
+    }
+    // 투표는 집계가 원본 방에 묶여 있고, 안내 메시지는 서버만 만든다
+    if matches!(source.content, MessageContent::Poll { .. } | MessageContent::System { .. }) {
+        return fail("이 종류의 메시지는 전달할 수 없습니다.");
+    }
+    if !attachments.is_empty() && source.expires_at.is_some() {
+        return fail("사라지는 메시지의 첨부는 전달할 수 없습니다.");
+    }
+
+    let rooms = match RoomEntity::find().filter(RoomCol::Id.is_in(room_ids.clone())).all(&conn).await {
+        Ok(rooms) => rooms,
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    };
+    for room_id in &room_ids {
+        let can_post = rooms.iter().any(|r| r.id == *room_id && participants_of(r).contains(&req.username));
+        if !can_post {
+            return fail(format!("{}번 방에 메시지를 보낼 수 없습니다.", room_id));
+        }
+    }
+
+    // 방마다 규칙(금지어, 첨부 종류·크기 등)이 다르므로 받을 방 기준으로 다시 검사한다
+    let attachment_metas: Vec<AttachmentMeta> =
+        attachments.iter().map(|a| AttachmentMeta { kind: a.kind.clone(), size: a.size.max(0) as u64 }).collect();
+    let mut new_messages = Vec::with_capacity(room_ids.len());
+    for room_id in &room_ids {
+        let new_message = NewMessage {
+            sender: req.username.clone(),
+            room_id: *room_id,
+            content: source.content.clone(),
+            client_msg_id: None,
+        };
+        if let Err(rejection) = validation::check(&conn, &new_message, &attachment_metas).await {
+            return fail(format!("{}번 방에 전달할 수 없습니다: {}", room_id, rejection));
+        }
+        new_messages.push(new_message);
+    }
+
+    let result = async {
+        let txn = conn.begin().await.map_err(|e| e.to_string())?;
+        let mut copies = Vec::new();
+        for new_message in &new_messages {
+            let chat = insert_message(&txn, new_message).await?;
+            // 사라지는 메시지는 보관 기간이 없는 방으로 옮겨도 원본이 사라질 때 함께 사라진다
+            let expires_at = match (chat.expires_at, source.expires_at) {
+                (Some(own), Some(origin)) => Some(own.min(origin)),
+                (own, origin) => own.or(origin),
+            };
+            let mut chat: ActiveChat = chat.into();
+            chat.expires_at = ActiveValue::Set(expires_at);
+            chat.forwarded_from_id = ActiveValue::Set(origin_id);
+            chat.forwarded_sender = ActiveValue::Set(Some(origin_sender.clone()));
+            chat.forwarded_at = ActiveValue::Set(origin_at);
+            copies.push(chat.update(&txn).await.map_err(|e| e.to_string())?);
+        }
+        txn.commit().await.map_err(|e| e.to_string())?;
+        Ok::<_, String>(copies)
+    }
+    .await;
+    let copies = match result {
+        Ok(copies) => copies,
+        Err(e) => return fail(format!("전달에 실패했습니다: {}", e)),
+    };
+
+    for chat in &copies {
+        outgoing::publish(&conn, &queue, ChatEvent::Message(chat.clone())).await;
+        preview::spawn_for_message(conn.clone(), queue.clone(), chat.clone());
+    }
+    Json(ApiResponse { success: 1, error: None, data: Some(copies) })
+}
@@ -0,0 +1,173 @@
+use axum::{
+    extract::{Multipart, State},
+    Json,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, TransactionTrait};
+use serde::Serialize;
+
+use crate::content::MessageContent;
+use crate::entities::{
+    chat::{ActiveModel as ActiveChat, Entity as ChatEntity},
+    room::ActiveModel as ActiveRoom,
+    users::{self, Entity as UserEntity},
+};
+use crate::import::{self, ImportedRoom, Mapping, Report, Source};
+use crate::search;
+
+/// 가져오기 파일 최대 크기 (Slack 워크스페이스 ZIP을 생각한 값)
+pub const MAX_IMPORT_BYTES: usize = 200 * 1024 * 1024;
+/// 한 번의 INSERT에 넣는 메시지 수
+const BATCH: usize = 500;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+fn fail<T>(error: impl Into<String>) -> Json<ApiResponse<T>> {
+    Json(ApiResponse { success: 0, error: Some(error.into()), data: None })
+}
+
+/// 매핑된 username 중 가입하지 않은 것
+async fn unknown_users(conn: &DatabaseConnection, report: &Report) -> Result<Vec<String>, sea_orm::DbErr> {
+    let wanted = report.usernames();
+    let found: Vec<String> = UserEntity::find()
+        .select_only()
+        .column(users::Column::Username)
+        .filter(users::Column::Username.is_in(wanted.iter().cloned()))
+        .into_tuple()
+        .all(conn)
+        .await?;
+    Ok(wanted.into_iter().filter(|u| !found.contains(u)).collect())
+}
+
+/// 방마다 새 방을 만들고 메시지를 원래 시각 그대로 BATCH개씩 넣는다. 하나라도 실패하면 전부 되돌린다.
+async fn store(
+    conn: &DatabaseConnection,
+    importer: &str,
+    rooms: Vec<ImportedRoom>,
+    mapping: &Mapping,
+    report: &mut Report,
+) -> Result<(), sea_orm::DbErr> {
+    let txn = conn.begin().await?;
+    for (room, room_report) in rooms.into_iter().zip(report.rooms.iter_mut()) {
+        let created = ActiveRoom {
+            participants: ActiveValue::Set(serde_json::to_string(&room_report.participants).unwrap()),
+            name: ActiveValue::Set(Some(room.name.clone())),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        for batch in room.messages.chunks(BATCH) {
+            let rows = batch.iter().map(|message| {
+                // 안내 메시지는 가져온 사람 이름으로 남긴다 (plan에서 모든 보낸 사람이 매핑된 것을 확인했다)
+                let (sender, content) = match &message.sender {
+                    Some(name) => (mapping.get(name).unwrap_or(name).to_string(), MessageContent::text(message.text.clone())),
+                    None => (importer.to_string(), MessageContent::System { text: message.text.clone() }),
+                };
+                ActiveChat {
+                    id: ActiveValue::NotSet,
+                    timestamp: ActiveValue::Set(message.sent_at),
+                    sender: ActiveValue::Set(sender),
+                    message: ActiveValue::Set(content.summary()),
+                    content: ActiveValue::Set(content),
+                    room_id: ActiveValue::Set(created.id),
+                    ..Default::default()
+                }
+            });
+            let inserted = ChatEntity::insert_many(rows).exec_with_returning_many(&txn).await?;
+            let index: Vec<(i32, &str)> = inserted.iter().map(|chat| (chat.id, chat.message.as_str())).collect();
+            search::index_messages(&txn, &index).await?;
+        }
+        room_report.room_id = Some(created.id);
+    }
+    txn.commit().await
+}
+
+/// POST /import (multipart): username, source(kakao|slack|whatsapp), file, mapping(JSON 텍스트나 파일),
+/// tz(카카오톡·WhatsApp 시각의 시간대, 기본 Asia/Seoul), room_name(방 이름 덮어쓰기, 방이 하나일 때), dry_run(기본 true)
+///
+/// dry_run이면 저장하지 않고 점검 보고서만 돌려준다. 실제로 가져올 때도 보고서가 ready가 아니면 거부한다.
+pub async fn import(State(conn): State<DatabaseConnection>, mut multipart: Multipart) -> Json<ApiResponse<Report>> {
+    let mut username = String::new();
+    let mut source: Option<String> = None;
+    let mut tz = String::from("Asia/Seoul");
+    let mut room_name: Option<String> = None;
+    let mut mapping_text = String::new();
+    let mut dry_run = true;
+    let mut file: Option<(Vec<u8>, Option<String>)> = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return fail(format!("업로드를 읽을 수 없습니다: {}", e)),
+        };
+        match field.name().unwrap_or_default() {
+            "username" => username = field.text().await.unwrap_or_default().trim().to_string(),
+            "source" => source = field.text().await.ok().map(|s| s.trim().to_string()),
+            "tz" => tz = field.text().await.unwrap_or_default().trim().to_string(),
+            "room_name" => room_name = field.text().await.ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+            "mapping" => mapping_text = field.text().await.unwrap_or_default(),
+            "dry_run" => dry_run = !field.text().await.is_ok_and(|v| matches!(v.trim(), "false" | "0")),
+            "file" => {
+                let name = field.file_name().map(str::to_string);
+                match field.bytes().await {
+                    Ok(bytes) => file = Some((bytes.to_vec(), name)),
+                    Err(_) => return fail(format!("파일은 {}MB까지 올릴 수 있습니다.", MAX_IMPORT_BYTES / 1024 / 1024)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Some(source) = source.as_deref().and_then(Source::parse) else {
+        return fail("source는 kakao, slack, whatsapp 중 하나입니다.");
+    };
+    let Some((bytes, file_name)) = file else {
+        return fail("가져올 파일이 필요합니다.");
+    };
+    if username.is_empty() {
+        return fail("가져오는 사람을 입력하세요.");
+    }
+    let Ok(tz) = tz.parse() else {
+        return fail(format!("알 수 없는 시간대 '{}'입니다. (예: Asia/Seoul, UTC)", tz));
+    };
+    let mapping = match Mapping::parse(&mapping_text) {
+        Ok(mapping) => mapping,
+        Err(e) => return fail(e),
+    };
+    // 압축 풀기와 파싱은 CPU를 쓰므로 blocking 스레드에서
+    let parsed = tokio::task::spawn_blocking(move || import::parse(source, &bytes, tz, file_name.as_deref())).await;
+    let mut parsed = match parsed {
+        Ok(Ok(parsed)) => parsed,
+        Ok(Err(e)) => return fail(e),
+        Err(_) => return fail("파일을 읽는 중 오류가 발생했습니다."),
+    };
+    if let (Some(name), [room]) = (room_name, parsed.rooms.as_mut_slice()) {
+        room.name = name;
+    }
+
+    let mut report = import::plan(source, &parsed, &mapping, &username, dry_run);
+    match unknown_users(&conn, &report).await {
+        Ok(unknown) => report.unknown_users = unknown,
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    }
+    report.update_ready();
+    if dry_run {
+        return Json(ApiResponse { success: 1, error: None, data: Some(report) });
+    }
+    if !report.ready {
+        return Json(ApiResponse {
+            success: 0,
+            error: Some("매핑되지 않은 이름이나 가입하지 않은 사용자가 있어 가져올 수 없습니다.".to_string()),
+            data: Some(report),
+        });
+    }
+    match store(&conn, &username, parsed.rooms, &mapping, &mut report).await {
+        Ok(()) => Json(ApiResponse { success: 1, error: None, data: Some(report) }),
+        Err(e) => fail(format!("가져오기에 실패해 아무것도 저장하지 않았습니다: {}", e)),
+    }
+}
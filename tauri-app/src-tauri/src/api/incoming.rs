@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use sea_orm::{
+    sea_query::Expr,
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::content::{MessageContent, TextFormat};
+use crate::entities::{
+    chat::ActiveModel as ActiveChat,
+    incoming_webhook::{ActiveModel, Column, Entity as HookEntity, Model as Hook},
+    room::Entity as RoomEntity,
+    users::Entity as UserEntity,
+};
+use crate::media::store;
+use crate::preview;
+
+use super::bot::hash_token;
+use super::chat::{insert_external_message, validate, NewMessage, SendResponse};
+use super::chat_room::{member_room, participants_of};
+use super::event::ChatEvent;
+use super::outgoing;
+use super::validation;
+
+const MAX_NAME_CHARS: usize = 40;
+const MAX_ATTACHMENTS: usize = 10;
+const MAX_FIELDS: usize = 20;
+/// webhook 하나가 WINDOW 동안 올릴 수 있는 메시지 수
+const MAX_PER_WINDOW: usize = 20;
+const WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+fn fail<T>(error: impl Into<String>) -> Json<ApiResponse<T>> {
+    Json(ApiResponse { success: 0, error: Some(error.into()), data: None })
+}
+
+/// 수신 webhook별 전송 제한. 메모리에만 두며 서버 재시작 시 초기화된다.
+#[derive(Clone, Default)]
+pub struct HookLimiter {
+    recent: Arc<Mutex<HashMap<i32, Vec<Instant>>>>,
+}
+
+impl HookLimiter {
+    /// 보낼 수 있으면 Ok, 아니면 다시 보낼 수 있을 때까지 남은 시간
+    pub fn allow(&self, hook_id: i32) -> Result<(), Duration> {
+        self.allow_at(hook_id, Instant::now())
+    }
+
+    fn allow_at(&self, hook_id: i32, now: Instant) -> Result<(), Duration> {
+        let mut recent = self.recent.lock().unwrap();
+        let sent = recent.entry(hook_id).or_default();
+        sent.retain(|t| now.duration_since(*t) < WINDOW);
+        if sent.len() >= MAX_PER_WINDOW {
+            return Err(WINDOW - now.duration_since(sent[0]));
+        }
+        sent.push(now);
+        if recent.len() > 1024 {
+            recent.retain(|_, ts| ts.iter().any(|t| now.duration_since(*t) < WINDOW));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NewHook {
+    pub username: String,
+    /// 메시지 보낸 사람 자리에 보일 이름 (예: GitHub, CI)
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct HookMember {
+    pub username: String,
+}
+
+/// 비밀 주소는 만들거나 새로 발급할 때 이 응답에서 한 번만 보여준다
+#[derive(Serialize)]
+pub struct HookWithUrl {
+    pub hook: Hook,
+    pub url: String,
+}
+
+/// 외부 시스템이 보내는 본문. text, fields, attachments 중 하나는 있어야 한다.
+#[derive(Deserialize, Default)]
+pub struct HookPayload {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub fields: Vec<HookField>,
+    #[serde(default)]
+    pub attachments: Vec<HookAttachment>,
+}
+
+#[derive(Deserialize)]
+pub struct HookField {
+    pub title: String,
+    pub value: String,
+}
+
+#[derive(Deserialize, Default)]
+pub struct HookAttachment {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub title_link: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub fields: Vec<HookField>,
+}
+
+fn push_fields(out: &mut Vec<String>, fields: &[HookField]) {
+    for field in fields {
+        out.push(format!("- **{}**: {}", field.title.trim(), field.value.trim()));
+    }
+}
+
+/// 본문을 markdown 한 덩어리로 만든다 (HTML은 저장할 때 markdown 렌더러가 정리한다)
+pub fn render(payload: &HookPayload) -> Result<String, String> {
+    if payload.attachments.len() > MAX_ATTACHMENTS {
+        return Err(format!("attachments는 {}개까지입니다.", MAX_ATTACHMENTS));
+    }
+    if payload.fields.len() > MAX_FIELDS || payload.attachments.iter().any(|a| a.fields.len() > MAX_FIELDS) {
+        return Err(format!("fields는 {}개까지입니다.", MAX_FIELDS));
+    }
+    let mut blocks: Vec<String> = Vec::new();
+    if let Some(text) = payload.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        blocks.push(text.to_string());
+    }
+    let mut fields = Vec::new();
+    push_fields(&mut fields, &payload.fields);
+    if !fields.is_empty() {
+        blocks.push(fields.join("\n"));
+    }
+    for attachment in &payload.attachments {
+        let mut lines = Vec::new();
+        match (attachment.title.as_deref().map(str::trim), attachment.title_link.as_deref()) {
+            (Some(title), Some(link)) if !title.is_empty() && url::Url::parse(link).is_ok_and(|u| matches!(u.scheme(), "http" | "https")) => {
+                lines.push(format!("**[{}]({})**", title, link))
+            }
+            (Some(title), _) if !title.is_empty() => lines.push(format!("**{}**", title)),
+            _ => {}
+        }
+        if let Some(text) = attachment.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            lines.push(text.to_string());
+        }
+        push_fields(&mut lines, &attachment.fields);
+        if !lines.is_empty() {
+            blocks.push(lines.join("\n"));
+        }
+    }
+    if blocks.is_empty() {
+        return Err("text, fields, attachments 중 하나는 있어야 합니다.".to_string());
+    }
+    Ok(blocks.join("\n\n"))
+}
+
+fn new_url() -> (String, String) {
+    let token = store::random_hex(24);
+    let hash = hash_token(&token);
+    (format!("/api/hooks/{}", token), hash)
+}
+
+async fn room_hook(conn: &DatabaseConnection, room_id: i32, hook_id: i32, username: &str) -> Result<Hook, String> {
+    member_room(conn, room_id, username).await?;
+    match HookEntity::find_by_id(hook_id).one(conn).await {
+        Ok(Some(hook)) if hook.room_id == room_id => Ok(hook),
+        Ok(_) => Err("webhook을 찾을 수 없습니다.".to_string()),
+        Err(e) => Err(format!("DB 오류: {}", e)),
+    }
+}
+
+// webhook 메시지는 이름을 보낸 사람으로 남기므로, 사용자나 방 참가자와 같은 이름이면 그 사람인 척할 수 있다
+async fn check_name(conn: &DatabaseConnection, room_id: i32, name: &str) -> Result<(), String> {
+    let taken = "사용자 이름과 같은 이름은 쓸 수 없습니다.".to_string();
+    let room = RoomEntity::find_by_id(room_id).one(conn).await.map_err(|e| format!("DB 오류: {}", e))?;
+    if room.is_some_and(|room| participants_of(&room).iter().any(|p| p.eq_ignore_ascii_case(name))) {
+        return Err(taken);
+    }
+    let user = UserEntity::find()
+        .filter(Expr::cust_with_values("lower(users.username) = lower($1)", [name]))
+        .one(conn)
+        .await
+        .map_err(|e| format!("DB 오류: {}", e))?;
+    match user {
+        Some(_) => Err(taken),
+        None => Ok(()),
+    }
+}
+
+/// POST /room/{id}/webhook: 방 참가자가 수신 webhook을 만든다
+pub async fn create_hook(
+    State(conn): State<DatabaseConnection>,
+    Path(room_id): Path<i32>,
+    Json(req): Json<NewHook>,
+) -> Json<ApiResponse<HookWithUrl>> {
+    let name = req.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return fail(format!("이름은 1~{}자로 입력하세요.", MAX_NAME_CHARS));
+    }
+    if let Err(e) = member_room(&conn, room_id, &req.username).await {
+        return fail(e);
+    }
+    if let Err(e) = check_name(&conn, room_id, &name).await {
+        return fail(e);
+    }
+    let (url, token_hash) = new_url();
+    let row = ActiveModel {
+        id: ActiveValue::NotSet,
+        room_id: ActiveValue::Set(room_id),
+        name: ActiveValue::Set(name),
+        token_hash: ActiveValue::Set(token_hash),
+        created_by: ActiveValue::Set(req.username),
+        created_at: ActiveValue::Set(chrono::Utc::now().naive_utc()),
+        last_used_at: ActiveValue::Set(None),
+    };
+    match row.insert(&conn).await {
+        Ok(hook) => Json(ApiResponse { success: 1, error: None, data: Some(HookWithUrl { hook, url }) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// GET /room/{id}/webhook?username=: 방의 수신 webhook 목록 (주소는 보이지 않는다)
+pub async fn list_hooks(
+    State(conn): State<DatabaseConnection>,
+    Path(room_id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<Vec<Hook>>> {
+    let username = params.get("username").map(String::as_str).unwrap_or_default();
+    if let Err(e) = member_room(&conn, room_id, username).await {
+        return fail(e);
+    }
+    match HookEntity::find().filter(Column::RoomId.eq(room_id)).order_by_asc(Column::Id).all(&conn).await {
+        Ok(hooks) => Json(ApiResponse { success: 1, error: None, data: Some(hooks) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// POST /room/{id}/webhook/{hook_id}/regenerate: 새 주소를 발급한다. 이전 주소는 바로 못 쓰게 된다.
+pub async fn regenerate(
+    State(conn): State<DatabaseConnection>,
+    Path((room_id, hook_id)): Path<(i32, i32)>,
+    Json(req): Json<HookMember>,
+) -> Json<ApiResponse<HookWithUrl>> {
+    let hook = match room_hook(&conn, room_id, hook_id, &req.username).await {
+        Ok(hook) => hook,
+        Err(e) => return fail(e),
+    };
+    let (url, token_hash) = new_url();
+    let mut row: ActiveModel = hook.into();
+    row.token_hash = ActiveValue::Set(token_hash);
+    match row.update(&conn).await {
+        Ok(hook) => Json(ApiResponse { success: 1, error: None, data: Some(HookWithUrl { hook, url }) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// DELETE /room/{id}/webhook/{hook_id}?username= (남긴 메시지는 그대로 둔다)
+pub async fn delete_hook(
+    State(conn): State<DatabaseConnection>,
+    Path((room_id, hook_id)): Path<(i32, i32)>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<i32>> {
+    let username = params.get("username").map(String::as_str).unwrap_or_default();
+    let hook = match room_hook(&conn, room_id, hook_id, username).await {
+        Ok(hook) => hook,
+        Err(e) => return fail(e),
+    };
+    match hook.delete(&conn).await {
+        Ok(_) => Json(ApiResponse { success: 1, error: None, data: Some(hook_id) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+fn reject(status: StatusCode, error: impl ToString) -> Response {
+    (status, Json(SendResponse { success: 0, error: Some(error.to_string()), chat: None, ..Default::default() })).into_response()
+}
+
+/// POST /hooks/{token}: 외부 시스템이 방에 메시지를 올린다. 인증은 주소의 토큰으로만 한다.
+/// 메시지는 연동 이름으로 남고 일반 전송과 같이 검증, 저장, 브로드캐스트된다.
+pub async fn receive(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    State(limiter): State<HookLimiter>,
+    Path(token): Path<String>,
+    Json(payload): Json<HookPayload>,
+) -> Response {
+    let hook = match HookEntity::find().filter(Column::TokenHash.eq(hash_token(&token))).one(&conn).await {
+        Ok(Some(hook)) => hook,
+        Ok(None) => return reject(StatusCode::NOT_FOUND, "webhook을 찾을 수 없습니다."),
+        Err(e) => return reject(StatusCode::INTERNAL_SERVER_ERROR, format!("DB 오류: {}", e)),
+    };
+    if let Err(wait) = limiter.allow(hook.id) {
+        let mut resp = reject(StatusCode::TOO_MANY_REQUESTS, "요청이 너무 많습니다. 잠시 후 다시 보내세요.");
+        resp.headers_mut().insert(header::RETRY_AFTER, (wait.as_secs() + 1).into());
+        return resp;
+    }
+    // 만든 뒤에 같은 이름의 사용자가 가입했거나 방에 들어왔을 수 있으므로 올릴 때마다 다시 확인한다
+    if let Err(e) = check_name(&conn, hook.room_id, &hook.name).await {
+        return reject(StatusCode::CONFLICT, e);
+    }
+    let text = match render(&payload) {
+        Ok(text) => text,
+        Err(e) => return reject(StatusCode::BAD_REQUEST, e),
+    };
+    let new_message = NewMessage {
+        sender: hook.name.clone(),
+        room_id: hook.room_id,
+        content: MessageContent::Text { text, format: TextFormat::Markdown, html: None },
+        client_msg_id: None,
+    };
+    if let Err(e) = validate(&new_message) {
+        return reject(StatusCode::BAD_REQUEST, e);
+    }
+    if let Err(rejection) = validation::check(&conn, &new_message, &[]).await {
+        return (StatusCode::BAD_REQUEST, Json(SendResponse::rejected(rejection))).into_response();
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let saved = async {
+        let txn = conn.begin().await.map_err(|e| e.to_string())?;
+        let chat = insert_external_message(&txn, &new_message).await?;
+        let mut chat: ActiveChat = chat.into();
+        chat.webhook_id = ActiveValue::Set(Some(hook.id));
+        let chat = chat.update(&txn).await.map_err(|e| e.to_string())?;
+        let mut used: ActiveModel = hook.into();
+        used.last_used_at = ActiveValue::Set(Some(now));
+        used.update(&txn).await.map_err(|e| e.to_string())?;
+        txn.commit().await.map_err(|e| e.to_string())?;
+        Ok::<_, String>(chat)
+    }
+    .await;
+    let chat = match saved {
+        Ok(chat) => chat,
+        Err(e) => return reject(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    outgoing::publish(&conn, &queue, ChatEvent::Message(chat.clone())).await;
+    preview::spawn_for_message(conn.clone(), queue.clone(), chat.clone());
+    Json(SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() }).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_text_fields_and_attachments() {
+        let payload = HookPayload {
+            text: Some("빌드 완료".to_string()),
+            fields: vec![HookField { title: "브랜치".to_string(), value: "main".to_string() }],
+            attachments: vec![HookAttachment {
+                title: Some("#42".to_string()),
+                title_link: Some("https://ci.example.com/42".to_string()),
+                text: Some("테스트 120개 통과".to_string()),
+                fields: vec![HookField { title: "시간".to_string(), value: "3분".to_string() }],
+            }],
+        };
+        assert_eq!(
+            render(&payload).unwrap(),
+            "빌드 완료\n\n- **브랜치**: main\n\n**[#42](https://ci.example.com/42)**\n테스트 120개 통과\n- **시간**: 3분"
+        );
+        assert!(render(&HookPayload::default()).is_err());
+        // http(s)가 아닌 링크는 제목만 남긴다
+        let payload = HookPayload {
+            attachments: vec![HookAttachment { title: Some("x".to_string()), title_link: Some("javascript:alert(1)".to_string()), ..Default::default() }],
+            ..Default::default()
+        };
+        assert_eq!(render(&payload).unwrap(), "**x**");
+    }
+
+    #[test]
+    fn limits_each_hook_separately() {
+        let limiter = HookLimiter::default();
+        let start = Instant::now();
+        for _ in 0..MAX_PER_WINDOW {
+            assert!(limiter.allow_at(1, start).is_ok());
+        }
+        assert!(limiter.allow_at(1, start + Duration::from_secs(10)).is_err_and(|wait| wait == Duration::from_secs(50)));
+        assert!(limiter.allow_at(2, start).is_ok());
+        assert!(limiter.allow_at(1, start + WINDOW).is_ok());
+    }
+}
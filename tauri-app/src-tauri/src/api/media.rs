@@ -0,0 +1,339 @@
+use axum::{
+    extract::{Multipart, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast;
+
+use crate::content::MessageContent;
+use crate::entities::{
+    attachment::{ActiveModel as ActiveAttachment, Column as AttachmentCol, Entity as AttachmentEntity, Model as Attachment},
+    attachment_token::{ActiveModel as ActiveToken, Column as TokenCol, Entity as TokenEntity},
+    chat::Entity as ChatEntity,
+    room::Entity as RoomEntity,
+};
+use crate::media::{store, visual};
+use crate::validation::AttachmentMeta;
+
+use super::bot;
+use super::chat::{insert_message, NewMessage, SendResponse};
+use super::chat_room::participants_of;
+use super::event::ChatEvent;
+use super::outgoing;
+use super::validation;
+
+/// 1회용 열람 토큰의 유효 시간
+const TOKEN_TTL_SECS: i64 = 60;
+// 이미 열어본 한 번 보기 첨부 대신 보여주는 문구
+const VIEWED_PLACEHOLDER: &str = "열어본 미디어입니다.";
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenRequest {
+    pub username: String,
+}
+
+#[derive(Serialize)]
+pub struct OpenToken {
+    /// 한 번만 쓸 수 있는 다운로드 주소
+    pub url: String,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+fn fail(error: impl ToString) -> Json<SendResponse> {
+    Json(SendResponse { success: 0, error: Some(error.to_string()), chat: None, ..Default::default() })
+}
+
+/// multipart 필드: sender, room_id, file, view_once(true/false)
+pub async fn send_media(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Json<SendResponse> {
+    let mut sender = String::new();
+    let mut room_id: Option<i32> = None;
+    let mut view_once = false;
+    let mut file: Option<Vec<u8>> = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return fail(format!("업로드를 읽을 수 없습니다: {}", e)),
+        };
+        match field.name().unwrap_or_default() {
+            "sender" => sender = field.text().await.unwrap_or_default(),
+            "room_id" => room_id = field.text().await.ok().and_then(|v| v.trim().parse().ok()),
+            "view_once" => view_once = field.text().await.is_ok_and(|v| matches!(v.trim(), "true" | "1")),
+            "file" => match field.bytes().await {
+                Ok(bytes) => file = Some(bytes.to_vec()),
+                Err(_) => return fail(visual::VisualError::TooLarge),
+            },
+            _ => {}
+        }
+    }
+
+    // 입력값 검증
+    let (Some(room_id), Some(file)) = (room_id, file) else {
+        return fail("room_id와 파일이 필요합니다.");
+    };
+    if sender.trim().is_empty() {
+        return fail("보내는 사람을 입력하세요.");
+    }
+    // 봇 계정으로 보내려면 그 봇의 API 토큰이 있어야 한다
+    if let Err(e) = bot::authorize_sender(&conn, &headers, &sender).await {
+        return fail(e);
+    }
+    let format = match visual::validate(&file) {
+        Ok(format) => format,
+        Err(e) => return fail(e),
+    };
+
+    let new_message = NewMessage {
+        sender,
+        room_id,
+        content: match format.kind() {
+            "image" => MessageContent::Image { view_once },
+            _ => MessageContent::Video { view_once },
+        },
+        client_msg_id: None,
+    };
+    let meta = AttachmentMeta { kind: format.kind().to_string(), size: file.len() as u64 };
+    if let Err(rejection) = validation::check(&conn, &new_message, &[meta]).await {
+        return Json(SendResponse::rejected(rejection));
+    }
+
+    let storage_key = match store::save(&file, format.extension()).await {
+        Ok(key) => key,
+        Err(_) => return fail("파일 저장에 실패했습니다."),
+    };
+    // 첨부 없는 메시지가 남지 않도록 메시지와 첨부를 한 트랜잭션에서 저장
+    let created = async {
+        let txn = conn.begin().await.map_err(|e| format!("DB 오류: {}", e))?;
+        let chat = insert_message(&txn, &new_message).await?;
+        ActiveAttachment {
+            id: ActiveValue::NotSet,
+            chat_id: ActiveValue::Set(chat.id),
+            kind: ActiveValue::Set(format.kind().to_string()),
+            mime: ActiveValue::Set(format.mime().to_string()),
+            storage_key: ActiveValue::Set(storage_key.clone()),
+            size: ActiveValue::Set(file.len() as i64),
+            duration_ms: ActiveValue::Set(None),
+            waveform: ActiveValue::Set(None),
+            created_at: ActiveValue::Set(chrono::Utc::now().naive_utc()),
+            view_once: ActiveValue::Set(view_once),
+            viewed_at: ActiveValue::Set(None),
+            viewed_by: ActiveValue::Set(None),
+        }
+        .insert(&txn)
+        .await
+        .map_err(|_| "메시지 저장에 실패했습니다.".to_string())?;
+        txn.commit().await.map_err(|e| format!("DB 오류: {}", e))?;
+        Ok::<_, String>(chat)
+    }
+    .await;
+    let chat = match created {
+        Ok(chat) => chat,
+        Err(e) => {
+            let _ = store::remove(&storage_key).await;
+            return fail(e);
+        }
+    };
+
+    outgoing::publish(&conn, &queue, ChatEvent::Message(chat.clone())).await;
+    Json(SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() })
+}
+
+/// POST /chat/attachment/{id}/open: 한 번 보기 첨부의 1회용 다운로드 주소를 발급한다.
+/// 보낸 사람은 열 수 없고, 같은 방의 수신자만 받을 수 있다.
+pub async fn open(
+    State(conn): State<DatabaseConnection>,
+    Path(id): Path<i32>,
+    Json(req): Json<OpenRequest>,
+) -> Json<ApiResponse<OpenToken>> {
+    let fail = |e: &str| Json(ApiResponse { success: 0, error: Some(e.to_string()), data: None });
+    let attachment = match AttachmentEntity::find_by_id(id).one(&conn).await {
+        Ok(Some(a)) if a.view_once => a,
+        Ok(Some(_)) => return fail("한 번 보기 첨부가 아닙니다."),
+        Ok(None) => return fail("첨부를 찾을 수 없습니다."),
+        Err(_) => return fail("DB 오류가 발생했습니다."),
+    };
+    if attachment.viewed_at.is_some() {
+        return fail(VIEWED_PLACEHOLDER);
+    }
+    let chat = match ChatEntity::find_by_id(attachment.chat_id).one(&conn).await {
+        Ok(Some(chat)) => chat,
+        _ => return fail("첨부를 찾을 수 없습니다."),
+    };
+    let is_member = match RoomEntity::find_by_id(chat.room_id).one(&conn).await {
+        Ok(Some(room)) => participants_of(&room).contains(&req.username),
+        _ => false,
+    };
+    if !is_member || chat.sender == req.username {
+        return fail("열 수 있는 권한이 없습니다.");
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let _ = TokenEntity::delete_many()
+        .filter(TokenCol::AttachmentId.eq(id))
+        .filter(TokenCol::ExpiresAt.lte(now))
+        .exec(&conn)
+        .await;
+    let token = store::random_hex(24);
+    let expires_at = now + chrono::Duration::seconds(TOKEN_TTL_SECS);
+    let row = ActiveToken {
+        token: ActiveValue::Set(token.clone()),
+        attachment_id: ActiveValue::Set(id),
+        username: ActiveValue::Set(req.username),
+        expires_at: ActiveValue::Set(expires_at),
+    };
+    if row.insert(&conn).await.is_err() {
+        return fail("DB 오류가 발생했습니다.");
+    }
+    Json(ApiResponse {
+        success: 1,
+        error: None,
+        data: Some(OpenToken { url: format!("/api/chat/attachment/{}?token={}", id, token), expires_at }),
+    })
+}
+
+fn viewed_response() -> Response {
+    (StatusCode::GONE, Json(json!({ "placeholder": VIEWED_PLACEHOLDER }))).into_response()
+}
+
+/// 한 번 보기 첨부 다운로드. 토큰 소비와 열람 기록을 한 트랜잭션에서 처리하므로
+/// 같은 토큰이나 다른 토큰으로 동시에 요청해도 파일은 한 번만 내려간다.
+pub async fn consume(
+    conn: &DatabaseConnection,
+    queue: &broadcast::Sender<ChatEvent>,
+    attachment: Attachment,
+    token: Option<&String>,
+) -> Response {
+    if attachment.viewed_at.is_some() {
+        return viewed_response();
+    }
+    let Some(token) = token else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    let now = chrono::Utc::now().naive_utc();
+
+    let claimed = async {
+        let txn = conn.begin().await?;
+        let mut tokens = TokenEntity::delete_many()
+            .filter(TokenCol::Token.eq(token.as_str()))
+            .filter(TokenCol::AttachmentId.eq(attachment.id))
+            .filter(TokenCol::ExpiresAt.gt(now))
+            .exec_with_returning(&txn)
+            .await?;
+        let Some(viewer) = tokens.pop() else {
+            txn.rollback().await?;
+            return Ok(None);
+        };
+        let marked = AttachmentEntity::update_many()
+            .col_expr(AttachmentCol::ViewedAt, Expr::value(now))
+            .col_expr(AttachmentCol::ViewedBy, Expr::value(viewer.username.clone()))
+            .filter(AttachmentCol::Id.eq(attachment.id))
+            .filter(AttachmentCol::ViewedAt.is_null())
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok::<_, sea_orm::DbErr>(Some((viewer.username, marked.rows_affected == 1)))
+    }
+    .await;
+    let viewer = match claimed {
+        Ok(Some((viewer, true))) => viewer,
+        // 다른 요청이 먼저 열었음
+        Ok(Some((_, false))) => return viewed_response(),
+        Ok(None) => return StatusCode::FORBIDDEN.into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    let bytes = match store::take(&attachment.storage_key).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("view-once blob {} missing: {}", attachment.storage_key, e);
+            return viewed_response();
+        }
+    };
+    if let Ok(Some(chat)) = ChatEntity::find_by_id(attachment.chat_id).one(conn).await {
+        let _ = queue.send(ChatEvent::Viewed {
+            room_id: chat.room_id,
+            chat_id: chat.id,
+            attachment_id: attachment.id,
+            username: viewer,
+            viewed_at: now,
+        });
+    }
+    (
+        [(header::CONTENT_TYPE, attachment.mime), (header::CACHE_CONTROL, "no-store".to_string())],
+        bytes,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use axum::{body::Body, extract::FromRequest, http::Request};
+    use sea_orm::{Database, DbBackend, DbErr, ProxyDatabaseTrait, ProxyExecResult, ProxyRow, Statement, Value};
+
+    // 어떤 조회에도 봇 계정 한 줄을 돌려주는 DB. 쓰기는 모두 실패한다.
+    #[derive(Debug)]
+    struct BotOnly;
+
+    #[async_trait::async_trait]
+    impl ProxyDatabaseTrait for BotOnly {
+        async fn query(&self, _: Statement) -> Result<Vec<ProxyRow>, DbErr> {
+            let values = [
+                ("id", Value::from(1)),
+                ("username", Value::from("deploy-bot")),
+                ("password", Value::from("")),
+                ("display_name", Value::String(None)),
+                ("status", Value::String(None)),
+                ("avatar", Value::String(None)),
+                ("is_bot", Value::from(true)),
+                ("owner", Value::from("alice")),
+            ];
+            Ok(vec![ProxyRow { values: values.into_iter().map(|(k, v)| (k.to_string(), v)).collect() }])
+        }
+
+        async fn execute(&self, _: Statement) -> Result<ProxyExecResult, DbErr> {
+            Err(DbErr::Custom("read only".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn bot_sender_needs_its_token() {
+        let conn = Database::connect_proxy(DbBackend::Postgres, Arc::new(Box::new(BotOnly))).await.unwrap();
+        let body = "--X\r\nContent-Disposition: form-data; name=\"sender\"\r\n\r\ndeploy-bot\r\n\
+                    --X\r\nContent-Disposition: form-data; name=\"room_id\"\r\n\r\n1\r\n\
+                    --X\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.png\"\r\n\r\nnot an image\r\n\
+                    --X--\r\n";
+        let request = Request::builder()
+            .method("POST")
+            .header(header::CONTENT_TYPE, "multipart/form-data; boundary=X")
+            .body(Body::from(body))
+            .unwrap();
+        let multipart = Multipart::from_request(request, &()).await.unwrap();
+
+        let Json(response) = send_media(State(conn), State(broadcast::channel(1).0), HeaderMap::new(), multipart).await;
+        assert_eq!(response.success, 0);
+        assert_eq!(response.error.as_deref(), Some("봇으로 보내려면 API 토큰이 필요합니다."));
+    }
+}
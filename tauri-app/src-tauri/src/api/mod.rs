@@ -4,3 +4,24 @@ pub mod state;
 pub mod user;
 pub mod friend;
 pub mod profile;
+pub mod attachment;
+pub mod voice;
+pub mod event;
+pub mod search;
+pub mod delivery;
+pub mod typing;
+pub mod schedule;
+pub mod retention;
+pub mod media;
+pub mod forward;
+pub mod poll;
+pub mod code;
+pub mod draft;
+pub mod bookmark;
+pub mod validation;
+pub mod command;
+pub mod bot;
+pub mod incoming;
+pub mod outgoing;
+pub mod export;
+pub mod import;
@@ -0,0 +1,587 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{NaiveDateTime, Utc};
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac};
+use reqwest::header;
+use sea_orm::{
+    sea_query::{Expr, LockBehavior, LockType},
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use tokio::sync::broadcast;
+
+use crate::entities::{
+    outgoing_webhook::{ActiveModel, Column, Entity as OutgoingEntity, Model as Outgoing},
+    webhook_attempt::{self, Entity as AttemptEntity, Model as Attempt},
+    webhook_delivery::{self, Entity as DeliveryEntity, Model as Delivery},
+};
+use crate::media::store;
+use crate::preview::fetch::{self, FetchError, FetchPolicy};
+
+use super::chat_room::member_room;
+use super::event::ChatEvent;
+
+pub const PENDING: &str = "pending";
+pub const DELIVERED: &str = "delivered";
+/// 재시도를 다 쓴 전달. 로그에서 보고 손으로 다시 보낼 수 있다.
+pub const DEAD: &str = "dead";
+
+/// 받을 수 있는 이벤트.
+/// message.edited는 아직 메시지 수정 기능이 없어 만들어질 일이 없으므로 빼 두었다. 수정이 생기면 event_name과 함께 추가한다.
+pub const EVENTS: [&str; 4] = ["message.created", "message.deleted", "member.joined", "member.left"];
+
+/// 전달기가 큐를 확인하는 주기
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// 한 번에 잡는 최대 건수
+const BATCH: u64 = 20;
+/// 잡은 전달을 다른 전달기가 다시 잡지 않도록 미뤄 두는 시간 (보내다 죽으면 이 뒤에 다시 시도)
+const LEASE_SECS: i64 = 60;
+const TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_ATTEMPTS: i32 = 8;
+const BASE_DELAY_SECS: i64 = 10;
+const MAX_DELAY_SECS: i64 = 60 * 60;
+const DEFAULT_LOG_LIMIT: u64 = 50;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+fn fail<T>(error: impl Into<String>) -> Json<ApiResponse<T>> {
+    Json(ApiResponse { success: 0, error: Some(error.into()), data: None })
+}
+
+#[derive(Deserialize)]
+pub struct NewOutgoing {
+    pub username: String,
+    pub url: String,
+    /// 없으면 전부
+    #[serde(default)]
+    pub events: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct OutgoingEdit {
+    pub username: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub events: Option<Vec<String>>,
+    #[serde(default)]
+    pub active: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct OutgoingMember {
+    pub username: String,
+}
+
+#[derive(Serialize)]
+pub struct OutgoingView {
+    pub id: i32,
+    pub room_id: i32,
+    pub url: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<Outgoing> for OutgoingView {
+    fn from(hook: Outgoing) -> Self {
+        OutgoingView {
+            events: serde_json::from_str(&hook.events).unwrap_or_default(),
+            id: hook.id,
+            room_id: hook.room_id,
+            url: hook.url,
+            active: hook.active,
+            created_by: hook.created_by,
+            created_at: hook.created_at,
+        }
+    }
+}
+
+/// 서명 키는 만들 때와 새로 발급할 때만 보여준다
+#[derive(Serialize)]
+pub struct OutgoingWithSecret {
+    pub webhook: OutgoingView,
+    pub secret: String,
+}
+
+/// 전달 기록 한 건과 시도 내역
+#[derive(Serialize)]
+pub struct DeliveryLog {
+    #[serde(flatten)]
+    pub delivery: Delivery,
+    pub log: Vec<Attempt>,
+}
+
+/// ChatEvent → webhook 이벤트 이름 (보내지 않는 이벤트는 None)
+fn event_name(event: &ChatEvent) -> Option<&'static str> {
+    match event {
+        ChatEvent::Message(_) => Some("message.created"),
+        ChatEvent::Deleted { .. } => Some("message.deleted"),
+        ChatEvent::MemberJoined { .. } => Some("member.joined"),
+        ChatEvent::MemberLeft { .. } => Some("member.left"),
+        _ => None,
+    }
+}
+
+/// `X-Webhook-Signature` 값. 타임스탬프를 함께 서명해서 오래된 요청을 다시 보내는 것을 막는다.
+/// 받는 쪽은 `{timestamp}.{본문}`을 같은 키로 HMAC-SHA256 해서 비교하면 된다.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    let digest: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", digest)
+}
+
+/// attempts번 실패한 뒤 다음 시도까지 기다릴 시간 (두 배씩, 최대 1시간)
+fn backoff(attempts: i32) -> chrono::Duration {
+    let secs = BASE_DELAY_SECS.saturating_mul(1i64 << (attempts - 1).clamp(0, 20));
+    chrono::Duration::seconds(secs.min(MAX_DELAY_SECS))
+}
+
+fn parse_events(events: Option<&[String]>) -> Result<String, String> {
+    let Some(events) = events else {
+        return Ok(serde_json::to_string(&EVENTS).unwrap());
+    };
+    let mut out: Vec<&str> = Vec::new();
+    for event in events.iter().map(|e| e.trim()) {
+        if !EVENTS.contains(&event) {
+            return Err(format!("알 수 없는 이벤트 '{}' (가능: {})", event, EVENTS.join(", ")));
+        }
+        if !out.contains(&event) {
+            out.push(event);
+        }
+    }
+    if out.is_empty() {
+        return Err("받을 이벤트를 하나 이상 고르세요.".to_string());
+    }
+    Ok(serde_json::to_string(&out).unwrap())
+}
+
+fn check_url(url: &str) -> Result<(), String> {
+    match url::Url::parse(url) {
+        Ok(u) if u.scheme() == "http" || u.scheme() == "https" => Ok(()),
+        _ => Err("주소는 http(s) URL이어야 합니다.".to_string()),
+    }
+}
+
+fn new_secret() -> String {
+    format!("whsec_{}", store::random_hex(24))
+}
+
+/// 이벤트를 구독한 webhook마다 전달을 큐에 넣는다
+async fn enqueue(conn: &DatabaseConnection, event: &ChatEvent) -> Result<(), DbErr> {
+    let Some(name) = event_name(event) else {
+        return Ok(());
+    };
+    let hooks = OutgoingEntity::find()
+        .filter(Column::RoomId.eq(event.room_id()))
+        .filter(Column::Active.eq(true))
+        .all(conn)
+        .await?;
+    let now = Utc::now().naive_utc();
+    let payload = json!({
+        "event": name,
+        "room_id": event.room_id(),
+        "occurred_at": now,
+        "data": event.data()
+    })
+    .to_string();
+    let rows: Vec<webhook_delivery::ActiveModel> = hooks
+        .into_iter()
+        .filter(|hook| serde_json::from_str::<Vec<String>>(&hook.events).is_ok_and(|events| events.iter().any(|e| e == name)))
+        .map(|hook| webhook_delivery::ActiveModel {
+            id: ActiveValue::NotSet,
+            webhook_id: ActiveValue::Set(hook.id),
+            event: ActiveValue::Set(name.to_string()),
+            payload: ActiveValue::Set(payload.clone()),
+            status: ActiveValue::Set(PENDING.to_string()),
+            attempts: ActiveValue::Set(0),
+            next_attempt_at: ActiveValue::Set(now),
+            created_at: ActiveValue::Set(now),
+            delivered_at: ActiveValue::Set(None),
+        })
+        .collect();
+    if !rows.is_empty() {
+        DeliveryEntity::insert_many(rows).exec(conn).await?;
+    }
+    Ok(())
+}
+
+/// 실시간 구독자에게 이벤트를 보내고, 방에 설정된 외부 webhook 전달도 큐에 넣는다.
+/// 큐에 넣지 못해도 실시간 전송은 한다.
+pub async fn publish(conn: &DatabaseConnection, queue: &broadcast::Sender<ChatEvent>, event: ChatEvent) {
+    if let Err(e) = enqueue(conn, &event).await {
+        eprintln!("webhook enqueue for room {} failed: {}", event.room_id(), e);
+    }
+    let _ = queue.send(event);
+}
+
+/// 때가 된 전달을 잡아 LEASE 동안 다른 전달기가 못 잡게 미뤄 둔다
+async fn claim(conn: &DatabaseConnection) -> Result<Vec<(Delivery, Outgoing)>, DbErr> {
+    let now = Utc::now().naive_utc();
+    let txn = conn.begin().await?;
+    let due = DeliveryEntity::find()
+        .filter(webhook_delivery::Column::Status.eq(PENDING))
+        .filter(webhook_delivery::Column::NextAttemptAt.lte(now))
+        .order_by_asc(webhook_delivery::Column::NextAttemptAt)
+        .limit(BATCH)
+        .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+        .all(&txn)
+        .await?;
+    if due.is_empty() {
+        txn.commit().await?;
+        return Ok(vec![]);
+    }
+    DeliveryEntity::update_many()
+        .col_expr(webhook_delivery::Column::NextAttemptAt, Expr::value(now + chrono::Duration::seconds(LEASE_SECS)))
+        .filter(webhook_delivery::Column::Id.is_in(due.iter().map(|d| d.id)))
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+
+    let hooks: HashMap<i32, Outgoing> = OutgoingEntity::find()
+        .filter(Column::Id.is_in(due.iter().map(|d| d.webhook_id)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|hook| (hook.id, hook))
+        .collect();
+    Ok(due.into_iter().filter_map(|d| hooks.get(&d.webhook_id).cloned().map(|hook| (d, hook))).collect())
+}
+
+// 전달 기록은 방 참가자에게 보이므로 내부 주소나 연결 세부가 담긴 원래 오류 대신 종류만 남긴다
+fn delivery_error(e: &FetchError) -> String {
+    match e {
+        FetchError::InvalidUrl => "잘못된 주소입니다.",
+        FetchError::Blocked(_) => "내부망 주소로는 보낼 수 없습니다.",
+        FetchError::Timeout => "응답 시간이 초과되었습니다.",
+        FetchError::NotHtml | FetchError::Http(_) => "주소를 확인할 수 없습니다.",
+    }
+    .to_string()
+}
+
+fn send_error(e: &reqwest::Error) -> String {
+    if e.is_timeout() {
+        "응답 시간이 초과되었습니다."
+    } else if e.is_connect() {
+        "연결할 수 없습니다."
+    } else {
+        "요청을 보내지 못했습니다."
+    }
+    .to_string()
+}
+
+// 서명해서 보내고 (상태 코드, 오류)를 돌려준다. 리다이렉트는 따라가지 않으므로 3xx도 실패다.
+async fn send(http: &reqwest::Client, url: url::Url, delivery: &Delivery, hook: &Outgoing) -> (Option<i32>, Option<String>) {
+    let timestamp = Utc::now().timestamp();
+    let sent = http
+        .post(url)
+        .timeout(TIMEOUT)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Webhook-Event", delivery.event.as_str())
+        .header("X-Webhook-Delivery", delivery.id.to_string())
+        .header("X-Webhook-Timestamp", timestamp.to_string())
+        .header("X-Webhook-Signature", sign(&hook.secret, timestamp, &delivery.payload))
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+    match sent {
+        Ok(resp) if resp.status().is_success() => (Some(resp.status().as_u16() as i32), None),
+        Ok(resp) => (Some(resp.status().as_u16() as i32), Some(format!("HTTP {}", resp.status()))),
+        Err(e) => (None, Some(send_error(&e))),
+    }
+}
+
+/// 한 번 보내 보고 결과를 기록한다
+async fn attempt(conn: &DatabaseConnection, addresses: &FetchPolicy, delivery: Delivery, hook: Outgoing) -> Result<(), DbErr> {
+    let started = Instant::now();
+    let (status_code, error) = if !hook.active {
+        (None, Some("webhook이 꺼져 있습니다.".to_string()))
+    } else {
+        match fetch::pinned_client(&hook.url, addresses).await {
+            Err(e) => (None, Some(delivery_error(&e))),
+            Ok((http, url)) => send(&http, url, &delivery, &hook).await,
+        }
+    };
+    let attempts = delivery.attempts + 1;
+    let now = Utc::now().naive_utc();
+    webhook_attempt::ActiveModel {
+        id: ActiveValue::NotSet,
+        delivery_id: ActiveValue::Set(delivery.id),
+        attempt: ActiveValue::Set(attempts),
+        status_code: ActiveValue::Set(status_code),
+        error: ActiveValue::Set(error.clone()),
+        duration_ms: ActiveValue::Set(started.elapsed().as_millis() as i64),
+        attempted_at: ActiveValue::Set(now),
+    }
+    .insert(conn)
+    .await?;
+
+    let mut row: webhook_delivery::ActiveModel = delivery.into();
+    row.attempts = ActiveValue::Set(attempts);
+    match error {
+        None => {
+            row.status = ActiveValue::Set(DELIVERED.to_string());
+            row.delivered_at = ActiveValue::Set(Some(now));
+        }
+        Some(_) if !hook.active || attempts >= MAX_ATTEMPTS => row.status = ActiveValue::Set(DEAD.to_string()),
+        Some(_) => row.next_attempt_at = ActiveValue::Set(now + backoff(attempts)),
+    }
+    row.update(conn).await?;
+    Ok(())
+}
+
+/// 외부 webhook 전달 루프. run_async에서 서버와 함께 띄운다.
+/// 큐가 DB에 있으므로 서버가 꺼져 있던 동안 쌓인 전달도 다시 켜지면 보낸다.
+pub async fn run_worker(conn: DatabaseConnection) {
+    // 내부망이어도 보낼 호스트는 WEBHOOK_ALLOWLIST에 (링크 미리보기와 같은 주소 검사)
+    let addresses = FetchPolicy { timeout: TIMEOUT, ..FetchPolicy::from_env_var("WEBHOOK_ALLOWLIST") };
+    let mut tick = tokio::time::interval(POLL_INTERVAL);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        let jobs = match claim(&conn).await {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                eprintln!("webhook queue claim failed: {e}");
+                continue;
+            }
+        };
+        for result in join_all(jobs.into_iter().map(|(delivery, hook)| attempt(&conn, &addresses, delivery, hook))).await {
+            if let Err(e) = result {
+                eprintln!("webhook delivery bookkeeping failed: {e}");
+            }
+        }
+    }
+}
+
+async fn room_hook(conn: &DatabaseConnection, room_id: i32, hook_id: i32, username: &str) -> Result<Outgoing, String> {
+    member_room(conn, room_id, username).await?;
+    match OutgoingEntity::find_by_id(hook_id).one(conn).await {
+        Ok(Some(hook)) if hook.room_id == room_id => Ok(hook),
+        Ok(_) => Err("webhook을 찾을 수 없습니다.".to_string()),
+        Err(e) => Err(format!("DB 오류: {}", e)),
+    }
+}
+
+/// POST /room/{id}/outgoing: 방 이벤트를 받을 주소를 등록한다
+pub async fn create(
+    State(conn): State<DatabaseConnection>,
+    Path(room_id): Path<i32>,
+    Json(req): Json<NewOutgoing>,
+) -> Json<ApiResponse<OutgoingWithSecret>> {
+    let url = req.url.trim().to_string();
+    if let Err(e) = check_url(&url) {
+        return fail(e);
+    }
+    let events = match parse_events(req.events.as_deref()) {
+        Ok(events) => events,
+        Err(e) => return fail(e),
+    };
+    if let Err(e) = member_room(&conn, room_id, &req.username).await {
+        return fail(e);
+    }
+    let secret = new_secret();
+    let row = ActiveModel {
+        id: ActiveValue::NotSet,
+        room_id: ActiveValue::Set(room_id),
+        url: ActiveValue::Set(url),
+        secret: ActiveValue::Set(secret.clone()),
+        events: ActiveValue::Set(events),
+        active: ActiveValue::Set(true),
+        created_by: ActiveValue::Set(req.username),
+        created_at: ActiveValue::Set(Utc::now().naive_utc()),
+    };
+    match row.insert(&conn).await {
+        Ok(hook) => Json(ApiResponse { success: 1, error: None, data: Some(OutgoingWithSecret { webhook: hook.into(), secret }) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// GET /room/{id}/outgoing?username=
+pub async fn list(
+    State(conn): State<DatabaseConnection>,
+    Path(room_id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<Vec<OutgoingView>>> {
+    let username = params.get("username").map(String::as_str).unwrap_or_default();
+    if let Err(e) = member_room(&conn, room_id, username).await {
+        return fail(e);
+    }
+    match OutgoingEntity::find().filter(Column::RoomId.eq(room_id)).order_by_asc(Column::Id).all(&conn).await {
+        Ok(hooks) => Json(ApiResponse { success: 1, error: None, data: Some(hooks.into_iter().map(Into::into).collect()) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// PUT /room/{id}/outgoing/{hook_id}: 주소, 이벤트, 켜고 끄기
+pub async fn edit(
+    State(conn): State<DatabaseConnection>,
+    Path((room_id, hook_id)): Path<(i32, i32)>,
+    Json(req): Json<OutgoingEdit>,
+) -> Json<ApiResponse<OutgoingView>> {
+    let hook = match room_hook(&conn, room_id, hook_id, &req.username).await {
+        Ok(hook) => hook,
+        Err(e) => return fail(e),
+    };
+    let mut row: ActiveModel = hook.into();
+    if let Some(url) = req.url.map(|u| u.trim().to_string()) {
+        if let Err(e) = check_url(&url) {
+            return fail(e);
+        }
+        row.url = ActiveValue::Set(url);
+    }
+    if let Some(events) = req.events {
+        match parse_events(Some(&events)) {
+            Ok(events) => row.events = ActiveValue::Set(events),
+            Err(e) => return fail(e),
+        }
+    }
+    if let Some(active) = req.active {
+        row.active = ActiveValue::Set(active);
+    }
+    match row.update(&conn).await {
+        Ok(hook) => Json(ApiResponse { success: 1, error: None, data: Some(hook.into()) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// POST /room/{id}/outgoing/{hook_id}/secret: 서명 키를 새로 발급한다. 큐에 남은 전달도 새 키로 서명된다.
+pub async fn rotate_secret(
+    State(conn): State<DatabaseConnection>,
+    Path((room_id, hook_id)): Path<(i32, i32)>,
+    Json(req): Json<OutgoingMember>,
+) -> Json<ApiResponse<OutgoingWithSecret>> {
+    let hook = match room_hook(&conn, room_id, hook_id, &req.username).await {
+        Ok(hook) => hook,
+        Err(e) => return fail(e),
+    };
+    let secret = new_secret();
+    let mut row: ActiveModel = hook.into();
+    row.secret = ActiveValue::Set(secret.clone());
+    match row.update(&conn).await {
+        Ok(hook) => Json(ApiResponse { success: 1, error: None, data: Some(OutgoingWithSecret { webhook: hook.into(), secret }) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// DELETE /room/{id}/outgoing/{hook_id}?username= (큐와 기록도 함께 지워진다)
+pub async fn delete(
+    State(conn): State<DatabaseConnection>,
+    Path((room_id, hook_id)): Path<(i32, i32)>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<i32>> {
+    let username = params.get("username").map(String::as_str).unwrap_or_default();
+    let hook = match room_hook(&conn, room_id, hook_id, username).await {
+        Ok(hook) => hook,
+        Err(e) => return fail(e),
+    };
+    match hook.delete(&conn).await {
+        Ok(_) => Json(ApiResponse { success: 1, error: None, data: Some(hook_id) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// GET /room/{id}/outgoing/{hook_id}/deliveries?username=&status=&limit=: 최근 전달 기록 (최신순)
+pub async fn deliveries(
+    State(conn): State<DatabaseConnection>,
+    Path((room_id, hook_id)): Path<(i32, i32)>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<Vec<DeliveryLog>>> {
+    let username = params.get("username").map(String::as_str).unwrap_or_default();
+    if let Err(e) = room_hook(&conn, room_id, hook_id, username).await {
+        return fail(e);
+    }
+    let limit = params.get("limit").and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_LOG_LIMIT).min(200);
+    let mut query = DeliveryEntity::find().filter(webhook_delivery::Column::WebhookId.eq(hook_id));
+    if let Some(status) = params.get("status") {
+        query = query.filter(webhook_delivery::Column::Status.eq(status.as_str()));
+    }
+    let found = query.order_by_desc(webhook_delivery::Column::Id).limit(limit).all(&conn).await;
+    let delivered = match found {
+        Ok(rows) => rows,
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    };
+    let mut logs: HashMap<i32, Vec<Attempt>> = HashMap::new();
+    let attempts = AttemptEntity::find()
+        .filter(webhook_attempt::Column::DeliveryId.is_in(delivered.iter().map(|d| d.id)))
+        .order_by_asc(webhook_attempt::Column::Id)
+        .all(&conn)
+        .await;
+    match attempts {
+        Ok(attempts) => {
+            for attempt in attempts {
+                logs.entry(attempt.delivery_id).or_default().push(attempt);
+            }
+        }
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    }
+    let items = delivered
+        .into_iter()
+        .map(|delivery| DeliveryLog { log: logs.remove(&delivery.id).unwrap_or_default(), delivery })
+        .collect();
+    Json(ApiResponse { success: 1, error: None, data: Some(items) })
+}
+
+/// POST /room/{id}/outgoing/{hook_id}/deliveries/{delivery_id}/retry: 실패한 전달을 처음부터 다시 보낸다
+pub async fn retry(
+    State(conn): State<DatabaseConnection>,
+    Path((room_id, hook_id, delivery_id)): Path<(i32, i32, i32)>,
+    Json(req): Json<OutgoingMember>,
+) -> Json<ApiResponse<Delivery>> {
+    if let Err(e) = room_hook(&conn, room_id, hook_id, &req.username).await {
+        return fail(e);
+    }
+    let delivery = match DeliveryEntity::find_by_id(delivery_id).one(&conn).await {
+        Ok(Some(d)) if d.webhook_id == hook_id && d.status == DEAD => d,
+        Ok(Some(d)) if d.webhook_id == hook_id => return fail("실패한 전달만 다시 보낼 수 있습니다."),
+        Ok(_) => return fail("전달 기록을 찾을 수 없습니다."),
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    };
+    let mut row: webhook_delivery::ActiveModel = delivery.into();
+    row.status = ActiveValue::Set(PENDING.to_string());
+    row.attempts = ActiveValue::Set(0);
+    row.next_attempt_at = ActiveValue::Set(Utc::now().naive_utc());
+    match row.update(&conn).await {
+        Ok(delivery) => Json(ApiResponse { success: 1, error: None, data: Some(delivery) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        // python: hmac.new(b"whsec_test", b'1700000000.{"event":"message.created"}', hashlib.sha256).hexdigest()
+        assert_eq!(
+            sign("whsec_test", 1700000000, r#"{"event":"message.created"}"#),
+            "sha256=9884eb2fcc09ffc10f00127fff0a0c5686da2fef61d0363442271c6dfa1917eb"
+        );
+        assert_ne!(sign("whsec_test", 1700000000, "{}"), sign("whsec_test", 1700000001, "{}"));
+    }
+
+    #[test]
+    fn backs_off_exponentially_up_to_an_hour() {
+        assert_eq!(backoff(1), chrono::Duration::seconds(10));
+        assert_eq!(backoff(2), chrono::Duration::seconds(20));
+        assert_eq!(backoff(4), chrono::Duration::seconds(80));
+        assert_eq!(backoff(MAX_ATTEMPTS + 10), chrono::Duration::seconds(MAX_DELAY_SECS));
+    }
+}
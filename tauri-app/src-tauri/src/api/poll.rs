@@ -0,0 +1,452 @@
+use std::collections::{BTreeSet, HashMap};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::content::MessageContent;
+use crate::entities::{
+    chat::Entity as ChatEntity,
+    poll::{ActiveModel as ActivePoll, Column as PollCol, Entity as PollEntity, Model as Poll},
+    poll_option::{ActiveModel as ActiveOption, Column as OptionCol, Entity as OptionEntity, Model as PollOption},
+    poll_vote::{ActiveModel as ActiveVote, Column as VoteCol, Entity as VoteEntity, Model as PollVote},
+    room::Entity as RoomEntity,
+};
+
+use super::bot;
+use super::chat::{already_sent, insert_message, validate, NewMessage, SendResponse};
+use super::chat_room::participants_of;
+use super::event::ChatEvent;
+use super::outgoing;
+use super::validation;
+
+pub const MAX_OPTIONS: usize = 10;
+const MAX_QUESTION_CHARS: usize = 200;
+const MAX_OPTION_CHARS: usize = 100;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+fn fail<T>(error: impl Into<String>) -> Json<ApiResponse<T>> {
+    Json(ApiResponse { success: 0, error: Some(error.into()), data: None })
+}
+
+#[derive(Deserialize)]
+pub struct NewPoll {
+    pub sender: String,
+    pub room_id: i32,
+    pub question: String,
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub multiple: bool,
+    #[serde(default)]
+    pub anonymous: bool,
+    /// RFC 3339, 시간대 포함. 없으면 계속 열려 있다.
+    #[serde(default)]
+    pub closes_at: Option<DateTime<FixedOffset>>,
+    /// chat/send의 client_msg_id와 같다. 같은 id로 다시 보내면 처음 만든 투표 메시지를 돌려준다.
+    #[serde(default)]
+    pub client_msg_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PollVoteRequest {
+    pub username: String,
+    /// 내 선택을 이 목록으로 바꾼다 (단일 선택이면 1개)
+    pub option_ids: Vec<i32>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PollOptionView {
+    pub id: i32,
+    pub label: String,
+    pub votes: usize,
+    /// 익명 투표면 None
+    pub voters: Option<Vec<String>>,
+}
+
+/// 집계된 투표 결과. get_chat의 메시지와 poll 이벤트에 실린다.
+#[derive(Serialize, Debug, Clone)]
+pub struct PollView {
+    pub id: i32,
+    pub chat_id: i32,
+    pub question: String,
+    pub multiple: bool,
+    pub anonymous: bool,
+    pub closes_at: Option<NaiveDateTime>,
+    pub closed: bool,
+    /// 한 번이라도 투표한 사람 수
+    pub total_voters: usize,
+    pub options: Vec<PollOptionView>,
+    /// 요청한 사람이 고른 선택지 (username을 준 조회에서만).
+    /// 익명 투표는 username만 바꿔 남의 선택을 볼 수 있으므로 본인이 투표·취소한 응답에만 넣는다.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_votes: Option<Vec<i32>>,
+}
+
+/// 선택지별 득표를 센다. options는 position 순으로 정렬되어 있어야 한다.
+pub fn tally(poll: &Poll, options: &[PollOption], votes: &[PollVote], viewer: Option<&str>, now: NaiveDateTime) -> PollView {
+    let voters: BTreeSet<&str> = votes.iter().map(|v| v.username.as_str()).collect();
+    let options = options
+        .iter()
+        .map(|option| {
+            let names: Vec<String> = votes
+                .iter()
+                .filter(|v| v.option_id == option.id)
+                .map(|v| v.username.clone())
+                .collect();
+            PollOptionView {
+                id: option.id,
+                label: option.label.clone(),
+                votes: names.len(),
+                voters: (!poll.anonymous).then_some(names),
+            }
+        })
+        .collect();
+    PollView {
+        id: poll.id,
+        chat_id: poll.chat_id,
+        question: poll.question.clone(),
+        multiple: poll.multiple,
+        anonymous: poll.anonymous,
+        closes_at: poll.closes_at,
+        closed: poll.closes_at.is_some_and(|t| t <= now),
+        total_voters: voters.len(),
+        options,
+        my_votes: viewer.map(|me| votes.iter().filter(|v| v.username == me).map(|v| v.option_id).collect()),
+    }
+}
+
+/// get_chat에서 쓰는 메시지별 투표 결과
+pub async fn load_for_chats(conn: &DatabaseConnection, chat_ids: &[i32], viewer: Option<&str>) -> HashMap<i32, PollView> {
+    if chat_ids.is_empty() {
+        return HashMap::new();
+    }
+    let polls = PollEntity::find()
+        .filter(PollCol::ChatId.is_in(chat_ids.to_vec()))
+        .all(conn)
+        .await
+        .unwrap_or_default();
+    if polls.is_empty() {
+        return HashMap::new();
+    }
+    let poll_ids: Vec<i32> = polls.iter().map(|p| p.id).collect();
+    let options = OptionEntity::find()
+        .filter(OptionCol::PollId.is_in(poll_ids.clone()))
+        .order_by_asc(OptionCol::Position)
+        .all(conn)
+        .await
+        .unwrap_or_default();
+    let votes = VoteEntity::find()
+        .filter(VoteCol::PollId.is_in(poll_ids))
+        .all(conn)
+        .await
+        .unwrap_or_default();
+
+    let now = Utc::now().naive_utc();
+    polls
+        .iter()
+        .map(|poll| {
+            let options: Vec<PollOption> = options.iter().filter(|o| o.poll_id == poll.id).cloned().collect();
+            let votes: Vec<PollVote> = votes.iter().filter(|v| v.poll_id == poll.id).cloned().collect();
+            let viewer = viewer.filter(|_| !poll.anonymous);
+            (poll.chat_id, tally(poll, &options, &votes, viewer, now))
+        })
+        .collect()
+}
+
+// 현재 집계를 방에 알리고, 요청한 사람 기준 결과를 돌려준다
+async fn publish(
+    conn: &DatabaseConnection,
+    queue: &broadcast::Sender<ChatEvent>,
+    poll: &Poll,
+    room_id: i32,
+    viewer: &str,
+) -> Result<PollView, DbErr> {
+    let options = OptionEntity::find()
+        .filter(OptionCol::PollId.eq(poll.id))
+        .order_by_asc(OptionCol::Position)
+        .all(conn)
+        .await?;
+    let votes = VoteEntity::find().filter(VoteCol::PollId.eq(poll.id)).all(conn).await?;
+    let now = Utc::now().naive_utc();
+    let _ = queue.send(ChatEvent::Poll { room_id, poll: tally(poll, &options, &votes, None, now) });
+    Ok(tally(poll, &options, &votes, Some(viewer), now))
+}
+
+/// POST /chat/poll: 투표 메시지를 만든다.
+pub async fn create_poll(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    headers: HeaderMap,
+    Json(req): Json<NewPoll>,
+) -> Json<SendResponse> {
+    let fail = |e: String| Json(SendResponse { success: 0, error: Some(e), chat: None, ..Default::default() });
+
+    let question = req.question.trim().to_string();
+    if question.is_empty() || question.chars().count() > MAX_QUESTION_CHARS {
+        return fail(format!("질문은 1~{}자로 입력하세요.", MAX_QUESTION_CHARS));
+    }
+    let mut labels: Vec<String> = Vec::new();
+    for label in req.options.iter().map(|o| o.trim()) {
+        if label.is_empty() || label.chars().count() > MAX_OPTION_CHARS {
+            return fail(format!("선택지는 1~{}자로 입력하세요.", MAX_OPTION_CHARS));
+        }
+        if labels.iter().any(|l| l == label) {
+            return fail(format!("선택지 '{}'가 중복되었습니다.", label));
+        }
+        labels.push(label.to_string());
+    }
+    if !(2..=MAX_OPTIONS).contains(&labels.len()) {
+        return fail(format!("선택지는 2~{}개여야 합니다.", MAX_OPTIONS));
+    }
+    let closes_at = req.closes_at.map(|t| t.with_timezone(&Utc).naive_utc());
+    if closes_at.is_some_and(|t| t <= Utc::now().naive_utc()) {
+        return fail("마감 시간은 현재 이후여야 합니다.".to_string());
+    }
+    let new_message = NewMessage {
+        sender: req.sender,
+        room_id: req.room_id,
+        content: MessageContent::Poll { question: question.clone() },
+        client_msg_id: req.client_msg_id,
+    };
+    if let Err(e) = validate(&new_message) {
+        return fail(e);
+    }
+    // 봇 계정으로 보내려면 그 봇의 API 토큰이 있어야 한다
+    if let Err(e) = bot::authorize_sender(&conn, &headers, &new_message.sender).await {
+        return fail(e);
+    }
+    match already_sent(&conn, &new_message).await {
+        Ok(Some(chat)) => return Json(SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() }),
+        Ok(None) => {}
+        Err(e) => return fail(e),
+    }
+    match RoomEntity::find_by_id(new_message.room_id).one(&conn).await {
+        Ok(Some(room)) if participants_of(&room).contains(&new_message.sender) => {}
+        _ => return fail("참여 중인 방이 아닙니다.".to_string()),
+    }
+    if let Err(rejection) = validation::check(&conn, &new_message, &[]).await {
+        return Json(SendResponse::rejected(rejection));
+    }
+
+    let created = async {
+        let txn = conn.begin().await.map_err(|e| e.to_string())?;
+        let chat = insert_message(&txn, &new_message).await?;
+        let poll = ActivePoll {
+            id: ActiveValue::NotSet,
+            chat_id: ActiveValue::Set(chat.id),
+            question: ActiveValue::Set(question),
+            multiple: ActiveValue::Set(req.multiple),
+            anonymous: ActiveValue::Set(req.anonymous),
+            closes_at: ActiveValue::Set(closes_at),
+            created_at: ActiveValue::Set(chat.timestamp),
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| e.to_string())?;
+        let mut options = Vec::new();
+        for (position, label) in labels.into_iter().enumerate() {
+            let option = ActiveOption {
+                id: ActiveValue::NotSet,
+                poll_id: ActiveValue::Set(poll.id),
+                position: ActiveValue::Set(position as i32),
+                label: ActiveValue::Set(label),
+            };
+            options.push(option.insert(&txn).await.map_err(|e| e.to_string())?);
+        }
+        txn.commit().await.map_err(|e| e.to_string())?;
+        Ok::<_, String>((chat, poll, options))
+    }
+    .await;
+    let (chat, poll, options) = match created {
+        Ok(created) => created,
+        Err(e) => return fail(format!("투표를 만들지 못했습니다: {}", e)),
+    };
+
+    outgoing::publish(&conn, &queue, ChatEvent::Message(chat.clone())).await;
+    let _ = queue.send(ChatEvent::Poll {
+        room_id: chat.room_id,
+        poll: tally(&poll, &options, &[], None, Utc::now().naive_utc()),
+    });
+    Json(SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() })
+}
+
+// 투표와 그 방을 찾고, 참가자인지와 마감 여부를 확인한다
+async fn open_poll_for(conn: &DatabaseConnection, poll_id: i32, username: &str) -> Result<(Poll, i32), String> {
+    let poll = match PollEntity::find_by_id(poll_id).one(conn).await {
+        Ok(Some(poll)) => poll,
+        Ok(None) => return Err("투표를 찾을 수 없습니다.".to_string()),
+        Err(e) => return Err(format!("DB 오류: {}", e)),
+    };
+    let chat = match ChatEntity::find_by_id(poll.chat_id).one(conn).await {
+        Ok(Some(chat)) => chat,
+        _ => return Err("투표를 찾을 수 없습니다.".to_string()),
+    };
+    let is_member = match RoomEntity::find_by_id(chat.room_id).one(conn).await {
+        Ok(Some(room)) => participants_of(&room).iter().any(|p| p == username),
+        _ => false,
+    };
+    if !is_member {
+        return Err("참여 중인 방이 아닙니다.".to_string());
+    }
+    if poll.closes_at.is_some_and(|t| t <= Utc::now().naive_utc()) {
+        return Err("마감된 투표입니다.".to_string());
+    }
+    Ok((poll, chat.room_id))
+}
+
+/// POST /chat/poll/{id}/vote: 내 선택을 option_ids로 바꾼다.
+pub async fn vote(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    Path(poll_id): Path<i32>,
+    Json(req): Json<PollVoteRequest>,
+) -> Json<ApiResponse<PollView>> {
+    let (poll, room_id) = match open_poll_for(&conn, poll_id, &req.username).await {
+        Ok(found) => found,
+        Err(e) => return fail(e),
+    };
+    let mut option_ids = req.option_ids;
+    option_ids.sort();
+    option_ids.dedup();
+    if option_ids.is_empty() {
+        return fail("선택지를 골라 주세요.");
+    }
+    if !poll.multiple && option_ids.len() > 1 {
+        return fail("하나만 고를 수 있는 투표입니다.");
+    }
+    let valid: Vec<i32> = OptionEntity::find()
+        .filter(OptionCol::PollId.eq(poll.id))
+        .all(&conn)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|o| o.id)
+        .collect();
+    if option_ids.iter().any(|id| !valid.contains(id)) {
+        return fail("이 투표의 선택지가 아닙니다.");
+    }
+
+    // 기존 선택을 지우고 새로 넣는다 (다시 투표하면 바뀐 선택으로 대체)
+    // 같은 사람의 요청이 동시에 들어와 둘 다 지우고 넣으면 하나만 고르는 투표에 두 표가 남으므로 투표 행을 잠근다
+    let saved = async {
+        let txn = conn.begin().await?;
+        PollEntity::find_by_id(poll.id).lock_exclusive().one(&txn).await?;
+        VoteEntity::delete_many()
+            .filter(VoteCol::PollId.eq(poll.id))
+            .filter(VoteCol::Username.eq(req.username.as_str()))
+            .exec(&txn)
+            .await?;
+        let now = Utc::now().naive_utc();
+        for option_id in &option_ids {
+            ActiveVote {
+                id: ActiveValue::NotSet,
+                poll_id: ActiveValue::Set(poll.id),
+                option_id: ActiveValue::Set(*option_id),
+                username: ActiveValue::Set(req.username.clone()),
+                created_at: ActiveValue::Set(now),
+            }
+            .insert(&txn)
+            .await?;
+        }
+        txn.commit().await
+    }
+    .await;
+    if let Err(e) = saved {
+        return fail(format!("DB 오류: {}", e));
+    }
+    match publish(&conn, &queue, &poll, room_id, &req.username).await {
+        Ok(view) => Json(ApiResponse { success: 1, error: None, data: Some(view) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// DELETE /chat/poll/{id}/vote?username=alice: 내 투표를 모두 취소한다.
+pub async fn retract(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    Path(poll_id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<PollView>> {
+    let username = params.get("username").cloned().unwrap_or_default();
+    let (poll, room_id) = match open_poll_for(&conn, poll_id, &username).await {
+        Ok(found) => found,
+        Err(e) => return fail(e),
+    };
+    let deleted = VoteEntity::delete_many()
+        .filter(VoteCol::PollId.eq(poll.id))
+        .filter(VoteCol::Username.eq(username.as_str()))
+        .exec(&conn)
+        .await;
+    if let Err(e) = deleted {
+        return fail(format!("DB 오류: {}", e));
+    }
+    match publish(&conn, &queue, &poll, room_id, &username).await {
+        Ok(view) => Json(ApiResponse { success: 1, error: None, data: Some(view) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poll(anonymous: bool, closes_at: Option<NaiveDateTime>) -> Poll {
+        Poll {
+            id: 1,
+            chat_id: 10,
+            question: "점심 메뉴?".to_string(),
+            multiple: true,
+            anonymous,
+            closes_at,
+            created_at: NaiveDateTime::default(),
+        }
+    }
+
+    fn option(id: i32, label: &str) -> PollOption {
+        PollOption { id, poll_id: 1, position: id, label: label.to_string() }
+    }
+
+    fn vote(option_id: i32, username: &str) -> PollVote {
+        PollVote { id: 0, poll_id: 1, option_id, username: username.to_string(), created_at: NaiveDateTime::default() }
+    }
+
+    #[test]
+    fn counts_votes_and_distinct_voters() {
+        let options = [option(1, "국밥"), option(2, "짜장면")];
+        let votes = [vote(1, "alice"), vote(2, "alice"), vote(1, "bob")];
+        let now = NaiveDateTime::default();
+        let view = tally(&poll(false, None), &options, &votes, Some("alice"), now);
+        assert_eq!(view.total_voters, 2);
+        assert_eq!(view.options[0].votes, 2);
+        assert_eq!(view.options[1].voters, Some(vec!["alice".to_string()]));
+        assert_eq!(view.my_votes, Some(vec![1, 2]));
+        assert!(!view.closed);
+    }
+
+    #[test]
+    fn anonymous_hides_voters_and_close_time_applies() {
+        let options = [option(1, "예"), option(2, "아니오")];
+        let votes = [vote(1, "alice")];
+        let now = NaiveDateTime::default() + chrono::Duration::hours(1);
+        let view = tally(&poll(true, Some(NaiveDateTime::default())), &options, &votes, None, now);
+        assert_eq!(view.options[0].votes, 1);
+        assert!(view.options.iter().all(|o| o.voters.is_none()));
+        assert!(view.my_votes.is_none());
+        assert!(view.closed);
+    }
+}
@@ -60,6 +60,8 @@ pub async fn update_profile(State(conn): State<DatabaseConnection>, Json(profile
                 display_name: ActiveValue::Set(Some(profile.display_name.clone())),
                 status: ActiveValue::Set(Some(profile.status.clone())),
                 avatar: ActiveValue::Set(Some(profile.avatar.clone())),
+                is_bot: ActiveValue::Set(u.is_bot),
+                owner: ActiveValue::Set(u.owner),
             };
             match updated.update(&conn).await {
                 Ok(_m) => Json(ApiResponse { success: 1, error: None, data: Some(profile) }),
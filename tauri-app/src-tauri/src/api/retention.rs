@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::entities::{
+    attachment,
+    chat::{Column as ChatCol, Entity as ChatEntity},
+    room::{ActiveModel as ActiveRoom, Entity as RoomEntity, Model as Room},
+    room_read,
+};
+use crate::media::store;
+
+use super::chat_room::participants_of;
+use super::event::ChatEvent;
+use super::outgoing;
+
+/// 설정할 수 있는 보관 기간 범위 (1분 ~ 4주)
+const MIN_TTL_SECS: i32 = 60;
+const MAX_TTL_SECS: i32 = 28 * 24 * 60 * 60;
+/// 정리 작업 주기
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+/// 한 트랜잭션에서 지우는 최대 메시지 수
+const SWEEP_BATCH: u64 = 500;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+#[derive(Deserialize)]
+pub struct TtlUpdate {
+    pub username: String,
+    /// 초 단위. null이면 사라지는 메시지를 끈다.
+    pub message_ttl: Option<i32>,
+}
+
+/// 만료되지 않은 메시지만 고르는 조건. 정리 작업이 돌기 전에도 조회에서 빠지게 한다.
+pub fn not_expired() -> Condition {
+    Condition::any()
+        .add(ChatCol::ExpiresAt.is_null())
+        .add(ChatCol::ExpiresAt.gt(chrono::Utc::now().naive_utc()))
+}
+
+/// 지금 보내는 메시지의 만료 시각 (설정 이후에 보낸 메시지에만 적용)
+pub fn expires_at(room: &Room, sent_at: NaiveDateTime) -> Option<NaiveDateTime> {
+    room.message_ttl.map(|secs| sent_at + chrono::Duration::seconds(secs as i64))
+}
+
+/// PUT /room/{id}/ttl: 방의 사라지는 메시지 설정. 참가자만 바꿀 수 있다.
+pub async fn set_ttl(
+    State(conn): State<DatabaseConnection>,
+    Path(room_id): Path<i32>,
+    Json(update): Json<TtlUpdate>,
+) -> Json<ApiResponse<Room>> {
+    let fail = |e: String| Json(ApiResponse { success: 0, error: Some(e), data: None });
+    if let Some(ttl) = update.message_ttl {
+        if !(MIN_TTL_SECS..=MAX_TTL_SECS).contains(&ttl) {
+            return fail(format!("보관 기간은 {}초에서 {}일 사이여야 합니다.", MIN_TTL_SECS, MAX_TTL_SECS / 86400));
+        }
+    }
+    let room = match RoomEntity::find_by_id(room_id).one(&conn).await {
+        Ok(Some(room)) if participants_of(&room).contains(&update.username) => room,
+        Ok(Some(_)) => return fail("참여 중인 방이 아닙니다.".to_string()),
+        Ok(None) => return fail("존재하지 않는 방입니다.".to_string()),
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    };
+    let mut room: ActiveRoom = room.into();
+    room.message_ttl = ActiveValue::Set(update.message_ttl);
+    match room.update(&conn).await {
+        Ok(room) => Json(ApiResponse { success: 1, error: None, data: Some(room) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// 만료된 메시지 정리 루프. run_async에서 서버와 함께 띄운다.
+pub async fn run_sweeper(conn: DatabaseConnection, queue: broadcast::Sender<ChatEvent>) {
+    let mut tick = tokio::time::interval(SWEEP_INTERVAL);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        loop {
+            match sweep(&conn).await {
+                Ok(deleted) => {
+                    let done = deleted.values().map(Vec::len).sum::<usize>() < SWEEP_BATCH as usize;
+                    for (room_id, chat_ids) in deleted {
+                        outgoing::publish(&conn, &queue, ChatEvent::Deleted { room_id, chat_ids }).await;
+                    }
+                    if done {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("expired message sweep failed: {e}");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// 만료된 메시지를 한 묶음 지우고 방별로 지운 id를 돌려준다.
+// 첨부/도착 기록은 FK cascade로 함께 지워지고, 저장된 파일은 커밋 후에 지운다.
+async fn sweep(conn: &DatabaseConnection) -> Result<BTreeMap<i32, Vec<i32>>, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+    let txn = conn.begin().await?;
+    let expired: Vec<(i32, i32)> = ChatEntity::find()
+        .select_only()
+        .columns([ChatCol::Id, ChatCol::RoomId])
+        .filter(ChatCol::ExpiresAt.lte(now))
+        .order_by_asc(ChatCol::Id)
+        .limit(SWEEP_BATCH)
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    if expired.is_empty() {
+        txn.commit().await?;
+        return Ok(BTreeMap::new());
+    }
+    let ids: Vec<i32> = expired.iter().map(|(id, _)| *id).collect();
+
+    // 지워질 메시지를 가리키는 읽음 위치는 그 앞의 남는 메시지로 옮긴다.
+    // 안 읽음 개수(id > last_read_id)가 삭제 전후로 같게 유지된다.
+    let reads = room_read::Entity::find()
+        .filter(room_read::Column::LastReadId.is_in(ids.clone()))
+        .all(&txn)
+        .await?;
+    for read in reads {
+        let previous: Option<i32> = ChatEntity::find()
+            .select_only()
+            .column(ChatCol::Id)
+            .filter(ChatCol::RoomId.eq(read.room_id))
+            .filter(ChatCol::Id.lte(read.last_read_id))
+            .filter(ChatCol::Id.is_not_in(ids.clone()))
+            .order_by_desc(ChatCol::Id)
+            .into_tuple()
+            .one(&txn)
+            .await?;
+        let mut read: room_read::ActiveModel = read.into();
+        read.last_read_id = ActiveValue::Set(previous);
+        read.update(&txn).await?;
+    }
+
+    let storage_keys: Vec<String> = attachment::Entity::find()
+        .select_only()
+        .column(attachment::Column::StorageKey)
+        .filter(attachment::Column::ChatId.is_in(ids.clone()))
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    ChatEntity::delete_many()
+        .filter(ChatCol::Id.is_in(ids))
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+
+    for key in storage_keys {
+        let _ = store::remove(&key).await;
+    }
+    let mut deleted: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    for (id, room_id) in expired {
+        deleted.entry(room_id).or_default().push(id);
+    }
+    Ok(deleted)
+}
@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, LockBehavior, LockType},
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::content::MessageContent;
+use crate::entities::{
+    chat::Model as Chat,
+    room::Entity as RoomEntity,
+    scheduled_chat::{ActiveModel, Column, Entity as ScheduledEntity, Model as ScheduledChat},
+};
+use crate::preview;
+
+use super::bot;
+use super::chat::{insert_external_message, validate, NewMessage};
+use super::chat_room::{member_room, participants_of};
+use super::event::ChatEvent;
+use super::outgoing;
+use super::validation;
+
+pub const PENDING: &str = "pending";
+pub const SENT: &str = "sent";
+pub const CANCELED: &str = "canceled";
+pub const FAILED: &str = "failed";
+
+/// 발송기가 due 메시지를 확인하는 주기
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// 한 번 깨어났을 때 보내는 최대 건수 (나머지는 다음 주기에)
+const MAX_PER_TICK: usize = 100;
+/// 예약할 수 있는 가장 먼 시점
+const MAX_AHEAD_DAYS: i64 = 365;
+/// 클라이언트 시계 오차를 감안해 이 정도 과거까지는 받아서 바로 보낸다
+const PAST_GRACE_SECS: i64 = 60;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+fn fail<T>(error: impl Into<String>) -> Json<ApiResponse<T>> {
+    Json(ApiResponse { success: 0, error: Some(error.into()), data: None })
+}
+
+#[derive(Deserialize)]
+pub struct NewSchedule {
+    pub sender: String,
+    pub room_id: i32,
+    pub message: String,
+    /// RFC 3339, 시간대 포함 (예: 2025-09-29T09:00:00+09:00)
+    pub send_at: DateTime<FixedOffset>,
+    /// chat/send의 client_msg_id와 같다. 같은 id로 다시 보내면 새로 예약하지 않고 처음 예약을 돌려준다.
+    #[serde(default)]
+    pub client_msg_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ScheduleEdit {
+    pub username: String,
+    pub message: Option<String>,
+    pub send_at: Option<DateTime<FixedOffset>>,
+}
+
+fn check_send_at(send_at: &DateTime<FixedOffset>) -> Result<NaiveDateTime, String> {
+    let now = Utc::now();
+    let send_at = send_at.with_timezone(&Utc);
+    if send_at < now - chrono::Duration::seconds(PAST_GRACE_SECS) {
+        return Err("예약 시간은 현재 이후여야 합니다.".to_string());
+    }
+    if send_at > now + chrono::Duration::days(MAX_AHEAD_DAYS) {
+        return Err(format!("{}일 이내로만 예약할 수 있습니다.", MAX_AHEAD_DAYS));
+    }
+    Ok(send_at.naive_utc())
+}
+
+/// POST /chat/schedule
+pub async fn create(
+    State(conn): State<DatabaseConnection>,
+    headers: HeaderMap,
+    Json(req): Json<NewSchedule>,
+) -> Json<ApiResponse<ScheduledChat>> {
+    let new_message = NewMessage {
+        sender: req.sender,
+        room_id: req.room_id,
+        content: MessageContent::text(req.message),
+        client_msg_id: req.client_msg_id,
+    };
+    if let Err(e) = validate(&new_message) {
+        return fail(e);
+    }
+    // 봇 계정으로 보내려면 그 봇의 API 토큰이 있어야 한다
+    if let Err(e) = bot::authorize_sender(&conn, &headers, &new_message.sender).await {
+        return fail(e);
+    }
+    if let Some(client_msg_id) = &new_message.client_msg_id {
+        let existing = ScheduledEntity::find()
+            .filter(Column::Sender.eq(new_message.sender.as_str()))
+            .filter(Column::ClientMsgId.eq(client_msg_id.as_str()))
+            .one(&conn)
+            .await;
+        match existing {
+            Ok(Some(saved)) => return Json(ApiResponse { success: 1, error: None, data: Some(saved) }),
+            Ok(None) => {}
+            Err(e) => return fail(format!("DB 오류: {}", e)),
+        }
+    }
+    let send_at = match check_send_at(&req.send_at) {
+        Ok(t) => t,
+        Err(e) => return fail(e),
+    };
+    match RoomEntity::find_by_id(new_message.room_id).one(&conn).await {
+        Ok(Some(room)) if participants_of(&room).contains(&new_message.sender) => {}
+        Ok(Some(_)) => return fail("참여 중인 방이 아닙니다."),
+        Ok(None) => return fail("존재하지 않는 방입니다."),
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    }
+    if let Err(rejection) = validation::check(&conn, &new_message, &[]).await {
+        return fail(rejection.to_string());
+    }
+
+    let now = Utc::now().naive_utc();
+    let row = ActiveModel {
+        id: ActiveValue::NotSet,
+        sender: ActiveValue::Set(new_message.sender),
+        client_msg_id: ActiveValue::Set(new_message.client_msg_id),
+        room_id: ActiveValue::Set(new_message.room_id),
+        message: ActiveValue::Set(new_message.content.summary()),
+        send_at: ActiveValue::Set(send_at),
+        status: ActiveValue::Set(PENDING.to_string()),
+        chat_id: ActiveValue::Set(None),
+        error: ActiveValue::Set(None),
+        created_at: ActiveValue::Set(now),
+        updated_at: ActiveValue::Set(now),
+    };
+    match row.insert(&conn).await {
+        Ok(saved) => Json(ApiResponse { success: 1, error: None, data: Some(saved) }),
+        Err(e) => fail(format!("예약 저장 실패: {}", e)),
+    }
+}
+
+/// GET /chat/schedule?username=alice&room_id=1&status=pending
+/// status를 생략하면 대기 중인 예약만, all이면 전부 보여준다.
+pub async fn list(
+    State(conn): State<DatabaseConnection>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<Vec<ScheduledChat>>> {
+    let Some(username) = params.get("username").filter(|u| !u.trim().is_empty()) else {
+        return fail("username이 필요합니다.");
+    };
+    let mut query = ScheduledEntity::find().filter(Column::Sender.eq(username.as_str()));
+    if let Some(room_id) = params.get("room_id").and_then(|v| v.parse::<i32>().ok()) {
+        query = query.filter(Column::RoomId.eq(room_id));
+    }
+    match params.get("status").map(String::as_str).unwrap_or(PENDING) {
+        "all" => {}
+        status => query = query.filter(Column::Status.eq(status)),
+    }
+    match query.order_by_asc(Column::SendAt).all(&conn).await {
+        Ok(rows) => Json(ApiResponse { success: 1, error: None, data: Some(rows) }),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+// 본인의 예약인지 확인하고, 대기 중이 아니면 이유를 돌려준다
+async fn find_own_pending(conn: &DatabaseConnection, id: i32, username: &str) -> Result<ScheduledChat, String> {
+    let row = match ScheduledEntity::find_by_id(id).one(conn).await {
+        Ok(Some(row)) if row.sender == username => row,
+        Ok(_) => return Err("예약 메시지를 찾을 수 없습니다.".to_string()),
+        Err(e) => return Err(format!("DB 오류: {}", e)),
+    };
+    not_pending_reason(&row.status).map_or(Ok(row), Err)
+}
+
+fn not_pending_reason(status: &str) -> Option<String> {
+    match status {
+        PENDING => None,
+        SENT => Some("이미 발송된 메시지입니다.".to_string()),
+        CANCELED => Some("취소된 예약입니다.".to_string()),
+        _ => Some("발송에 실패한 예약입니다.".to_string()),
+    }
+}
+
+/// PUT /chat/schedule/{id}: 대기 중인 예약의 내용이나 시간을 바꾼다.
+pub async fn edit(
+    State(conn): State<DatabaseConnection>,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+    Json(req): Json<ScheduleEdit>,
+) -> Json<ApiResponse<ScheduledChat>> {
+    // 봇 계정으로 보내려면 그 봇의 API 토큰이 있어야 한다
+    if let Err(e) = bot::authorize_sender(&conn, &headers, &req.username).await {
+        return fail(e);
+    }
+    let row = match find_own_pending(&conn, id, &req.username).await {
+        Ok(row) => row,
+        Err(e) => return fail(e),
+    };
+    let message = req.message.unwrap_or(row.message);
+    let new_message = NewMessage {
+        sender: row.sender,
+        room_id: row.room_id,
+        content: MessageContent::text(message),
+        client_msg_id: None,
+    };
+    if let Err(e) = validate(&new_message) {
+        return fail(e);
+    }
+    let send_at = match req.send_at.as_ref().map(check_send_at) {
+        Some(Ok(t)) => t,
+        Some(Err(e)) => return fail(e),
+        None => row.send_at,
+    };
+    if let Err(rejection) = validation::check(&conn, &new_message, &[]).await {
+        return fail(rejection.to_string());
+    }
+
+    // 발송기가 먼저 집어 갔다면 status 조건에 걸려 0건이 된다
+    let updated = ScheduledEntity::update_many()
+        .col_expr(Column::Message, Expr::value(new_message.content.summary()))
+        .col_expr(Column::SendAt, Expr::value(send_at))
+        .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(Column::Id.eq(id))
+        .filter(Column::Status.eq(PENDING))
+        .exec(&conn)
+        .await;
+    match updated {
+        Ok(res) if res.rows_affected == 1 => match ScheduledEntity::find_by_id(id).one(&conn).await {
+            Ok(saved) => Json(ApiResponse { success: 1, error: None, data: saved }),
+            Err(e) => fail(format!("DB 오류: {}", e)),
+        },
+        Ok(_) => fail("이미 발송된 메시지입니다."),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+/// DELETE /chat/schedule/{id}?username=alice
+pub async fn cancel(
+    State(conn): State<DatabaseConnection>,
+    Path(id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<i32>> {
+    let username = params.get("username").cloned().unwrap_or_default();
+    if let Err(e) = find_own_pending(&conn, id, &username).await {
+        return fail(e);
+    }
+    let canceled = ScheduledEntity::update_many()
+        .col_expr(Column::Status, Expr::value(CANCELED))
+        .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(Column::Id.eq(id))
+        .filter(Column::Status.eq(PENDING))
+        .exec(&conn)
+        .await;
+    match canceled {
+        Ok(res) if res.rows_affected == 1 => Json(ApiResponse { success: 1, error: None, data: Some(id) }),
+        Ok(_) => fail("이미 발송된 메시지입니다."),
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+enum Dispatched {
+    Sent(Box<Chat>),
+    Failed,
+    Idle,
+}
+
+/// 예약 발송 루프. run_async에서 서버와 함께 띄운다.
+pub async fn run_dispatcher(conn: DatabaseConnection, queue: broadcast::Sender<ChatEvent>) {
+    let mut tick = tokio::time::interval(POLL_INTERVAL);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        for _ in 0..MAX_PER_TICK {
+            match dispatch_one(&conn).await {
+                Ok(Dispatched::Sent(chat)) => {
+                    outgoing::publish(&conn, &queue, ChatEvent::Message((*chat).clone())).await;
+                    preview::spawn_for_message(conn.clone(), queue.clone(), *chat);
+                }
+                Ok(Dispatched::Failed) => {}
+                Ok(Dispatched::Idle) => break,
+                Err(e) => {
+                    eprintln!("scheduled message dispatch failed: {e}");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// 예약 하나를 잠그고 chat 저장과 상태 변경을 한 트랜잭션으로 커밋한다.
+// 중간에 서버가 죽으면 둘 다 롤백되어 재시작 후 다시 보내므로 중복 발송이 없다.
+// SKIP LOCKED라 발송기가 여러 개 떠 있어도 같은 행을 두 번 잡지 않는다.
+async fn dispatch_one(conn: &DatabaseConnection) -> Result<Dispatched, DbErr> {
+    let now = Utc::now().naive_utc();
+    let txn = conn.begin().await?;
+    let job = ScheduledEntity::find()
+        .filter(Column::Status.eq(PENDING))
+        .filter(Column::SendAt.lte(now))
+        .order_by_asc(Column::SendAt)
+        .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+        .one(&txn)
+        .await?;
+    let Some(job) = job else {
+        txn.commit().await?;
+        return Ok(Dispatched::Idle);
+    };
+    let id = job.id;
+    match send_locked(&txn, job, now).await {
+        Ok(outcome) => {
+            txn.commit().await?;
+            Ok(outcome)
+        }
+        // 이 예약만의 문제로 계속 실패하면 큐 맨 앞에서 다른 예약을 막으므로 새 트랜잭션에서 실패로 남긴다
+        Err(e) => {
+            let _ = txn.rollback().await;
+            mark_failed(conn, id, format!("DB 오류: {}", e)).await?;
+            Ok(Dispatched::Failed)
+        }
+    }
+}
+
+async fn mark_failed(conn: &DatabaseConnection, id: i32, error: String) -> Result<(), DbErr> {
+    ScheduledEntity::update_many()
+        .col_expr(Column::Status, Expr::value(FAILED))
+        .col_expr(Column::Error, Expr::value(error))
+        .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(Column::Id.eq(id))
+        .filter(Column::Status.eq(PENDING))
+        .exec(conn)
+        .await
+        .map(|_| ())
+}
+
+// chat 저장은 savepoint 안에서 한다. 실패해도 바깥 트랜잭션은 멀쩡해서 같은 트랜잭션에서 실패로 기록할 수 있다.
+async fn send_locked(txn: &DatabaseTransaction, job: ScheduledChat, now: NaiveDateTime) -> Result<Dispatched, DbErr> {
+    let new_message = NewMessage {
+        sender: job.sender.clone(),
+        room_id: job.room_id,
+        content: MessageContent::text(job.message.clone()),
+        client_msg_id: None,
+    };
+    // 예약한 뒤 방에서 나갔거나 방 규칙이 바뀌었을 수 있으므로 보내는 시점에 다시 확인한다.
+    // 참가자임을 확인했으니 저장할 때 방에 다시 넣지 않는다.
+    let allowed = match member_room(txn, job.room_id, &job.sender).await {
+        Ok(_) => validation::check(txn, &new_message, &[]).await.map_err(|rejection| rejection.to_string()),
+        Err(e) => Err(e),
+    };
+    let mut row: ActiveModel = job.into();
+    row.updated_at = ActiveValue::Set(now);
+    let stored = match allowed {
+        Ok(()) => {
+            let savepoint = txn.begin().await?;
+            let stored = insert_external_message(&savepoint, &new_message).await;
+            if stored.is_ok() {
+                savepoint.commit().await?;
+            } else {
+                savepoint.rollback().await?;
+            }
+            stored
+        }
+        Err(e) => Err(e),
+    };
+    let outcome = match stored {
+        Ok(chat) => {
+            row.status = ActiveValue::Set(SENT.to_string());
+            row.chat_id = ActiveValue::Set(Some(chat.id));
+            Dispatched::Sent(Box::new(chat))
+        }
+        // 방이 사라졌거나 더는 보낼 수 없는 경우. 다시 시도해도 소용없으니 실패로 남긴다.
+        Err(e) => {
+            row.status = ActiveValue::Set(FAILED.to_string());
+            row.error = ActiveValue::Set(Some(e));
+            Dispatched::Failed
+        }
+    };
+    row.update(txn).await?;
+    Ok(outcome)
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, EntityTrait, FromQueryResult, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+use serde::Serialize;
+
+use super::chat_room::{participants_of, rooms_of};
+use super::retention;
+use crate::entities::chat::{Column, Entity as ChatEntity};
+use crate::search::{query, snippet, tokenize};
+
+const DEFAULT_LIMIT: u64 = 20;
+const MAX_LIMIT: u64 = 100;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct HitRow {
+    id: i32,
+    room_id: i32,
+    sender: String,
+    message: String,
+    timestamp: chrono::NaiveDateTime,
+    rank: f32,
+}
+
+#[derive(Serialize)]
+pub struct SearchRoom {
+    pub id: i32,
+    pub participants: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    pub id: i32,
+    pub sender: String,
+    pub message: String,
+    pub timestamp: chrono::NaiveDateTime,
+    pub rank: f32,
+    /// `<mark>`로 강조한 HTML 조각 (본문은 이스케이프됨)
+    pub snippet: String,
+    pub room: SearchRoom,
+}
+
+/// GET /chat/search?username=&q=&room_id=&limit=&offset=
+///
+/// q는 `from:` `in:` `has:` `before:` `after:` `"구절"` 문법을 지원한다. (search::query 참고)
+pub async fn search(
+    State(conn): State<DatabaseConnection>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<ApiResponse<Vec<SearchHit>>> {
+    let username = params.get("username").map(|u| u.trim()).unwrap_or_default();
+    let q = params.get("q").map(|q| q.trim()).unwrap_or_default();
+    if username.is_empty() || q.is_empty() {
+        return Json(ApiResponse { success: 0, error: Some("username과 검색어(q)가 필요합니다.".to_string()), data: None });
+    }
+    let limit = params.get("limit").and_then(|v| v.parse::<u64>().ok()).unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = params.get("offset").and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+
+    // 참여 중인 방만 검색 대상
+    let mut rooms = match rooms_of(&conn, username).await {
+        Ok(rooms) => rooms,
+        Err(e) => return Json(ApiResponse { success: 0, error: Some(format!("DB 오류: {}", e)), data: None }),
+    };
+    if let Some(room_id) = params.get("room_id").and_then(|v| v.parse::<i32>().ok()) {
+        rooms.retain(|r| r.id == room_id);
+    }
+    let parsed = match query::parse(q) {
+        Ok(parsed) => parsed,
+        Err(e) => return Json(ApiResponse { success: 0, error: Some(e.to_string()), data: None }),
+    };
+    let fulltext = parsed.fulltext();
+    let fts = tokenize::query_text(&fulltext);
+    let filter = parsed.condition();
+    if rooms.is_empty() || (fts.is_empty() && filter.is_empty()) {
+        return Json(ApiResponse { success: 1, error: None, data: Some(vec![]) });
+    }
+
+    let mut select = ChatEntity::find()
+        .select_only()
+        .columns([Column::Id, Column::RoomId, Column::Sender, Column::Message, Column::Timestamp])
+        .filter(Column::RoomId.is_in(rooms.iter().map(|r| r.id)))
+        .filter(retention::not_expired())
+        .filter(filter);
+    if fts.is_empty() {
+        // 필터만 있는 검색은 최신순
+        select = select.expr_as(Expr::val(0f32), "rank");
+    } else {
+        select = select
+            .expr_as(
+                Expr::cust_with_values("ts_rank(chat.search_vector, plainto_tsquery('simple', $1))", [fts.clone()]),
+                "rank",
+            )
+            .filter(Expr::cust_with_values("chat.search_vector @@ plainto_tsquery('simple', $1)", [fts]))
+            .order_by_desc(Expr::cust("rank"));
+    }
+    let rows = select
+        .order_by_desc(Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .into_model::<HitRow>()
+        .all(&conn)
+        .await;
+    let rows = match rows {
+        Ok(rows) => rows,
+        Err(e) => return Json(ApiResponse { success: 0, error: Some(format!("DB 오류: {}", e)), data: None }),
+    };
+
+    let participants: HashMap<i32, Vec<String>> = rooms.iter().map(|r| (r.id, participants_of(r))).collect();
+    let hits = rows
+        .into_iter()
+        .map(|row| SearchHit {
+            snippet: snippet::highlight(&row.message, &fulltext),
+            room: SearchRoom {
+                id: row.room_id,
+                participants: participants.get(&row.room_id).cloned().unwrap_or_default(),
+            },
+            id: row.id,
+            sender: row.sender,
+            message: row.message,
+            timestamp: row.timestamp,
+            rank: row.rank,
+        })
+        .collect();
+    Json(ApiResponse { success: 1, error: None, data: Some(hits) })
+}
@@ -1,4 +1,6 @@
-use crate::entities::chat::Model as Chat;
+use super::event::ChatEvent;
+use super::incoming::HookLimiter;
+use super::typing::TypingLimiter;
 
 use sea_orm::DatabaseConnection;
 use tokio::sync::broadcast;
@@ -6,5 +8,7 @@ use tokio::sync::broadcast;
 #[derive(Clone)]
 pub struct AppState {
     pub conn: DatabaseConnection,
-    pub queue: broadcast::Sender<Chat>,
+    pub queue: broadcast::Sender<ChatEvent>,
+    pub typing: TypingLimiter,
+    pub hooks: HookLimiter,
 }
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, Json};
+use sea_orm::{DatabaseConnection, EntityTrait};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::entities::room::Entity as RoomEntity;
+
+use super::chat_room::participants_of;
+use super::event::ChatEvent;
+
+/// 클라이언트가 "입력 중" 표시를 유지하는 시간. 이 안에 새 이벤트가 없으면 지운다.
+pub const TYPING_TTL: Duration = Duration::from_secs(5);
+/// 같은 방에 대해 다시 알리기까지의 최소 간격
+const MIN_INTERVAL: Duration = Duration::from_secs(2);
+/// 사용자 한 명이 WINDOW 동안 보낼 수 있는 이벤트 수 (여러 방 합산)
+const MAX_PER_WINDOW: usize = 10;
+const WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+struct LimiterState {
+    last_sent: HashMap<(String, i32), Instant>,
+    recent: HashMap<String, Vec<Instant>>,
+}
+
+/// 입력 중 이벤트의 사용자별 전송 제한. 메모리에만 두며 서버 재시작 시 초기화된다.
+#[derive(Clone, Default)]
+pub struct TypingLimiter {
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl TypingLimiter {
+    pub fn allow(&self, username: &str, room_id: i32, active: bool) -> bool {
+        self.allow_at(username, room_id, active, Instant::now())
+    }
+
+    fn allow_at(&self, username: &str, room_id: i32, active: bool, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let key = (username.to_string(), room_id);
+
+        // 멈춤 알림은 직전에 시작을 알린 경우에만 내보낸다
+        if !active {
+            return state.last_sent.remove(&key).is_some();
+        }
+        if state.last_sent.get(&key).is_some_and(|t| now.duration_since(*t) < MIN_INTERVAL) {
+            return false;
+        }
+        let recent = state.recent.entry(username.to_string()).or_default();
+        recent.retain(|t| now.duration_since(*t) < WINDOW);
+        if recent.len() >= MAX_PER_WINDOW {
+            return false;
+        }
+        recent.push(now);
+        state.last_sent.insert(key, now);
+
+        // 오래된 항목 정리
+        if state.last_sent.len() > 1024 {
+            state.last_sent.retain(|_, t| now.duration_since(*t) < TYPING_TTL);
+            state.recent.retain(|_, ts| ts.iter().any(|t| now.duration_since(*t) < WINDOW));
+        }
+        true
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TypingUpdate {
+    pub username: String,
+    pub room_id: i32,
+    /// false면 입력을 멈춤 (입력창 비움/전송)
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+pub struct TypingResponse {
+    pub success: i32,
+    pub error: Option<String>,
+    /// 제한에 걸려 이번 요청은 전파하지 않았으면 false
+    pub sent: bool,
+}
+
+/// POST /chat/typing: 입력 중 상태를 방 구독자에게 알린다. chat 테이블에는 저장하지 않는다.
+pub async fn typing(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    State(limiter): State<TypingLimiter>,
+    Json(update): Json<TypingUpdate>,
+) -> Json<TypingResponse> {
+    if update.username.trim().is_empty() {
+        return Json(TypingResponse { success: 0, error: Some("username이 필요합니다.".to_string()), sent: false });
+    }
+    // 제한을 먼저 확인해 멈춘 클라이언트가 DB까지 두드리지 않게 한다
+    if !limiter.allow(&update.username, update.room_id, update.active) {
+        return Json(TypingResponse { success: 1, error: None, sent: false });
+    }
+    let is_member = match RoomEntity::find_by_id(update.room_id).one(&conn).await {
+        Ok(Some(room)) => participants_of(&room).contains(&update.username),
+        _ => false,
+    };
+    if !is_member {
+        return Json(TypingResponse { success: 0, error: Some("참여 중인 방이 아닙니다.".to_string()), sent: false });
+    }
+    let _ = queue.send(ChatEvent::Typing {
+        room_id: update.room_id,
+        username: update.username,
+        active: update.active,
+        ttl_ms: TYPING_TTL.as_millis() as u64,
+    });
+    Json(TypingResponse { success: 1, error: None, sent: true })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_per_room_interval_and_per_user_burst() {
+        let limiter = TypingLimiter::default();
+        let t0 = Instant::now();
+        assert!(limiter.allow_at("alice", 1, true, t0));
+        assert!(!limiter.allow_at("alice", 1, true, t0 + Duration::from_millis(500)));
+        assert!(limiter.allow_at("bob", 1, true, t0 + Duration::from_millis(500)));
+        assert!(limiter.allow_at("alice", 1, true, t0 + MIN_INTERVAL));
+
+        // 여러 방을 돌며 보내도 창 안에서는 MAX_PER_WINDOW까지만
+        let mut allowed = 0;
+        for room in 100..130 {
+            if limiter.allow_at("carol", room, true, t0) {
+                allowed += 1;
+            }
+        }
+        assert_eq!(allowed, MAX_PER_WINDOW);
+        assert!(limiter.allow_at("carol", 200, true, t0 + WINDOW));
+    }
+
+    #[test]
+    fn stop_only_after_start() {
+        let limiter = TypingLimiter::default();
+        let t0 = Instant::now();
+        assert!(!limiter.allow_at("alice", 1, false, t0));
+        assert!(limiter.allow_at("alice", 1, true, t0));
+        assert!(limiter.allow_at("alice", 1, false, t0));
+        assert!(!limiter.allow_at("alice", 1, false, t0));
+    }
+}
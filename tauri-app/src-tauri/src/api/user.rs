@@ -92,6 +92,8 @@ pub async fn signup(
         display_name: ActiveValue::Set(None),
         status: ActiveValue::Set(None),
         avatar: ActiveValue::Set(None),
+        is_bot: ActiveValue::Set(false),
+        owner: ActiveValue::Set(None),
     };
     let _ = new_user.insert(&conn).await;
     Json(ApiResponse { success: 1, error: None })
@@ -128,7 +130,8 @@ pub async fn login(
         .one(&conn)
         .await
         .unwrap();
-    if let Some(user) = user {
+    // 봇은 비밀번호가 없고 API 토큰으로만 쓴다
+    if let Some(user) = user.filter(|u| !u.is_bot) {
         let parsed_hash = PasswordHash::new(&user.password).unwrap();
         let argon2 = Argon2::default();
         if argon2.verify_password(req.password.as_bytes(), &parsed_hash).is_ok() {
@@ -159,6 +162,8 @@ pub async fn put_user(State(conn): State<DatabaseConnection>, Json(user): Json<U
         display_name: ActiveValue::Set(result.display_name),
         status: ActiveValue::Set(result.status),
         avatar: ActiveValue::Set(result.avatar),
+        is_bot: ActiveValue::Set(result.is_bot),
+        owner: ActiveValue::Set(result.owner),
     };
 
     Json(new_user.update(&conn).await.unwrap())
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue, ConnectionTrait, DatabaseConnection, EntityTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::room::{ActiveModel as ActiveRoom, Entity as RoomEntity, Model as Room};
+use crate::validation::{self, AttachmentMeta, Candidate, Pipeline, ValidationRules, Violation};
+
+use super::chat::NewMessage;
+use super::chat_room::participants_of;
+
+#[derive(Serialize)]
+pub struct ApiResponse<T> {
+    pub success: i32,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+#[derive(Deserialize)]
+pub struct RulesUpdate {
+    pub username: String,
+    /// null이면 방 규칙을 지우고 서버 규칙만 쓴다
+    pub rules: Option<ValidationRules>,
+}
+
+/// 보내기 전 검증 실패
+#[derive(Debug)]
+pub enum Rejection {
+    Invalid(Vec<Violation>),
+    Db(String),
+}
+
+impl Rejection {
+    /// 응답에 실을 상세 목록 (DB 오류는 비어 있다)
+    pub fn violations(&self) -> Vec<Violation> {
+        match self {
+            Rejection::Invalid(violations) => violations.clone(),
+            Rejection::Db(_) => vec![],
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Invalid(violations) => {
+                let messages: Vec<&str> = violations.iter().map(|v| v.message.as_str()).collect();
+                write!(f, "{}", messages.join(" "))
+            }
+            Rejection::Db(e) => write!(f, "DB 오류: {}", e),
+        }
+    }
+}
+
+/// 서버 규칙과 방 규칙을 모두 통과하는지 확인한다. 저장 직전에 각 전송 API에서 부른다.
+pub async fn check<C: ConnectionTrait>(
+    conn: &C,
+    new_message: &NewMessage,
+    attachments: &[AttachmentMeta],
+) -> Result<(), Rejection> {
+    let room = RoomEntity::find_by_id(new_message.room_id)
+        .one(conn)
+        .await
+        .map_err(|e| Rejection::Db(e.to_string()))?;
+    let candidate = Candidate { content: &new_message.content, attachments };
+    let mut violations = validation::server_pipeline().run(&candidate);
+    // 방 규칙은 저장할 때 검사했으므로 여기서 만들기 실패할 일은 없다
+    if let Some((room_id, Some(rules))) = room.map(|r| (r.id, r.rules)) {
+        if let Some(pipeline) = room_pipeline(room_id, &rules) {
+            for violation in pipeline.run(&candidate) {
+                if !violations.contains(&violation) {
+                    violations.push(violation);
+                }
+            }
+        }
+    }
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(Rejection::Invalid(violations))
+    }
+}
+
+// 방 id -> (만들 때 쓴 규칙, 파이프라인)
+type PipelineCache = Mutex<HashMap<i32, (ValidationRules, Arc<Pipeline>)>>;
+
+// 방 규칙으로 만든 파이프라인. 보낼 때마다 정규식을 다시 컴파일하지 않도록 방마다 한 번 만들어 둔다.
+fn room_pipelines() -> &'static PipelineCache {
+    static CACHE: OnceLock<PipelineCache> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+// 규칙이 set_rules 밖에서 바뀌었어도 (가져오기 등) 저장된 규칙과 비교해 다시 만든다
+fn room_pipeline(room_id: i32, rules: &ValidationRules) -> Option<Arc<Pipeline>> {
+    if let Some((cached, pipeline)) = room_pipelines().lock().unwrap().get(&room_id) {
+        if cached == rules {
+            return Some(pipeline.clone());
+        }
+    }
+    let pipeline = Arc::new(Pipeline::from_rules(rules).ok()?);
+    room_pipelines().lock().unwrap().insert(room_id, (rules.clone(), pipeline.clone()));
+    Some(pipeline)
+}
+
+/// PUT /room/{id}/rules: 방의 메시지 검증 규칙. 참가자만 바꿀 수 있다.
+pub async fn set_rules(
+    State(conn): State<DatabaseConnection>,
+    Path(room_id): Path<i32>,
+    Json(update): Json<RulesUpdate>,
+) -> Json<ApiResponse<Room>> {
+    let fail = |e: String| Json(ApiResponse { success: 0, error: Some(e), data: None });
+    if let Some(rules) = &update.rules {
+        if let Err(e) = Pipeline::from_rules(rules) {
+            return fail(e);
+        }
+    }
+    let room = match RoomEntity::find_by_id(room_id).one(&conn).await {
+        Ok(Some(room)) if participants_of(&room).contains(&update.username) => room,
+        Ok(Some(_)) => return fail("참여 중인 방이 아닙니다.".to_string()),
+        Ok(None) => return fail("존재하지 않는 방입니다.".to_string()),
+        Err(e) => return fail(format!("DB 오류: {}", e)),
+    };
+    let mut room: ActiveRoom = room.into();
+    room.rules = ActiveValue::Set(update.rules);
+    match room.update(&conn).await {
+        Ok(room) => {
+            room_pipelines().lock().unwrap().remove(&room.id);
+            Json(ApiResponse { success: 1, error: None, data: Some(room) })
+        }
+        Err(e) => fail(format!("DB 오류: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::MessageContent;
+
+    #[test]
+    fn room_pipeline_is_reused_until_rules_change() {
+        let rules = ValidationRules { forbidden_patterns: vec!["(?i)spam".to_string()], ..Default::default() };
+        let first = room_pipeline(-1, &rules).unwrap();
+        assert!(Arc::ptr_eq(&first, &room_pipeline(-1, &rules).unwrap()));
+
+        let changed = ValidationRules { forbidden_patterns: vec!["(?i)ham".to_string()], ..Default::default() };
+        let second = room_pipeline(-1, &changed).unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+        let content = MessageContent::text("ham and eggs");
+        assert_eq!(second.run(&Candidate { content: &content, attachments: &[] }).len(), 1);
+    }
+}
@@ -0,0 +1,121 @@
+use axum::{
+    extract::{Multipart, State},
+    http::HeaderMap,
+    Json,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseConnection, TransactionTrait};
+use tokio::sync::broadcast;
+
+use crate::content::MessageContent;
+use crate::entities::attachment::ActiveModel as ActiveAttachment;
+use crate::media::{audio, store};
+use crate::validation::AttachmentMeta;
+
+use super::bot;
+use super::chat::{insert_message, NewMessage, SendResponse};
+use super::event::ChatEvent;
+use super::outgoing;
+use super::validation;
+
+fn fail(error: impl ToString) -> Json<SendResponse> {
+    Json(SendResponse { success: 0, error: Some(error.to_string()), chat: None, ..Default::default() })
+}
+
+/// multipart 필드: sender, room_id, file
+pub async fn send_voice(
+    State(conn): State<DatabaseConnection>,
+    State(queue): State<broadcast::Sender<ChatEvent>>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Json<SendResponse> {
+    let mut sender = String::new();
+    let mut room_id: Option<i32> = None;
+    let mut file: Option<Vec<u8>> = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return fail(format!("업로드를 읽을 수 없습니다: {}", e)),
+        };
+        match field.name().unwrap_or_default() {
+            "sender" => sender = field.text().await.unwrap_or_default(),
+            "room_id" => room_id = field.text().await.ok().and_then(|v| v.trim().parse().ok()),
+            "file" => match field.bytes().await {
+                Ok(bytes) => file = Some(bytes.to_vec()),
+                Err(_) => return fail(audio::AudioError::TooLarge),
+            },
+            _ => {}
+        }
+    }
+
+    // 입력값 검증
+    let (Some(room_id), Some(file)) = (room_id, file) else {
+        return fail("room_id와 음성 파일이 필요합니다.");
+    };
+    if sender.trim().is_empty() {
+        return fail("보내는 사람을 입력하세요.");
+    }
+    // 봇 계정으로 보내려면 그 봇의 API 토큰이 있어야 한다
+    if let Err(e) = bot::authorize_sender(&conn, &headers, &sender).await {
+        return fail(e);
+    }
+
+    // 디코딩은 CPU를 쓰므로 blocking 스레드에서
+    let (info, file) = match tokio::task::spawn_blocking(move || (audio::analyze(&file), file)).await {
+        Ok((Ok(info), file)) => (info, file),
+        Ok((Err(e), _)) => return fail(e),
+        Err(_) => return fail("음성 파일 분석에 실패했습니다."),
+    };
+
+    let new_message = NewMessage {
+        sender,
+        room_id,
+        content: MessageContent::Voice { duration_ms: info.duration_ms },
+        client_msg_id: None,
+    };
+    let meta = AttachmentMeta { kind: "voice".to_string(), size: file.len() as u64 };
+    if let Err(rejection) = validation::check(&conn, &new_message, &[meta]).await {
+        return Json(SendResponse::rejected(rejection));
+    }
+
+    let storage_key = match store::save(&file, info.container.extension()).await {
+        Ok(key) => key,
+        Err(_) => return fail("음성 파일 저장에 실패했습니다."),
+    };
+    // 첨부 없는 음성 메시지가 남지 않도록 메시지와 첨부를 한 트랜잭션에서 저장
+    let created = async {
+        let txn = conn.begin().await.map_err(|e| format!("DB 오류: {}", e))?;
+        let chat = insert_message(&txn, &new_message).await?;
+        ActiveAttachment {
+            id: ActiveValue::NotSet,
+            chat_id: ActiveValue::Set(chat.id),
+            kind: ActiveValue::Set("voice".to_string()),
+            mime: ActiveValue::Set(info.container.mime().to_string()),
+            storage_key: ActiveValue::Set(storage_key.clone()),
+            size: ActiveValue::Set(file.len() as i64),
+            duration_ms: ActiveValue::Set(Some(info.duration_ms as i32)),
+            waveform: ActiveValue::Set(Some(serde_json::to_string(&info.waveform).unwrap())),
+            created_at: ActiveValue::Set(chrono::Utc::now().naive_utc()),
+            view_once: ActiveValue::Set(false),
+            viewed_at: ActiveValue::Set(None),
+            viewed_by: ActiveValue::Set(None),
+        }
+        .insert(&txn)
+        .await
+        .map_err(|_| "음성 메시지 저장에 실패했습니다.".to_string())?;
+        txn.commit().await.map_err(|e| format!("DB 오류: {}", e))?;
+        Ok::<_, String>(chat)
+    }
+    .await;
+    let chat = match created {
+        Ok(chat) => chat,
+        Err(e) => {
+            let _ = store::remove(&storage_key).await;
+            return fail(e);
+        }
+    };
+
+    outgoing::publish(&conn, &queue, ChatEvent::Message(chat.clone())).await;
+    Json(SendResponse { success: 1, error: None, chat: Some(chat), ..Default::default() })
+}
@@ -0,0 +1,157 @@
+//! 배포 알림 봇. CI가 `POST /notify`로 배포 상태를 알리면 방에 올리고,
+//! 방에서 `!deploys`라고 하면 서비스·환경별 마지막 배포를 보여준다.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use super::{serve, BotClient, BotEvent, BotHandler, Reply};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployStatus {
+    Started,
+    Succeeded,
+    Failed,
+    RolledBack,
+}
+
+/// CI가 보내는 배포 알림
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeployNotice {
+    pub room_id: i32,
+    pub service: String,
+    pub environment: String,
+    pub version: String,
+    pub status: DeployStatus,
+    /// 빌드 로그 등
+    #[serde(default)]
+    pub url: Option<String>,
+    /// 배포한 사람
+    #[serde(default)]
+    pub actor: Option<String>,
+}
+
+impl DeployNotice {
+    pub fn text(&self) -> String {
+        let (icon, what) = match self.status {
+            DeployStatus::Started => ("🚀", "배포 시작"),
+            DeployStatus::Succeeded => ("✅", "배포 완료"),
+            DeployStatus::Failed => ("❌", "배포 실패"),
+            DeployStatus::RolledBack => ("↩️", "롤백"),
+        };
+        let mut text = format!("{} {} {} → {} {}", icon, self.service, self.version, self.environment, what);
+        if let Some(actor) = &self.actor {
+            text.push_str(&format!(" (by {})", actor));
+        }
+        if let Some(url) = &self.url {
+            text.push('\n');
+            text.push_str(url);
+        }
+        text
+    }
+}
+
+/// 방별로 (서비스, 환경) → 마지막 알림
+#[derive(Default)]
+pub struct DeployNotifier {
+    latest: Mutex<BTreeMap<(i32, String, String), DeployNotice>>,
+}
+
+impl DeployNotifier {
+    pub fn record(&self, notice: &DeployNotice) -> Reply {
+        let key = (notice.room_id, notice.service.clone(), notice.environment.clone());
+        self.latest.lock().unwrap().insert(key, notice.clone());
+        Reply::new(notice.room_id, notice.text())
+    }
+
+    fn status(&self, room_id: i32) -> String {
+        let latest = self.latest.lock().unwrap();
+        let lines: Vec<String> = latest
+            .iter()
+            .filter(|((room, _, _), _)| *room == room_id)
+            .map(|(_, notice)| format!("- {}", notice.text().lines().next().unwrap_or_default()))
+            .collect();
+        if lines.is_empty() {
+            "이 방에 알린 배포가 없습니다.".to_string()
+        } else {
+            format!("최근 배포\n{}", lines.join("\n"))
+        }
+    }
+}
+
+impl BotHandler for DeployNotifier {
+    fn handle(&self, event: &BotEvent) -> Vec<Reply> {
+        match event.message() {
+            Some((sender, text)) if sender != event.bot && text.trim() == "!deploys" => {
+                vec![Reply::new(event.room_id, self.status(event.room_id))]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+#[derive(Clone)]
+struct NotifyState {
+    notifier: Arc<DeployNotifier>,
+    client: BotClient,
+}
+
+/// webhook 수신(`POST /`)과 CI 알림(`POST /notify`, 봇 토큰으로 인증)을 함께 띄운다
+pub fn router(notifier: Arc<DeployNotifier>, client: BotClient) -> Router {
+    let notify = Router::new()
+        .route("/notify", post(|State(state): State<NotifyState>, headers: HeaderMap, Json(notice): Json<DeployNotice>| async move {
+            let token = headers
+                .get(header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "));
+            if !token.is_some_and(|t| state.client.is_own_token(t)) {
+                return (StatusCode::UNAUTHORIZED, "invalid token".to_string());
+            }
+            let reply = state.notifier.record(&notice);
+            match state.client.send(&reply).await {
+                Ok(()) => (StatusCode::OK, "ok".to_string()),
+                Err(e) => (StatusCode::BAD_GATEWAY, e),
+            }
+        }))
+        .with_state(NotifyState { notifier: notifier.clone(), client: client.clone() });
+    serve(notifier, client).merge(notify)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notice(service: &str, status: DeployStatus) -> DeployNotice {
+        DeployNotice {
+            room_id: 3,
+            service: service.to_string(),
+            environment: "production".to_string(),
+            version: "1.4.2".to_string(),
+            status,
+            url: Some("https://ci.example.com/42".to_string()),
+            actor: Some("alice".to_string()),
+        }
+    }
+
+    #[test]
+    fn formats_notices_and_reports_latest() {
+        let notifier = DeployNotifier::default();
+        let reply = notifier.record(&notice("api", DeployStatus::Started));
+        assert_eq!(reply.text, "🚀 api 1.4.2 → production 배포 시작 (by alice)\nhttps://ci.example.com/42");
+        notifier.record(&notice("api", DeployStatus::Failed));
+        notifier.record(&notice("web", DeployStatus::Succeeded));
+        assert_eq!(
+            notifier.status(3),
+            "최근 배포\n- ❌ api 1.4.2 → production 배포 실패 (by alice)\n- ✅ web 1.4.2 → production 배포 완료 (by alice)"
+        );
+        assert_eq!(notifier.status(4), "이 방에 알린 배포가 없습니다.");
+    }
+}
@@ -0,0 +1,258 @@
+//! 봇 계정이 받는 이벤트와, 봇을 만드는 쪽에서 쓰는 도구.
+//!
+//! 서버는 봇이 구독한 방 이벤트를 `BotEvent`로 만들어 봇의 webhook 주소로 POST 한다(`webhook`).
+//! 봇은 이벤트를 받아 `BotHandler`로 답을 정하고, 일반 전송 API(`/chat/send`)에 자기 토큰을 붙여 답한다.
+//! 같이 들어 있는 스탠드업 봇과 배포 알림 봇은 이 도구로 만든 예이고, 서버 안에서 띄울 수도 있다(`hosted_router`).
+
+pub mod deploy;
+pub mod standup;
+pub mod webhook;
+
+use std::sync::Arc;
+
+use axum::{extract::State, http::{HeaderMap, StatusCode}, routing::post, Router};
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// 이 서버가 직접 띄우는 봇(`hosted_router`)의 webhook 주소. 루프백이지만 주소 검사 없이 보낸다.
+pub const HOSTED_WEBHOOK_BASE: &str = "http://127.0.0.1:3100/bots/";
+
+/// 봇이 구독할 수 있는 이벤트 (ChatEvent의 이름과 같다).
+/// reaction은 아직 메시지 반응 기능이 없어 보낼 이벤트가 없으므로, 반응 기능이 생길 때 함께 추가한다.
+pub const EVENTS: [&str; 2] = ["message", "member_joined"];
+
+/// webhook으로 보내는 본문
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BotEvent {
+    /// 전달마다 새로 만드는 id. 재시도해도 같으므로 봇은 이것으로 중복을 거를 수 있다.
+    pub delivery_id: String,
+    /// message, member_joined
+    pub event: String,
+    /// 이 이벤트를 받는 봇의 username
+    pub bot: String,
+    pub room_id: i32,
+    /// SSE로 보내는 것과 같은 모양의 이벤트 내용
+    pub data: Value,
+}
+
+impl BotEvent {
+    /// message 이벤트면 (보낸 사람, 본문)
+    pub fn message(&self) -> Option<(&str, &str)> {
+        if self.event != "message" {
+            return None;
+        }
+        Some((self.data.get("sender")?.as_str()?, self.data.get("message")?.as_str()?))
+    }
+
+    /// member_joined 이벤트면 들어온 사람
+    pub fn joined(&self) -> Option<&str> {
+        if self.event != "member_joined" {
+            return None;
+        }
+        self.data.get("username")?.as_str()
+    }
+}
+
+/// 봇이 방에 남길 답
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub room_id: i32,
+    pub text: String,
+}
+
+impl Reply {
+    pub fn new(room_id: i32, text: impl Into<String>) -> Self {
+        Reply { room_id, text: text.into() }
+    }
+}
+
+/// 받은 이벤트에 어떻게 답할지 정한다. 답이 없으면 빈 Vec.
+pub trait BotHandler: Send + Sync {
+    fn handle(&self, event: &BotEvent) -> Vec<Reply>;
+}
+
+/// 봇 토큰으로 채팅 API를 부르는 클라이언트
+#[derive(Clone)]
+pub struct BotClient {
+    /// `http://127.0.0.1:3100/api`처럼 API 경로까지
+    pub base_url: String,
+    pub username: String,
+    token: String,
+    http: reqwest::Client,
+}
+
+impl BotClient {
+    pub fn new(base_url: impl Into<String>, username: impl Into<String>, token: impl Into<String>) -> Self {
+        BotClient {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            username: username.into(),
+            token: token.into(),
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 봇 자신에게 들어오는 요청(배포 알림 등)을 같은 토큰으로 확인할 때
+    pub fn is_own_token(&self, token: &str) -> bool {
+        !self.token.is_empty() && self.token == token
+    }
+
+    /// 일반 전송 API로 메시지를 보낸다
+    pub async fn send(&self, reply: &Reply) -> Result<(), String> {
+        let body = json!({ "sender": self.username, "room_id": reply.room_id, "message": reply.text });
+        let resp = self
+            .http
+            .post(format!("{}/chat/send", self.base_url))
+            .header(header::AUTHORIZATION, format!("Bearer {}", self.token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = resp.status();
+        let text = resp.text().await.map_err(|e| e.to_string())?;
+        let result: Value = serde_json::from_str(&text).map_err(|_| format!("HTTP {}: {}", status, text))?;
+        if result.get("success").and_then(Value::as_i64) == Some(1) {
+            Ok(())
+        } else {
+            Err(result.get("error").and_then(Value::as_str).unwrap_or("전송 실패").to_string())
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Hosted {
+    handler: Arc<dyn BotHandler>,
+    client: BotClient,
+    key: Arc<str>,
+}
+
+/// 봇 하나를 webhook 수신기로 띄운다: `POST /`로 `BotEvent`를 받아 답을 보낸다.
+/// 봇 토큰으로 한 서명이 맞지 않으면 401로 거절한다.
+/// 답은 응답을 막지 않도록 백그라운드에서 보낸다(서버의 재시도가 중복 답을 만들지 않게).
+pub fn serve(handler: Arc<dyn BotHandler>, client: BotClient) -> Router {
+    let key = webhook::signing_key(&client.token).into();
+    Router::new()
+        .route("/", post(|State(bot): State<Hosted>, headers: HeaderMap, body: String| async move {
+            let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+            let now = chrono::Utc::now().timestamp();
+            if webhook::verify(&bot.key, header("X-Bot-Timestamp"), header("X-Bot-Signature"), &body, now).is_err() {
+                return StatusCode::UNAUTHORIZED;
+            }
+            let Ok(event) = serde_json::from_str::<BotEvent>(&body) else {
+                return StatusCode::BAD_REQUEST;
+            };
+            let replies = bot.handler.handle(&event);
+            tokio::spawn(async move {
+                for reply in replies {
+                    if let Err(e) = bot.client.send(&reply).await {
+                        eprintln!("bot {} reply failed: {}", bot.client.username, e);
+                    }
+                }
+            });
+            StatusCode::NO_CONTENT
+        }))
+        .with_state(Hosted { handler, client, key })
+}
+
+/// 환경 변수에 토큰이 있는 기본 봇들을 `/bots/<이름>` 아래에 띄운다.
+/// STANDUP_BOT_TOKEN(+STANDUP_BOT_NAME), DEPLOY_BOT_TOKEN(+DEPLOY_BOT_NAME). 토큰이 없으면 띄우지 않는다.
+/// 봇의 webhook 주소는 `http://127.0.0.1:3100/bots/standup`처럼 등록한다.
+pub fn hosted_router(api_base: &str) -> Router {
+    let client = |prefix: &str, default_name: &str| {
+        let token = std::env::var(format!("{}_BOT_TOKEN", prefix)).ok()?;
+        let name = std::env::var(format!("{}_BOT_NAME", prefix)).unwrap_or_else(|_| default_name.to_string());
+        Some(BotClient::new(api_base, name, token))
+    };
+    let mut router = Router::new();
+    if let Some(client) = client("STANDUP", "standup-bot") {
+        router = router.nest("/bots/standup", serve(Arc::new(standup::StandupBot::default()), client));
+    }
+    if let Some(client) = client("DEPLOY", "deploy-bot") {
+        router = router.nest("/bots/deploy", deploy::router(Arc::new(deploy::DeployNotifier::default()), client));
+    }
+    router
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use std::sync::Mutex;
+
+    use axum::Json;
+
+    use crate::preview::fetch::FetchPolicy;
+
+    type Captured = Arc<Mutex<Vec<(Option<String>, Value)>>>;
+
+    /// /chat/send를 흉내 내며 받은 요청을 모아 두는 로컬 수신기
+    async fn receiver() -> (SocketAddr, Captured) {
+        let captured: Captured = Arc::default();
+        let app = Router::new()
+            .route("/api/chat/send", post(|State(captured): State<Captured>, headers: HeaderMap, Json(body): Json<Value>| async move {
+                let auth = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()).map(str::to_string);
+                captured.lock().unwrap().push((auth, body));
+                Json(json!({ "success": 1 }))
+            }))
+            .with_state(captured.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (addr, captured)
+    }
+
+    #[tokio::test]
+    async fn hosted_bot_replies_through_send_api_with_token() {
+        let (api, captured) = receiver().await;
+        let client = BotClient::new(format!("http://{}/api", api), "standup-bot", "t0ken");
+        let bot = serve(Arc::new(standup::StandupBot::default()), client);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let hook = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, bot).await.unwrap() });
+
+        let event = BotEvent {
+            delivery_id: "d1".to_string(),
+            event: "message".to_string(),
+            bot: "standup-bot".to_string(),
+            room_id: 7,
+            data: json!({ "sender": "alice", "message": "!standup", "room_id": 7 }),
+        };
+        let url = format!("http://{}/", hook);
+        let policy = webhook::RetryPolicy {
+            attempts: 1,
+            addresses: FetchPolicy { allow_hosts: vec!["127.0.0.1".to_string()], ..Default::default() },
+            ..Default::default()
+        };
+        // 다른 키로 서명했거나 서명이 없으면 받지 않는다
+        let forged = webhook::deliver(&url, &event, &webhook::signing_key("guess"), &policy).await;
+        assert_eq!(forged, Err("HTTP 401 Unauthorized".to_string()));
+        let unsigned = reqwest::Client::new()
+            .post(&url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_string(&event).unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unsigned.status(), StatusCode::UNAUTHORIZED);
+
+        let delivered = webhook::deliver(&url, &event, &webhook::signing_key("t0ken"), &policy).await;
+        assert_eq!(delivered, Ok(1));
+
+        for _ in 0..50 {
+            if !captured.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let captured = captured.lock().unwrap();
+        let (auth, body) = &captured[0];
+        assert_eq!(auth.as_deref(), Some("Bearer t0ken"));
+        assert_eq!(body["sender"], "standup-bot");
+        assert_eq!(body["room_id"], 7);
+        assert!(body["message"].as_str().unwrap().contains("스탠드업"));
+    }
+}
@@ -0,0 +1,99 @@
+//! 스탠드업 봇. `!standup`으로 시작하면 그동안 방에 올라온 답을 모았다가 `!standup end`에 요약한다.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::{BotEvent, BotHandler, Reply};
+
+const QUESTIONS: &str = "오늘의 스탠드업을 시작합니다. 어제 한 일 / 오늘 할 일 / 막힌 점을 한 메시지로 남겨 주세요. 마치려면 !standup end";
+
+/// 방별로 진행 중인 스탠드업. 한 사람이 여러 번 쓰면 이어 붙인다.
+#[derive(Default)]
+pub struct StandupBot {
+    sessions: Mutex<HashMap<i32, Vec<(String, String)>>>,
+}
+
+impl StandupBot {
+    fn on_message(&self, room_id: i32, sender: &str, text: &str) -> Option<String> {
+        let mut sessions = self.sessions.lock().unwrap();
+        match text.trim() {
+            "!standup" | "!standup start" => {
+                if sessions.contains_key(&room_id) {
+                    return Some("이미 스탠드업이 진행 중입니다. 마치려면 !standup end".to_string());
+                }
+                sessions.insert(room_id, Vec::new());
+                Some(QUESTIONS.to_string())
+            }
+            "!standup end" => {
+                let Some(answers) = sessions.remove(&room_id) else {
+                    return Some("진행 중인 스탠드업이 없습니다. !standup 으로 시작하세요.".to_string());
+                };
+                Some(summary(&answers))
+            }
+            text => {
+                let answers = sessions.get_mut(&room_id)?;
+                if text.starts_with('!') || text.is_empty() {
+                    return None;
+                }
+                match answers.iter_mut().find(|(who, _)| who == sender) {
+                    Some((_, answer)) => {
+                        answer.push_str(" / ");
+                        answer.push_str(text);
+                    }
+                    None => answers.push((sender.to_string(), text.to_string())),
+                }
+                None
+            }
+        }
+    }
+}
+
+fn summary(answers: &[(String, String)]) -> String {
+    if answers.is_empty() {
+        return "스탠드업을 마쳤습니다. 남긴 사람이 없습니다.".to_string();
+    }
+    let mut out = format!("스탠드업 요약 ({}명)", answers.len());
+    for (who, answer) in answers {
+        out.push_str(&format!("\n- {}: {}", who, answer));
+    }
+    out
+}
+
+impl BotHandler for StandupBot {
+    fn handle(&self, event: &BotEvent) -> Vec<Reply> {
+        if let Some((sender, text)) = event.message() {
+            // 자기 메시지에는 답하지 않는다
+            if sender == event.bot {
+                return vec![];
+            }
+            return self.on_message(event.room_id, sender, text).map(|t| Reply::new(event.room_id, t)).into_iter().collect();
+        }
+        if let Some(username) = event.joined() {
+            if self.sessions.lock().unwrap().contains_key(&event.room_id) {
+                return vec![Reply::new(event.room_id, format!("{}님, 스탠드업이 진행 중이에요. {}", username, QUESTIONS))];
+            }
+        }
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_answers_until_end() {
+        let bot = StandupBot::default();
+        assert_eq!(bot.on_message(1, "alice", "!standup").as_deref(), Some(QUESTIONS));
+        assert!(bot.on_message(1, "alice", "어제 로그인 수정").is_none());
+        assert!(bot.on_message(1, "bob", "오늘 배포").is_none());
+        assert!(bot.on_message(1, "alice", "막힌 점 없음").is_none());
+        // 다른 방은 영향 없음
+        assert!(bot.on_message(2, "carol", "안녕").is_none());
+        assert_eq!(
+            bot.on_message(1, "bob", "!standup end").unwrap(),
+            "스탠드업 요약 (2명)\n- alice: 어제 로그인 수정 / 막힌 점 없음\n- bob: 오늘 배포"
+        );
+        assert!(bot.on_message(1, "bob", "!standup end").unwrap().contains("없습니다"));
+    }
+}
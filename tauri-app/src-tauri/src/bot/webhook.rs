@@ -0,0 +1,168 @@
+//! 봇 webhook 전달. 연결 실패, 시간 초과, 5xx, 429는 간격을 두 배씩 늘리며 다시 보내고
+//! 그 밖의 4xx는 봇이 받지 않겠다는 뜻으로 보고 바로 그만둔다.
+//!
+//! 요청마다 `X-Bot-Timestamp`와 `X-Bot-Signature`(나가는 webhook과 같은 `sha256=` HMAC)를 붙인다.
+//! 키는 봇 토큰의 SHA-256 hex라서 서버는 저장된 token_hash로 서명하고, 봇은 자기 토큰으로 같은 키를 만든다.
+//! 주소는 링크 미리보기와 같은 규칙으로 검사해서 내부망으로는 보내지 않고, 리다이렉트도 따라가지 않는다.
+
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::{header, StatusCode};
+use sha2::Sha256;
+
+use super::BotEvent;
+use crate::api::{bot::hash_token, outgoing::sign};
+use crate::preview::fetch::{self, FetchError, FetchPolicy};
+
+/// 서명한 시각과 받은 시각이 이만큼 넘게 차이 나면 다시 보낸 요청으로 보고 거부한다
+pub const MAX_SKEW_SECS: i64 = 300;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// 처음 보내기를 포함한 최대 시도 횟수
+    pub attempts: u32,
+    /// 첫 재시도 전 대기 (이후 두 배씩)
+    pub base_delay: Duration,
+    pub timeout: Duration,
+    /// 보낼 수 있는 주소 (내부망이어도 보낼 호스트는 allow_hosts에)
+    pub addresses: FetchPolicy,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: 4,
+            base_delay: Duration::from_secs(1),
+            timeout: Duration::from_secs(5),
+            addresses: FetchPolicy::default(),
+        }
+    }
+}
+
+fn retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// 봇 토큰으로 서명 키를 만든다 (서버에 저장된 token_hash와 같다)
+pub fn signing_key(token: &str) -> String {
+    hash_token(token)
+}
+
+/// 받은 요청의 서명을 확인한다. now는 유닉스 시각(초).
+pub fn verify(key: &str, timestamp: Option<&str>, signature: Option<&str>, body: &str, now: i64) -> Result<(), &'static str> {
+    let timestamp: i64 = timestamp.and_then(|t| t.parse().ok()).ok_or("서명 시각이 없습니다.")?;
+    if (now - timestamp).abs() > MAX_SKEW_SECS {
+        return Err("서명 시각이 너무 오래되었습니다.");
+    }
+    let digest = signature.and_then(|s| s.strip_prefix("sha256=")).and_then(from_hex).ok_or("서명이 없습니다.")?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    // 비교 시간으로 서명을 추측하지 못하게 hmac의 상수 시간 비교를 쓴다
+    mac.verify_slice(&digest).map_err(|_| "서명이 맞지 않습니다.")
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// 이벤트를 key로 서명해 보낸다. 성공하면 몇 번째 시도에서 받았는지, 실패하면 마지막 이유를 돌려준다.
+pub async fn deliver(
+    url: &str,
+    event: &BotEvent,
+    key: &str,
+    policy: &RetryPolicy,
+) -> Result<u32, String> {
+    let body = serde_json::to_string(event).map_err(|e| e.to_string())?;
+    let mut last_error = String::new();
+    for attempt in 1..=policy.attempts.max(1) {
+        if attempt > 1 {
+            tokio::time::sleep(policy.base_delay * 2u32.pow(attempt - 2)).await;
+        }
+        // 매번 주소를 다시 확인하고 그 결과로 연결한다 (DNS를 바꿔 내부망으로 돌리는 것 방지)
+        let (client, target) = match fetch::pinned_client(url, &policy.addresses).await {
+            Ok(pinned) => pinned,
+            Err(e @ (FetchError::InvalidUrl | FetchError::Blocked(_))) => return Err(e.to_string()),
+            Err(e) => {
+                last_error = e.to_string();
+                continue;
+            }
+        };
+        // 재시도는 늦게 보내는 것이므로 시각과 서명을 새로 만든다
+        let timestamp = chrono::Utc::now().timestamp();
+        let sent = client
+            .post(target)
+            .timeout(policy.timeout)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("X-Bot-Event", event.event.as_str())
+            .header("X-Bot-Delivery", event.delivery_id.as_str())
+            .header("X-Bot-Timestamp", timestamp.to_string())
+            .header("X-Bot-Signature", sign(key, timestamp, &body))
+            .body(body.clone())
+            .send()
+            .await;
+        match sent {
+            Ok(resp) if resp.status().is_success() => return Ok(attempt),
+            Ok(resp) if retryable(resp.status()) => last_error = format!("HTTP {}", resp.status()),
+            Ok(resp) => return Err(format!("HTTP {}", resp.status())),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use axum::{extract::State, routing::post, Router};
+
+    /// 처음 두 번은 503, 그다음부터 204를 주는 수신기
+    #[tokio::test]
+    async fn retries_server_errors_then_gives_up_on_client_errors() {
+        let hits = Arc::new(AtomicU32::new(0));
+        let app = Router::new()
+            .route("/flaky", post(|State(hits): State<Arc<AtomicU32>>| async move {
+                if hits.fetch_add(1, Ordering::SeqCst) < 2 { StatusCode::SERVICE_UNAVAILABLE } else { StatusCode::NO_CONTENT }
+            }))
+            .route("/gone", post(|| async { StatusCode::GONE }))
+            .with_state(hits.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let event = BotEvent {
+            delivery_id: "d1".to_string(),
+            event: "message".to_string(),
+            bot: "b".to_string(),
+            room_id: 1,
+            data: serde_json::json!({}),
+        };
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(10),
+            addresses: FetchPolicy { allow_hosts: vec!["127.0.0.1".to_string()], ..Default::default() },
+            ..Default::default()
+        };
+        assert_eq!(deliver(&format!("http://{}/flaky", addr), &event, "k", &policy).await, Ok(3));
+        assert_eq!(deliver(&format!("http://{}/gone", addr), &event, "k", &policy).await, Err("HTTP 410 Gone".to_string()));
+        // 허용하지 않은 내부 주소로는 보내지 않는다
+        let blocked = deliver(&format!("http://{}/flaky", addr), &event, "k", &RetryPolicy::default()).await;
+        assert_eq!(blocked, Err("blocked address: 127.0.0.1".to_string()));
+    }
+
+    #[test]
+    fn verifies_signature_and_freshness() {
+        let key = signing_key("t0ken");
+        let signature = sign(&key, 1_000, "{}");
+        assert_eq!(verify(&key, Some("1000"), Some(&signature), "{}", 1_010), Ok(()));
+        assert!(verify(&key, Some("1000"), Some(&signature), "{\"x\":1}", 1_010).is_err());
+        assert!(verify(&signing_key("other"), Some("1000"), Some(&signature), "{}", 1_010).is_err());
+        assert!(verify(&key, Some("1000"), Some(&signature), "{}", 1_000 + MAX_SKEW_SECS + 1).is_err());
+        assert!(verify(&key, None, None, "{}", 1_000).is_err());
+    }
+}
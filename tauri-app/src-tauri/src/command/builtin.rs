@@ -0,0 +1,174 @@
+//! 기본 명령어: /me, /shrug, /poll, /remind, /mute, /topic
+
+use super::{parse_duration, split_args, Action, Invocation, MuteSpan, SlashCommand};
+use crate::content::MessageContent;
+
+const SHRUG: &str = r"¯\_(ツ)_/¯";
+const MAX_TOPIC_CHARS: usize = 100;
+
+/// `/me 손을 흔든다` → `* alice 손을 흔든다`
+pub struct Me;
+
+impl SlashCommand for Me {
+    fn name(&self) -> &'static str {
+        "me"
+    }
+    fn usage(&self) -> &'static str {
+        "/me 행동"
+    }
+    fn description(&self) -> &'static str {
+        "내 행동을 3인칭으로 보여줍니다."
+    }
+    fn run(&self, inv: &Invocation) -> Result<Action, String> {
+        if inv.args.is_empty() {
+            return Err("사용법: /me 행동".to_string());
+        }
+        Ok(Action::Post(MessageContent::text(format!("* {} {}", inv.username, inv.args))))
+    }
+}
+
+pub struct Shrug;
+
+impl SlashCommand for Shrug {
+    fn name(&self) -> &'static str {
+        "shrug"
+    }
+    fn usage(&self) -> &'static str {
+        "/shrug [메시지]"
+    }
+    fn description(&self) -> &'static str {
+        "메시지 끝에 ¯\\_(ツ)_/¯ 를 붙입니다."
+    }
+    fn run(&self, inv: &Invocation) -> Result<Action, String> {
+        let text = if inv.args.is_empty() { SHRUG.to_string() } else { format!("{} {}", inv.args, SHRUG) };
+        Ok(Action::Post(MessageContent::text(text)))
+    }
+}
+
+/// `/poll "질문" "선택지1" "선택지2"`
+pub struct PollCommand;
+
+impl SlashCommand for PollCommand {
+    fn name(&self) -> &'static str {
+        "poll"
+    }
+    fn usage(&self) -> &'static str {
+        "/poll \"질문\" \"선택지1\" \"선택지2\" ..."
+    }
+    fn description(&self) -> &'static str {
+        "투표를 만듭니다."
+    }
+    fn run(&self, inv: &Invocation) -> Result<Action, String> {
+        let mut args = split_args(inv.args)?.into_iter();
+        let Some(question) = args.next() else {
+            return Err(format!("사용법: {}", self.usage()));
+        };
+        // 선택지 개수와 길이는 투표 API가 다시 검사한다
+        Ok(Action::Poll { question, options: args.collect() })
+    }
+}
+
+/// `/remind 30m 회의 준비`
+pub struct Remind;
+
+impl SlashCommand for Remind {
+    fn name(&self) -> &'static str {
+        "remind"
+    }
+    fn usage(&self) -> &'static str {
+        "/remind 30m|2h|1d 내용"
+    }
+    fn description(&self) -> &'static str {
+        "정한 시간 뒤에 이 방에 알림을 보냅니다."
+    }
+    fn run(&self, inv: &Invocation) -> Result<Action, String> {
+        let (when, text) = inv.args.split_once(char::is_whitespace).unwrap_or((inv.args, ""));
+        let after = parse_duration(when).map_err(|e| format!("{} 사용법: {}", e, self.usage()))?;
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(format!("알림 내용을 입력하세요. 사용법: {}", self.usage()));
+        }
+        Ok(Action::Remind { after, text: text.to_string() })
+    }
+}
+
+/// `/mute`(계속), `/mute 1h`, `/mute off`
+pub struct Mute;
+
+impl SlashCommand for Mute {
+    fn name(&self) -> &'static str {
+        "mute"
+    }
+    fn usage(&self) -> &'static str {
+        "/mute [30m|2h|1d|off]"
+    }
+    fn description(&self) -> &'static str {
+        "이 방의 알림을 끄거나 다시 켭니다."
+    }
+    fn run(&self, inv: &Invocation) -> Result<Action, String> {
+        match inv.args {
+            "" => Ok(Action::Mute(MuteSpan::Forever)),
+            "off" | "해제" => Ok(Action::Mute(MuteSpan::Off)),
+            value => parse_duration(value)
+                .map(|d| Action::Mute(MuteSpan::For(d)))
+                .map_err(|e| format!("{} 사용법: {}", e, self.usage())),
+        }
+    }
+}
+
+/// `/topic`(보기), `/topic 새 주제`, `/topic -`(지우기)
+pub struct Topic;
+
+impl SlashCommand for Topic {
+    fn name(&self) -> &'static str {
+        "topic"
+    }
+    fn usage(&self) -> &'static str {
+        "/topic [새 주제 | -]"
+    }
+    fn description(&self) -> &'static str {
+        "방 주제를 보거나 바꿉니다. '-'는 주제를 지웁니다."
+    }
+    fn run(&self, inv: &Invocation) -> Result<Action, String> {
+        match inv.args {
+            "" => Ok(Action::Reply(match &inv.room.topic {
+                Some(topic) => format!("현재 주제: {}", topic),
+                None => "주제가 없습니다.".to_string(),
+            })),
+            "-" => Ok(Action::Topic(None)),
+            topic if topic.chars().count() > MAX_TOPIC_CHARS => {
+                Err(format!("주제는 {}자 이내여야 합니다.", MAX_TOPIC_CHARS))
+            }
+            topic => Ok(Action::Topic(Some(topic.to_string()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::room::Model as Room;
+
+    fn run(command: &dyn SlashCommand, args: &str) -> Result<Action, String> {
+        let room = Room { id: 1, participants: "[]".to_string(), name: None, message_ttl: None, rules: None, topic: None };
+        command.run(&Invocation { username: "alice", room: &room, args })
+    }
+
+    #[test]
+    fn builtins_turn_args_into_actions() {
+        assert_eq!(run(&Me, "waves").unwrap(), Action::Post(MessageContent::text("* alice waves")));
+        assert_eq!(run(&Shrug, "몰라").unwrap(), Action::Post(MessageContent::text(r"몰라 ¯\_(ツ)_/¯")));
+        assert_eq!(
+            run(&PollCommand, r#""점심?" 김밥 라면"#).unwrap(),
+            Action::Poll { question: "점심?".to_string(), options: vec!["김밥".to_string(), "라면".to_string()] }
+        );
+        assert_eq!(
+            run(&Remind, "30m 회의 준비").unwrap(),
+            Action::Remind { after: chrono::Duration::minutes(30), text: "회의 준비".to_string() }
+        );
+        assert!(run(&Remind, "나중에 회의").is_err());
+        assert_eq!(run(&Mute, "off").unwrap(), Action::Mute(MuteSpan::Off));
+        assert_eq!(run(&Topic, "-").unwrap(), Action::Topic(None));
+        assert_eq!(run(&Topic, "").unwrap(), Action::Reply("주제가 없습니다.".to_string()));
+    }
+}
@@ -0,0 +1,203 @@
+//! `/`로 시작하는 메시지를 처리하는 명령어 모음.
+//!
+//! 명령어는 입력을 해석해 무엇을 할지(`Action`)만 돌려주고, 저장이나 브로드캐스트는 api::command가 한다.
+//! `//`로 시작하면 명령어가 아니라 `/`로 시작하는 일반 메시지로 보낸다.
+
+pub mod builtin;
+
+use std::sync::OnceLock;
+
+use chrono::Duration;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::content::MessageContent;
+use crate::entities::room::Model as Room;
+
+/// 명령어 실행 결과
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// 방에 메시지로 올린다
+    Post(MessageContent),
+    /// 실행한 사람에게만 보이는 답
+    Reply(String),
+    Poll { question: String, options: Vec<String> },
+    /// 지금부터 after 뒤에 방에 알림 메시지를 보낸다
+    Remind { after: Duration, text: String },
+    Mute(MuteSpan),
+    /// None이면 주제를 지운다
+    Topic(Option<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MuteSpan {
+    For(Duration),
+    Forever,
+    Off,
+}
+
+/// 명령어를 실행한 맥락
+pub struct Invocation<'a> {
+    pub username: &'a str,
+    pub room: &'a Room,
+    /// 명령어 이름 뒤의 나머지 (앞뒤 공백 제거)
+    pub args: &'a str,
+}
+
+pub trait SlashCommand: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// 자동완성에 보여줄 사용법 (예: `/remind 10m 할 일`)
+    fn usage(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    /// 이 방에서 쓸 수 있는지 (기본은 항상)
+    fn available(&self, _room: &Room) -> bool {
+        true
+    }
+    fn run(&self, invocation: &Invocation) -> Result<Action, String>;
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CommandInfo {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Default)]
+pub struct Registry {
+    commands: Vec<Box<dyn SlashCommand>>,
+}
+
+impl Registry {
+    pub fn with(mut self, command: impl SlashCommand + 'static) -> Self {
+        self.commands.push(Box::new(command));
+        self
+    }
+
+    pub fn find(&self, name: &str) -> Option<&dyn SlashCommand> {
+        self.commands.iter().find(|c| c.name() == name).map(|c| c.as_ref())
+    }
+
+    /// 방에서 쓸 수 있고 이름이 prefix로 시작하는 명령어 (이름순)
+    pub fn complete(&self, room: &Room, prefix: &str) -> Vec<CommandInfo> {
+        let prefix = prefix.trim_start_matches('/').to_ascii_lowercase();
+        let mut found: Vec<CommandInfo> = self
+            .commands
+            .iter()
+            .filter(|c| c.name().starts_with(&prefix) && c.available(room))
+            .map(|c| CommandInfo { name: c.name(), usage: c.usage(), description: c.description() })
+            .collect();
+        found.sort_by_key(|c| c.name);
+        found
+    }
+}
+
+/// 기본 명령어가 등록된 목록
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        Registry::default()
+            .with(builtin::Me)
+            .with(builtin::Shrug)
+            .with(builtin::PollCommand)
+            .with(builtin::Remind)
+            .with(builtin::Mute)
+            .with(builtin::Topic)
+    })
+}
+
+fn command_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^/([A-Za-z][A-Za-z0-9_-]*)(?:\s+|$)").unwrap())
+}
+
+/// 명령어 모양이면 (소문자 이름, 인자). `//`로 시작하거나 `/ `처럼 이름이 없으면 None.
+pub fn parse(text: &str) -> Option<(String, &str)> {
+    let text = text.trim_start();
+    let caps = command_re().captures(text)?;
+    let whole = caps.get(0)?;
+    Some((caps[1].to_ascii_lowercase(), text[whole.end()..].trim()))
+}
+
+/// 따옴표로 묶은 인자를 하나로 본다: `"점심 뭐 먹지" 김밥 "라면 세트"`
+pub fn split_args(args: &str) -> Result<Vec<String>, String> {
+    let mut out = Vec::new();
+    let mut chars = args.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let Some(&first) = chars.peek() else { break };
+        let mut arg = String::new();
+        if first == '"' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => arg.push(c),
+                    None => return Err("따옴표가 닫히지 않았습니다.".to_string()),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                arg.push(c);
+                chars.next();
+            }
+        }
+        out.push(arg);
+    }
+    Ok(out)
+}
+
+/// 명령어로 지정할 수 있는 가장 긴 기간
+pub const MAX_DURATION_DAYS: i64 = 365;
+
+/// `10m`, `2h`, `1d`, `30분`, `3시간`, `2일`. 실패하면 사용법 앞에 붙일 안내 문구를 돌려준다.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let unreadable = || "시간을 읽을 수 없습니다.".to_string();
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).ok_or_else(unreadable)?;
+    let amount: i64 = value[..split].parse().ok().filter(|n| *n > 0).ok_or_else(unreadable)?;
+    let duration = match &value[split..] {
+        "m" | "min" | "분" => Duration::try_minutes(amount),
+        "h" | "시간" => Duration::try_hours(amount),
+        "d" | "일" => Duration::try_days(amount),
+        _ => return Err(unreadable()),
+    };
+    duration
+        .filter(|d| *d <= Duration::days(MAX_DURATION_DAYS))
+        .ok_or_else(|| format!("{}일 이내로만 지정할 수 있습니다.", MAX_DURATION_DAYS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_and_escapes() {
+        assert_eq!(parse("/me waves"), Some(("me".to_string(), "waves")));
+        assert_eq!(parse("/SHRUG"), Some(("shrug".to_string(), "")));
+        assert_eq!(parse("//me literal"), None);
+        assert_eq!(parse("/ nothing"), None);
+        assert_eq!(parse("/usr/bin"), None);
+        assert_eq!(parse("hello /me"), None);
+    }
+
+    #[test]
+    fn splits_quoted_args_and_durations() {
+        assert_eq!(split_args(r#""점심 뭐 먹지" 김밥 "라면 세트""#).unwrap(), vec!["점심 뭐 먹지", "김밥", "라면 세트"]);
+        assert!(split_args(r#""열림"#).is_err());
+        assert_eq!(parse_duration("90m"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("3시간"), Ok(Duration::hours(3)));
+        assert_eq!(parse_duration("365d"), Ok(Duration::days(365)));
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("soon").is_err());
+        // 넘치는 값도 패닉 없이 거절한다
+        assert!(parse_duration("366d").unwrap_err().contains("365일"));
+        assert!(parse_duration("9223372036854775807d").is_err());
+        assert!(parse_duration("99999999999999m").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! 메시지 종류(kind)와 종류별 본문(content).
+//!
+//! chat.content 컬럼(jsonb)에 `{"kind": "...", "content": {...}}` 모양 그대로 저장하고,
+//! API 요청/응답과 SSE 이벤트도 같은 모양을 쓴다. chat.message에는 알림·검색용 한 줄 요약이 들어간다.
+
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{highlight, markdown};
+
+// 저장 전 안전 한도. 글자 수 제한은 validation 파이프라인(서버·방 설정)에서 한다.
+const MAX_TEXT_BYTES: usize = 16 * 1024;
+const MAX_LABEL_CHARS: usize = 100;
+const MAX_LANGUAGE_CHARS: usize = 30;
+/// code 메시지 본문에 싣는 미리보기 한도. 넘치는 코드는 첨부 파일로 따로 받는다.
+pub const CODE_PREVIEW_BYTES: usize = 4 * 1024;
+pub const CODE_PREVIEW_LINES: usize = 40;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, FromJsonQueryResult)]
+#[serde(tag = "kind", content = "content", rename_all = "snake_case")]
+pub enum MessageContent {
+    Text {
+        text: String,
+        #[serde(default)]
+        format: TextFormat,
+        /// markdown이면 서버가 만든 안전한 HTML. 클라이언트가 보낸 값은 무시하고 다시 만든다.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        html: Option<String>,
+    },
+    /// 서버가 남기는 안내 (입장, 설정 변경 등)
+    System { text: String },
+    /// 첨부는 attachment 테이블에 있다
+    Voice { duration_ms: i64 },
+    Image { view_once: bool },
+    Video { view_once: bool },
+    Sticker { pack: String, sticker: String },
+    Location { latitude: f64, longitude: f64, label: Option<String> },
+    Contact { username: String, display_name: Option<String> },
+    /// 선택지와 집계는 poll 테이블에 있다
+    Poll { question: String },
+    /// 코드 조각. 길면 앞부분만 싣고(truncated) 전체는 kind가 code인 첨부로 둔다.
+    Code {
+        language: Option<String>,
+        code: String,
+        line_count: i64,
+        #[serde(default)]
+        truncated: bool,
+        /// 서버가 만든 구문 강조 HTML (class만 달려 있다)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        html: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TextFormat {
+    #[default]
+    Plain,
+    Markdown,
+}
+
+/// 알려진 kind 전체
+pub const KINDS: [&str; 10] = ["text", "system", "voice", "image", "video", "sticker", "location", "contact", "poll", "code"];
+/// /chat/send로 클라이언트가 직접 보낼 수 있는 kind (나머지는 전용 API나 서버만 만든다)
+pub const SENDABLE_KINDS: [&str; 4] = ["text", "sticker", "location", "contact"];
+
+impl MessageContent {
+    pub fn text(text: impl Into<String>) -> Self {
+        MessageContent::Text { text: text.into(), format: TextFormat::Plain, html: None }
+    }
+
+    /// 저장 직전에 호출. markdown 본문의 HTML을 원문에서 다시 만든다.
+    pub fn rendered(&self) -> Self {
+        match self {
+            MessageContent::Text { text, format, .. } => MessageContent::Text {
+                text: text.clone(),
+                format: *format,
+                html: (*format == TextFormat::Markdown).then(|| markdown::to_html(text)),
+            },
+            MessageContent::Code { language, code, line_count, truncated, .. } => MessageContent::Code {
+                language: language.clone(),
+                code: code.clone(),
+                line_count: *line_count,
+                truncated: *truncated,
+                html: Some(highlight::to_html(code, language.as_deref())),
+            },
+            other => other.clone(),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MessageContent::Text { .. } => "text",
+            MessageContent::System { .. } => "system",
+            MessageContent::Voice { .. } => "voice",
+            MessageContent::Image { .. } => "image",
+            MessageContent::Video { .. } => "video",
+            MessageContent::Sticker { .. } => "sticker",
+            MessageContent::Location { .. } => "location",
+            MessageContent::Contact { .. } => "contact",
+            MessageContent::Poll { .. } => "poll",
+            MessageContent::Code { .. } => "code",
+        }
+    }
+
+    /// kind 이름과 content JSON으로 만든다. 모르는 kind나 모양이 틀린 content는 이유를 담아 거부한다.
+    pub fn parse(kind: &str, content: Value) -> Result<Self, String> {
+        if !KINDS.contains(&kind) {
+            return Err(format!("알 수 없는 메시지 종류 '{}'입니다. ({} 중 하나)", kind, KINDS.join(", ")));
+        }
+        serde_json::from_value(json!({ "kind": kind, "content": content }))
+            .map_err(|e| format!("'{}' 메시지의 content가 올바르지 않습니다: {}", kind, e))
+    }
+
+    /// 값 범위 검사 (형식은 parse에서 이미 확인됨)
+    pub fn validate(&self) -> Result<(), String> {
+        let label_ok = |s: &str| !s.trim().is_empty() && s.chars().count() <= MAX_LABEL_CHARS;
+        match self {
+            MessageContent::Text { text, .. } | MessageContent::System { text } => {
+                if text.trim().is_empty() {
+                    return Err("메시지를 입력하세요.".to_string());
+                }
+                if text.len() > MAX_TEXT_BYTES {
+                    return Err("메시지가 너무 깁니다.".to_string());
+                }
+            }
+            MessageContent::Sticker { pack, sticker } => {
+                if !label_ok(pack) || !label_ok(sticker) {
+                    return Err("스티커 정보가 올바르지 않습니다.".to_string());
+                }
+            }
+            MessageContent::Location { latitude, longitude, label } => {
+                if !(-90.0..=90.0).contains(latitude) || !(-180.0..=180.0).contains(longitude) {
+                    return Err("위도는 -90~90, 경도는 -180~180 사이여야 합니다.".to_string());
+                }
+                if label.as_deref().is_some_and(|l| !label_ok(l)) {
+                    return Err(format!("장소 이름은 1~{}자로 입력하세요.", MAX_LABEL_CHARS));
+                }
+            }
+            MessageContent::Contact { username, display_name } => {
+                if !label_ok(username) || display_name.as_deref().is_some_and(|n| !label_ok(n)) {
+                    return Err("연락처 정보가 올바르지 않습니다.".to_string());
+                }
+            }
+            MessageContent::Poll { question } => {
+                if question.trim().is_empty() {
+                    return Err("투표 질문이 비어 있습니다.".to_string());
+                }
+            }
+            MessageContent::Code { language, code, .. } => {
+                if code.trim().is_empty() {
+                    return Err("코드를 입력하세요.".to_string());
+                }
+                if code.len() > CODE_PREVIEW_BYTES || code.lines().count() > CODE_PREVIEW_LINES {
+                    return Err(format!(
+                        "본문에 싣는 코드는 {}바이트, {}줄 이내여야 합니다.",
+                        CODE_PREVIEW_BYTES, CODE_PREVIEW_LINES
+                    ));
+                }
+                let language_ok = |l: &str| {
+                    !l.is_empty() && l.chars().count() <= MAX_LANGUAGE_CHARS && !l.chars().any(char::is_whitespace)
+                };
+                if language.as_deref().is_some_and(|l| !language_ok(l)) {
+                    return Err(format!("언어 이름은 공백 없이 1~{}자로 입력하세요.", MAX_LANGUAGE_CHARS));
+                }
+            }
+            MessageContent::Voice { .. } | MessageContent::Image { .. } | MessageContent::Video { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// chat.message에 저장하는 한 줄 요약. 종류를 모르는 클라이언트는 이것을 보여준다.
+    pub fn summary(&self) -> String {
+        match self {
+            MessageContent::Text { text, format: TextFormat::Markdown, .. } => markdown::plain_text(text),
+            MessageContent::Text { text, .. } | MessageContent::System { text } => text.clone(),
+            MessageContent::Voice { .. } => "🎤 음성 메시지".to_string(),
+            MessageContent::Image { view_once: false } => "📷 사진".to_string(),
+            MessageContent::Image { view_once: true } => "📷 한 번만 볼 수 있는 사진".to_string(),
+            MessageContent::Video { view_once: false } => "🎬 동영상".to_string(),
+            MessageContent::Video { view_once: true } => "🎬 한 번만 볼 수 있는 동영상".to_string(),
+            MessageContent::Sticker { .. } => "(스티커)".to_string(),
+            MessageContent::Location { label, .. } => format!("📍 {}", label.as_deref().unwrap_or("위치")),
+            MessageContent::Contact { username, display_name } => {
+                format!("👤 {}", display_name.as_deref().unwrap_or(username))
+            }
+            MessageContent::Poll { question } => format!("📊 {}", question),
+            MessageContent::Code { language, line_count, .. } => match language {
+                Some(language) => format!("💻 {} 코드 ({}줄)", language, line_count),
+                None => format!("💻 코드 ({}줄)", line_count),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_as_kind_and_content() {
+        let location = MessageContent::Location { latitude: 37.5, longitude: 127.0, label: Some("회사".to_string()) };
+        let value = serde_json::to_value(&location).unwrap();
+        assert_eq!(value["kind"], "location");
+        assert_eq!(value["content"]["label"], "회사");
+        assert_eq!(MessageContent::parse("location", value["content"].clone()).unwrap(), location);
+        assert_eq!(location.summary(), "📍 회사");
+    }
+
+    #[test]
+    fn rejects_unknown_kind_and_bad_content() {
+        let e = MessageContent::parse("gif", json!({})).unwrap_err();
+        assert!(e.contains("'gif'") && e.contains("text"));
+        let e = MessageContent::parse("location", json!({ "latitude": "north" })).unwrap_err();
+        assert!(e.contains("'location'"));
+        let far = MessageContent::Location { latitude: 91.0, longitude: 0.0, label: None };
+        assert!(far.validate().is_err());
+        assert!(MessageContent::text("   ").validate().is_err());
+    }
+}
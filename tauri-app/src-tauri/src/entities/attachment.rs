@@ -0,0 +1,42 @@
+//! `SeaORM` Entity for attachment table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "attachment")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub chat_id: i32,
+    pub kind: String,             // "voice", "image", "video"
+    pub mime: String,
+    pub storage_key: String,      // 업로드 디렉터리 안의 파일 이름
+    pub size: i64,
+    pub duration_ms: Option<i32>,
+    pub waveform: Option<String>, // 0~100 값의 JSON 배열
+    pub created_at: DateTime,
+    pub view_once: bool,          // 수신자가 한 번 열면 파일을 지움
+    pub viewed_at: Option<DateTime>,
+    pub viewed_by: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::chat::Entity",
+        from = "Column::ChatId",
+        to = "super::chat::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Chat,
+}
+
+impl Related<super::chat::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Chat.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,34 @@
+//! `SeaORM` Entity for attachment_token table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "attachment_token")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub token: String,
+    pub attachment_id: i32,
+    pub username: String,     // 토큰을 받은 수신자
+    pub expires_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::attachment::Entity",
+        from = "Column::AttachmentId",
+        to = "super::attachment::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Attachment,
+}
+
+impl Related<super::attachment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Attachment.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
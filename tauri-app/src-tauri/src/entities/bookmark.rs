@@ -0,0 +1,41 @@
+//! `SeaORM` Entity for bookmark table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "bookmark")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub username: String,
+    pub chat_id: Option<i32>,      // 메시지가 지워지면 NULL
+    pub room_id: i32,
+    pub sender: String,            // 지워진 뒤에도 누구의 메시지였는지 보여주기 위해 남김
+    #[sea_orm(column_type = "Text", nullable)]
+    pub note: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub tags: String,              // JSON 배열 문자열 (room.participants와 같은 방식)
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::chat::Entity",
+        from = "Column::ChatId",
+        to = "super::chat::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Chat,
+}
+
+impl Related<super::chat::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Chat.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
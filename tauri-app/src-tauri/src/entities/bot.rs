@@ -0,0 +1,26 @@
+//! `SeaORM` Entity for bot table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "bot")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(unique)]
+    pub username: String,          // users.username (is_bot = true)
+    pub owner: String,
+    #[sea_orm(unique)]
+    #[serde(skip_serializing)]
+    pub token_hash: String,        // API 토큰의 SHA-256 (hex)
+    pub webhook_url: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub events: String,            // 구독하는 이벤트 이름의 JSON 배열 문자열
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -3,15 +3,26 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+use crate::content::MessageContent;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "chat")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub timestamp: DateTime,
     pub sender: String,
-    pub message: String,
+    pub message: String,            // content의 한 줄 요약 (검색, 알림, 예전 클라이언트용)
+    #[sea_orm(column_type = "JsonBinary")]
+    #[serde(flatten)]
+    pub content: MessageContent,    // kind + content. DB의 kind 컬럼은 여기서 계산됨
     pub room_id: i32,
+    pub expires_at: Option<DateTime>, // 방에 보관 기간이 설정된 경우에만
+    pub forwarded_from_id: Option<i32>, // 전달된 메시지의 원본 (원본이 지워지면 NULL)
+    pub forwarded_sender: Option<String>,
+    pub forwarded_at: Option<DateTime>,
+    pub client_msg_id: Option<String>, // 재전송 중복 방지용 클라이언트 id (sender별 유일)
+    pub webhook_id: Option<i32>,    // 수신 webhook이 남긴 메시지면 그 webhook (sender는 연동 이름)
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
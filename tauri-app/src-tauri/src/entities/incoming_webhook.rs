@@ -0,0 +1,39 @@
+//! `SeaORM` Entity for incoming_webhook table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "incoming_webhook")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub room_id: i32,
+    pub name: String,              // 메시지 보낸 사람 자리에 보이는 연동 이름
+    #[sea_orm(unique)]
+    #[serde(skip_serializing)]
+    pub token_hash: String,        // 주소에 들어가는 비밀 토큰의 SHA-256 (hex)
+    pub created_by: String,
+    pub created_at: DateTime,
+    pub last_used_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::room::Entity",
+        from = "Column::RoomId",
+        to = "super::room::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Room,
+}
+
+impl Related<super::room::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Room.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
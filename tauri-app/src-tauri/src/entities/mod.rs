@@ -7,3 +7,18 @@ pub mod room;
 pub mod room_read;
 pub mod users;
 pub mod friends;
+pub mod attachment;
+pub mod link_preview;
+pub mod chat_delivery;
+pub mod scheduled_chat;
+pub mod attachment_token;
+pub mod poll;
+pub mod poll_option;
+pub mod poll_vote;
+pub mod draft;
+pub mod bookmark;
+pub mod bot;
+pub mod incoming_webhook;
+pub mod outgoing_webhook;
+pub mod webhook_delivery;
+pub mod webhook_attempt;
@@ -0,0 +1,40 @@
+//! `SeaORM` Entity for outgoing_webhook table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "outgoing_webhook")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub room_id: i32,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,            // HMAC-SHA256 서명 키 (만들 때와 새로 발급할 때만 보여준다)
+    #[sea_orm(column_type = "Text")]
+    pub events: String,            // 받을 이벤트 이름의 JSON 배열 문자열
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::room::Entity",
+        from = "Column::RoomId",
+        to = "super::room::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Room,
+}
+
+impl Related<super::room::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Room.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
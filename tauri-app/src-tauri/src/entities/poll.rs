@@ -0,0 +1,46 @@
+//! `SeaORM` Entity for poll table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "poll")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(unique)]
+    pub chat_id: i32,               // 투표가 붙은 메시지
+    pub question: String,
+    pub multiple: bool,             // 여러 개 선택 가능
+    pub anonymous: bool,            // 누가 투표했는지 숨김
+    pub closes_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::chat::Entity",
+        from = "Column::ChatId",
+        to = "super::chat::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Chat,
+    #[sea_orm(has_many = "super::poll_option::Entity")]
+    PollOption,
+}
+
+impl Related<super::chat::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Chat.def()
+    }
+}
+
+impl Related<super::poll_option::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PollOption.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
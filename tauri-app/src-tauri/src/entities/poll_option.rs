@@ -0,0 +1,34 @@
+//! `SeaORM` Entity for poll_option table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "poll_option")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub poll_id: i32,
+    pub position: i32,   // 보여주는 순서
+    pub label: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::poll::Entity",
+        from = "Column::PollId",
+        to = "super::poll::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Poll,
+}
+
+impl Related<super::poll::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Poll.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
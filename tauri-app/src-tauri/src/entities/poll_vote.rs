@@ -0,0 +1,49 @@
+//! `SeaORM` Entity for poll_vote table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "poll_vote")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub poll_id: i32,
+    pub option_id: i32,
+    pub username: String,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::poll::Entity",
+        from = "Column::PollId",
+        to = "super::poll::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Poll,
+    #[sea_orm(
+        belongs_to = "super::poll_option::Entity",
+        from = "Column::OptionId",
+        to = "super::poll_option::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    PollOption,
+}
+
+impl Related<super::poll::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Poll.def()
+    }
+}
+
+impl Related<super::poll_option::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PollOption.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -3,3 +3,18 @@
 pub use super::chat::Entity as Chat;
 pub use super::room::Entity as Room;
 pub use super::users::Entity as Users;
+pub use super::attachment::Entity as Attachment;
+pub use super::link_preview::Entity as LinkPreview;
+pub use super::chat_delivery::Entity as ChatDelivery;
+pub use super::scheduled_chat::Entity as ScheduledChat;
+pub use super::attachment_token::Entity as AttachmentToken;
+pub use super::poll::Entity as Poll;
+pub use super::poll_option::Entity as PollOption;
+pub use super::poll_vote::Entity as PollVote;
+pub use super::draft::Entity as Draft;
+pub use super::bookmark::Entity as Bookmark;
+pub use super::bot::Entity as Bot;
+pub use super::incoming_webhook::Entity as IncomingWebhook;
+pub use super::outgoing_webhook::Entity as OutgoingWebhook;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
+pub use super::webhook_attempt::Entity as WebhookAttempt;
@@ -3,12 +3,19 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::validation::ValidationRules;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
 #[sea_orm(table_name = "room")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub participants: String,
+    pub name: Option<String>,
+    pub message_ttl: Option<i32>, // 사라지는 메시지 보관 기간(초), None이면 계속 보관
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub rules: Option<ValidationRules>, // 방별 메시지 검증 규칙 (서버 규칙에 더해 적용)
+    pub topic: Option<String>,    // /topic으로 정한 주제
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -12,6 +12,7 @@ pub struct Model {
     pub username: String,
     pub last_read_id: Option<i32>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub muted_until: Option<chrono::DateTime<chrono::Utc>>, // /mute로 알림을 끈 기한
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
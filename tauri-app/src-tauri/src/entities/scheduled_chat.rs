@@ -0,0 +1,42 @@
+//! `SeaORM` Entity for scheduled_chat table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "scheduled_chat")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub sender: String,
+    pub room_id: i32,
+    #[sea_orm(column_type = "Text")]
+    pub message: String,
+    pub send_at: DateTime,         // UTC
+    pub status: String,            // pending, sent, canceled, failed
+    pub chat_id: Option<i32>,      // 발송된 chat 행
+    pub error: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+    pub client_msg_id: Option<String>, // 같은 id로 다시 예약하면 처음 예약을 돌려준다
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::room::Entity",
+        from = "Column::RoomId",
+        to = "super::room::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Room,
+}
+
+impl Related<super::room::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Room.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -13,6 +13,8 @@ pub struct Model {
     pub display_name: Option<String>,
     pub status: Option<String>,
     pub avatar: Option<String>,
+    pub is_bot: bool,
+    pub owner: Option<String>, // 봇을 만든 사용자 (사람 계정은 NULL)
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,38 @@
+//! `SeaORM` Entity for webhook_attempt table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_attempt")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub delivery_id: i32,
+    pub attempt: i32,
+    pub status_code: Option<i32>,  // 응답을 못 받았으면 NULL
+    #[sea_orm(column_type = "Text", nullable)]
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    pub attempted_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook_delivery::Entity",
+        from = "Column::DeliveryId",
+        to = "super::webhook_delivery::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    WebhookDelivery,
+}
+
+impl Related<super::webhook_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WebhookDelivery.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
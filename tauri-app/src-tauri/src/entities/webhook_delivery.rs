@@ -0,0 +1,40 @@
+//! `SeaORM` Entity for webhook_delivery table
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Hash, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_delivery")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event: String,
+    #[sea_orm(column_type = "Text")]
+    pub payload: String,           // 보낼 본문 그대로 (재시도해도 같은 바이트에 서명한다)
+    pub status: String,            // pending, delivered, dead
+    pub attempts: i32,
+    pub next_attempt_at: DateTime,
+    pub created_at: DateTime,
+    pub delivered_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::outgoing_webhook::Entity",
+        from = "Column::WebhookId",
+        to = "super::outgoing_webhook::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    OutgoingWebhook,
+}
+
+impl Related<super::outgoing_webhook::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OutgoingWebhook.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
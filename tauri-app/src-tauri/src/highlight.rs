@@ -0,0 +1,85 @@
+//! `code` 메시지의 구문 강조.
+//!
+//! syntect 기본 문법으로 토큰을 나누고, 색 대신 `hl-` 접두사가 붙은 class만 단 `<span>`을 만든다.
+//! 색은 클라이언트가 /chat/code/style.css를 불러 입힌다.
+
+use std::sync::OnceLock;
+
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+const THEME: &str = "InspiredGitHub";
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+// 언어 힌트는 이름(Rust), 확장자(rs), 토큰(rust) 어느 것이든 받는다
+fn find_syntax(language: Option<&str>) -> Option<&'static SyntaxReference> {
+    let language = language?.trim();
+    if language.is_empty() {
+        return None;
+    }
+    let set = syntaxes();
+    set.find_syntax_by_token(language)
+        .or_else(|| set.find_syntax_by_extension(&language.to_ascii_lowercase()))
+}
+
+/// 언어 힌트를 알아들을 수 있는지
+pub fn is_known(language: &str) -> bool {
+    find_syntax(Some(language)).is_some()
+}
+
+/// 코드를 class가 달린 HTML로 바꾼다. 모르는 언어는 이스케이프만 한 일반 텍스트로 낸다.
+pub fn to_html(code: &str, language: Option<&str>) -> String {
+    let set = syntaxes();
+    let syntax = find_syntax(language).unwrap_or_else(|| set.find_syntax_plain_text());
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, set, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        if generator.parse_html_for_line_which_includes_newline(line).is_err() {
+            // 문법 정의가 처리하지 못한 줄이 있으면 강조 없이 보여준다
+            return format!("<pre class=\"hl-code\">{}</pre>", escape(code));
+        }
+    }
+    format!("<pre class=\"hl-code\">{}</pre>", generator.finalize())
+}
+
+/// to_html의 class에 맞춘 스타일시트
+pub fn stylesheet() -> String {
+    let themes = ThemeSet::load_defaults();
+    themes
+        .themes
+        .get(THEME)
+        .and_then(|theme| css_for_theme_with_class_style(theme, CLASS_STYLE).ok())
+        .unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_known_language_with_classes() {
+        let html = to_html("fn main() {}\n", Some("rust"));
+        assert!(html.starts_with("<pre class=\"hl-code\">"));
+        assert!(html.contains("class=\"hl-"));
+        assert!(html.contains("main"));
+        assert!(is_known("rs") && is_known("Rust"));
+    }
+
+    #[test]
+    fn unknown_language_is_escaped_plain_text() {
+        let html = to_html("<script>alert(1)</script>", Some("nope"));
+        assert!(!html.contains("<script"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!is_known("nope"));
+    }
+}
@@ -0,0 +1,206 @@
+//! 카카오톡 "대화 내보내기".
+//!
+//! PC txt: 날짜 구분선 아래 `[이름] [오후 3:04] 내용`.
+//! 모바일 txt: `2024년 1월 5일 오후 3:04, 이름 : 내용` (iOS는 `2024. 1. 5. 오후 3:04, 이름 : 내용`).
+//! 모바일 csv: `Date,User,Message` 머리글 아래 `2024-01-05 15:04:00,"이름","내용"`.
+//! 시각 없는 줄은 앞 메시지에 이어 붙이고, 입장·퇴장 같은 안내는 보낸 사람 없는 메시지로 둔다.
+
+use std::sync::OnceLock;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use chrono_tz::Tz;
+use regex::Regex;
+
+use super::{hour_24, stem, to_utc, ImportedMessage, ImportedRoom, Parsed};
+
+const DEFAULT_NAME: &str = "카카오톡 대화";
+
+/// PC 날짜 구분선: `--------------- 2024년 1월 5일 금요일 ---------------`
+fn pc_date() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^-+ (\d{4})년 (\d{1,2})월 (\d{1,2})일 \S+ -+$").unwrap())
+}
+
+/// PC 메시지: `[이름] [오후 3:04] 내용`
+fn pc_message() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\[(.+?)\] \[(오전|오후) (\d{1,2}):(\d{2})\] ?(.*)$").unwrap())
+}
+
+/// 모바일 줄: `2024년 1월 5일 오후 3:04, 나머지` / `2024. 1. 5. 오후 3:04, 나머지`
+fn mobile_line() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(\d{4})(?:년 |\. )(\d{1,2})(?:월 |\. )(\d{1,2})(?:일|\.) (오전|오후) (\d{1,2}):(\d{2}),? ?(.*)$").unwrap()
+    })
+}
+
+/// 모바일 날짜 줄: `2024년 1월 5일 금요일`
+fn mobile_date() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\d{4}(?:년 |\. )\d{1,2}(?:월 |\. )\d{1,2}(?:일|\.) \S+요일$").unwrap())
+}
+
+/// 보낸 사람 없이 남는 안내 문구
+fn is_notice(line: &str) -> bool {
+    ["님이 들어왔습니다.", "님이 나갔습니다.", "님을 초대했습니다.", "님을 내보냈습니다.", "초대했습니다.", "채팅방 관리자가 메시지를 가렸습니다."]
+        .iter()
+        .any(|suffix| line.ends_with(suffix))
+}
+
+pub fn parse(text: &str, tz: Tz, name_hint: Option<&str>) -> Result<Parsed, String> {
+    let first = text.lines().next().unwrap_or_default();
+    if first.trim_start().starts_with("Date,User,Message") {
+        return parse_csv(text, tz, name_hint);
+    }
+    let name = room_name(first).or(name_hint.map(|n| stem(n).to_string())).unwrap_or_else(|| DEFAULT_NAME.to_string());
+
+    let mut messages: Vec<ImportedMessage> = Vec::new();
+    let mut skipped = 0;
+    let mut day: Option<NaiveDate> = None;
+    // 머리글(방 이름, 저장한 날짜)은 첫 날짜나 메시지가 나오기 전까지 건너뛴다
+    let mut started = false;
+    for line in text.lines().skip(1) {
+        if let Some(c) = pc_date().captures(line) {
+            day = NaiveDate::from_ymd_opt(c[1].parse().unwrap(), c[2].parse().unwrap(), c[3].parse().unwrap());
+            started = true;
+            continue;
+        }
+        if let Some(c) = pc_message().captures(line) {
+            let Some(at) = day.and_then(|d| d.and_hms_opt(hour_24(c[3].parse().unwrap(), Some(&c[2] == "오후")), c[4].parse().unwrap(), 0)) else {
+                skipped += 1;
+                continue;
+            };
+            messages.push(ImportedMessage { sender: Some(c[1].to_string()), sent_at: to_utc(tz, at), text: c[5].to_string() });
+            started = true;
+            continue;
+        }
+        if mobile_date().is_match(line) {
+            started = true;
+            continue;
+        }
+        if let Some(c) = mobile_line().captures(line) {
+            let at = NaiveDate::from_ymd_opt(c[1].parse().unwrap(), c[2].parse().unwrap(), c[3].parse().unwrap())
+                .and_then(|d| d.and_hms_opt(hour_24(c[5].parse().unwrap(), Some(&c[4] == "오후")), c[6].parse().unwrap(), 0));
+            let Some(at) = at else {
+                skipped += 1;
+                continue;
+            };
+            let rest = &c[7];
+            let message = match rest.split_once(" : ") {
+                Some((sender, body)) => ImportedMessage { sender: Some(sender.to_string()), sent_at: to_utc(tz, at), text: body.to_string() },
+                // "저장한 날짜"처럼 머리글에 있는 시각 줄은 메시지가 아니다
+                None if !started => continue,
+                None => ImportedMessage { sender: None, sent_at: to_utc(tz, at), text: rest.to_string() },
+            };
+            messages.push(message);
+            started = true;
+            continue;
+        }
+        if !started {
+            continue;
+        }
+        match messages.last_mut() {
+            Some(last) if is_notice(line) => {
+                let sent_at = last.sent_at;
+                messages.push(ImportedMessage { sender: None, sent_at, text: line.to_string() });
+            }
+            Some(last) => {
+                last.text.push('\n');
+                last.text.push_str(line);
+            }
+            None if line.trim().is_empty() => {}
+            None => skipped += 1,
+        }
+    }
+    // 줄바꿈으로 끝난 메시지의 빈 줄 정리
+    for message in &mut messages {
+        let trimmed = message.text.trim_end_matches('\n').len();
+        message.text.truncate(trimmed);
+    }
+    Ok(Parsed { rooms: vec![ImportedRoom { name, messages }], skipped })
+}
+
+/// 첫 줄 `홍길동 님과 카카오톡 대화` / `개발팀 카카오톡 대화` → 방 이름
+fn room_name(first: &str) -> Option<String> {
+    let first = first.trim();
+    let name = first.strip_suffix(" 님과 카카오톡 대화").or_else(|| first.strip_suffix(" 카카오톡 대화"))?;
+    Some(name.trim().to_string()).filter(|n| !n.is_empty())
+}
+
+fn parse_csv(text: &str, tz: Tz, name_hint: Option<&str>) -> Result<Parsed, String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(text.as_bytes());
+    let mut messages = Vec::new();
+    let mut skipped = 0;
+    for record in reader.records() {
+        let Ok(record) = record else {
+            skipped += 1;
+            continue;
+        };
+        let (Some(date), Some(user), Some(body)) = (record.get(0), record.get(1), record.get(2)) else {
+            skipped += 1;
+            continue;
+        };
+        let Ok(at) = NaiveDateTime::parse_from_str(date.trim(), "%Y-%m-%d %H:%M:%S") else {
+            skipped += 1;
+            continue;
+        };
+        let sender = Some(user.trim().to_string()).filter(|u| !u.is_empty());
+        messages.push(ImportedMessage { sender, sent_at: to_utc(tz, at), text: body.to_string() });
+    }
+    let name = name_hint.map(|n| stem(n).to_string()).unwrap_or_else(|| DEFAULT_NAME.to_string());
+    Ok(Parsed { rooms: vec![ImportedRoom { name, messages }], skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn seoul() -> Tz {
+        "Asia/Seoul".parse().unwrap()
+    }
+
+    #[test]
+    fn parses_pc_export() {
+        let text = "\u{feff}개발팀 카카오톡 대화\n저장한 날짜 : 2024-01-06 10:00:00\n\n\
+--------------- 2024년 1월 5일 금요일 ---------------\n\
+[홍길동] [오후 3:04] 안녕하세요\n\
+두 번째 줄\n\
+김철수님이 들어왔습니다.\n\
+[김철수] [오전 12:30] 늦었네요\n";
+        // 파일 앞의 BOM은 import::parse가 떼어 낸다
+        let parsed = super::super::parse(super::super::Source::Kakao, text.as_bytes(), seoul(), None).unwrap();
+        let room = &parsed.rooms[0];
+        assert_eq!(room.name, "개발팀");
+        assert_eq!(room.messages.len(), 3);
+        assert_eq!(room.messages[0].text, "안녕하세요\n두 번째 줄");
+        assert_eq!(room.messages[0].sent_at, at("2024-01-05 06:04:00"));
+        assert_eq!(room.messages[1].sender, None);
+        assert_eq!(room.messages[2].sent_at, at("2024-01-04 15:30:00"));
+    }
+
+    #[test]
+    fn parses_mobile_txt_and_csv() {
+        let text = "홍길동 님과 카카오톡 대화\n저장한 날짜 : 2024년 1월 6일 오전 10:00\n\n\
+2024년 1월 5일 금요일\n\
+2024년 1월 5일 오후 3:04, 홍길동 : 안녕 : 반가워\n\
+2024. 1. 5. 오후 3:05, 김철수 : 응\n\
+2024년 1월 5일 오후 3:06, 이영희님이 나갔습니다.\n";
+        let room = &parse(text, seoul(), None).unwrap().rooms[0];
+        assert_eq!(room.name, "홍길동");
+        assert_eq!(room.messages.len(), 3);
+        assert_eq!((room.messages[0].sender.as_deref(), room.messages[0].text.as_str()), (Some("홍길동"), "안녕 : 반가워"));
+        assert_eq!(room.messages[1].sent_at, at("2024-01-05 06:05:00"));
+        assert_eq!(room.messages[2].sender, None);
+
+        let csv = "Date,User,Message\n2024-01-05 15:04:00,\"홍길동\",\"여러\n줄\"\nbad,row,x\n";
+        let parsed = parse(csv, seoul(), Some("KakaoTalk_Chat_팀.csv")).unwrap();
+        assert_eq!(parsed.skipped, 1);
+        assert_eq!(parsed.rooms[0].name, "KakaoTalk_Chat_팀");
+        assert_eq!(parsed.rooms[0].messages[0].text, "여러\n줄");
+    }
+}
@@ -0,0 +1,273 @@
+//! 다른 메신저에서 내보낸 대화 기록 가져오기.
+//!
+//! 각 파서는 내보내기 파일을 `ImportedRoom` 목록으로 바꾸기만 한다. 시각은 모두 UTC로 맞춘다.
+//! 외부 이름을 우리 username으로 잇는 매핑 파일(`Mapping`)과, 저장 전에 무엇이 들어갈지 보여주는 점검 보고서(`plan`)도
+//! DB 없이 여기서 만든다. 실제 저장은 `api::import`가 한다.
+
+pub mod kakao;
+pub mod slack;
+pub mod whatsapp;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// 카카오톡 "대화 내보내기" (PC·모바일 txt, 모바일 csv)
+    Kakao,
+    /// Slack 워크스페이스 내보내기 ZIP
+    Slack,
+    /// WhatsApp "채팅 내보내기" txt
+    WhatsApp,
+}
+
+impl Source {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "kakao" => Some(Source::Kakao),
+            "slack" => Some(Source::Slack),
+            "whatsapp" => Some(Source::WhatsApp),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Source::Kakao => "kakao",
+            Source::Slack => "slack",
+            Source::WhatsApp => "whatsapp",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedMessage {
+    /// 외부 서비스에서의 보낸 사람 이름. None이면 입장·퇴장 같은 안내.
+    pub sender: Option<String>,
+    /// UTC
+    pub sent_at: NaiveDateTime,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedRoom {
+    pub name: String,
+    pub messages: Vec<ImportedMessage>,
+}
+
+#[derive(Debug, Default)]
+pub struct Parsed {
+    pub rooms: Vec<ImportedRoom>,
+    /// 알아보지 못해 건너뛴 줄(항목) 수
+    pub skipped: usize,
+}
+
+/// 파일을 읽는다. name_hint는 파일 자체에 방 이름이 없을 때 쓸 이름(보통 업로드한 파일 이름).
+/// 시각대가 없는 형식(카카오톡, WhatsApp)은 tz의 현지 시각으로 본다.
+pub fn parse(source: Source, bytes: &[u8], tz: Tz, name_hint: Option<&str>) -> Result<Parsed, String> {
+    match source {
+        Source::Kakao => kakao::parse(&text_of(bytes), tz, name_hint),
+        Source::Slack => slack::parse(bytes),
+        Source::WhatsApp => whatsapp::parse(&text_of(bytes), tz, name_hint),
+    }
+}
+
+/// 내보내기 txt는 BOM이 붙어 있기도 하다
+fn text_of(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_start_matches('\u{feff}').to_string()
+}
+
+/// 현지 시각 → UTC. 서머타임으로 겹치는 시각은 앞의 것, 건너뛴 시각은 그대로 UTC로 본다.
+fn to_utc(tz: Tz, local: NaiveDateTime) -> NaiveDateTime {
+    tz.from_local_datetime(&local).earliest().map(|t| t.naive_utc()).unwrap_or(local)
+}
+
+/// 오전/오후, AM/PM 12시간제 → 24시간제
+fn hour_24(hour: u32, pm: Option<bool>) -> u32 {
+    match pm {
+        Some(false) if hour == 12 => 0,
+        Some(true) if hour < 12 => hour + 12,
+        _ => hour,
+    }
+}
+
+/// 파일 이름에서 방 이름으로 쓸 부분 (확장자 제거)
+fn stem(name: &str) -> &str {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    name.rsplit_once('.').map(|(stem, _)| stem).filter(|s| !s.is_empty()).unwrap_or(name)
+}
+
+/// 외부 이름 → username. 매핑 파일은 `{"홍길동": "gildong", "Kim Cheolsu": "cheolsu"}` 모양의 JSON이다.
+#[derive(Debug, Default, Clone)]
+pub struct Mapping(HashMap<String, String>);
+
+impl Mapping {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim_start_matches('\u{feff}').trim();
+        if text.is_empty() {
+            return Ok(Mapping::default());
+        }
+        let map: HashMap<String, String> = serde_json::from_str(text)
+            .map_err(|e| format!("매핑 파일은 {{\"외부 이름\": \"username\"}} 모양의 JSON이어야 합니다: {}", e))?;
+        Ok(Mapping(map.into_iter().map(|(k, v)| (k.trim().to_string(), v.trim().to_string())).collect()))
+    }
+
+    pub fn get(&self, external: &str) -> Option<&str> {
+        self.0.get(external).map(String::as_str).filter(|u| !u.is_empty())
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SenderReport {
+    pub name: String,
+    pub messages: usize,
+    /// 매핑된 username (없으면 가져올 수 없다)
+    pub username: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RoomReport {
+    pub name: String,
+    pub messages: usize,
+    pub system_messages: usize,
+    pub first_at: Option<NaiveDateTime>,
+    pub last_at: Option<NaiveDateTime>,
+    pub senders: Vec<SenderReport>,
+    /// 새 방의 참가자 (매핑된 사람 + 가져오는 사람)
+    pub participants: Vec<String>,
+    /// 실제로 가져온 뒤 만들어진 방
+    pub room_id: Option<i32>,
+}
+
+/// 가져오기 점검 보고서. dry_run이면 아무것도 저장하지 않고 이것만 돌려준다.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Report {
+    pub source: String,
+    pub dry_run: bool,
+    pub rooms: Vec<RoomReport>,
+    pub messages: usize,
+    pub skipped: usize,
+    /// 매핑 파일에 없는 외부 이름
+    pub unmapped: Vec<String>,
+    /// 매핑됐지만 가입하지 않은 username (api::import가 채운다)
+    pub unknown_users: Vec<String>,
+    /// 이대로 가져올 수 있는지
+    pub ready: bool,
+}
+
+impl Report {
+    /// 빠진 매핑이나 없는 사용자가 없고 가져올 메시지가 있으면 가져올 수 있다
+    pub fn update_ready(&mut self) {
+        self.ready = self.unmapped.is_empty() && self.unknown_users.is_empty() && self.messages > 0;
+    }
+
+    /// 보고서에 나오는 모든 username (가입 여부 확인용)
+    pub fn usernames(&self) -> BTreeSet<String> {
+        self.rooms.iter().flat_map(|r| r.participants.iter().cloned()).collect()
+    }
+}
+
+/// 저장하기 전에 방마다 몇 개가, 누구 이름으로 들어갈지 정리한다
+pub fn plan(source: Source, parsed: &Parsed, mapping: &Mapping, importer: &str, dry_run: bool) -> Report {
+    let mut unmapped = BTreeSet::new();
+    let rooms: Vec<RoomReport> = parsed
+        .rooms
+        .iter()
+        .map(|room| {
+            let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+            let mut system_messages = 0;
+            for message in &room.messages {
+                match &message.sender {
+                    Some(sender) => *counts.entry(sender).or_default() += 1,
+                    None => system_messages += 1,
+                }
+            }
+            let senders: Vec<SenderReport> = counts
+                .into_iter()
+                .map(|(name, messages)| {
+                    let username = mapping.get(name).map(str::to_string);
+                    if username.is_none() {
+                        unmapped.insert(name.to_string());
+                    }
+                    SenderReport { name: name.to_string(), messages, username }
+                })
+                .collect();
+            let participants: BTreeSet<String> = senders
+                .iter()
+                .filter_map(|s| s.username.clone())
+                .chain(std::iter::once(importer.to_string()))
+                .collect();
+            RoomReport {
+                name: room.name.clone(),
+                messages: room.messages.len(),
+                system_messages,
+                first_at: room.messages.iter().map(|m| m.sent_at).min(),
+                last_at: room.messages.iter().map(|m| m.sent_at).max(),
+                senders,
+                participants: participants.into_iter().collect(),
+                room_id: None,
+            }
+        })
+        .collect();
+    let mut report = Report {
+        source: source.name().to_string(),
+        dry_run,
+        messages: rooms.iter().map(|r| r.messages).sum(),
+        rooms,
+        skipped: parsed.skipped,
+        unmapped: unmapped.into_iter().collect(),
+        unknown_users: vec![],
+        ready: false,
+    };
+    report.update_ready();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn plan_reports_unmapped_senders_and_participants() {
+        let parsed = Parsed {
+            rooms: vec![ImportedRoom {
+                name: "팀".to_string(),
+                messages: vec![
+                    ImportedMessage { sender: Some("홍길동".into()), sent_at: at("2024-01-05 06:04:00"), text: "안녕".into() },
+                    ImportedMessage { sender: None, sent_at: at("2024-01-05 06:05:00"), text: "김철수님이 들어왔습니다.".into() },
+                    ImportedMessage { sender: Some("김철수".into()), sent_at: at("2024-01-05 06:06:00"), text: "hi".into() },
+                    ImportedMessage { sender: Some("홍길동".into()), sent_at: at("2024-01-05 06:07:00"), text: "ㅎㅎ".into() },
+                ],
+            }],
+            skipped: 1,
+        };
+        let mapping = Mapping::parse(r#"{"홍길동": "gildong"}"#).unwrap();
+        let report = plan(Source::Kakao, &parsed, &mapping, "admin", true);
+        assert!(!report.ready);
+        assert_eq!(report.unmapped, vec!["김철수"]);
+        let room = &report.rooms[0];
+        assert_eq!((room.messages, room.system_messages), (4, 1));
+        assert_eq!(room.senders[1], SenderReport { name: "홍길동".into(), messages: 2, username: Some("gildong".into()) });
+        assert_eq!(room.participants, vec!["admin", "gildong"]);
+        assert_eq!(room.first_at, Some(at("2024-01-05 06:04:00")));
+
+        let mapping = Mapping::parse(r#"{"홍길동": "gildong", "김철수": "cheolsu"}"#).unwrap();
+        assert!(plan(Source::Kakao, &parsed, &mapping, "admin", true).ready);
+        assert!(Mapping::parse("홍길동 = gildong").is_err());
+    }
+
+    #[test]
+    fn converts_local_time_and_twelve_hour_clock() {
+        let seoul: Tz = "Asia/Seoul".parse().unwrap();
+        assert_eq!(to_utc(seoul, at("2024-01-05 15:04:00")), at("2024-01-05 06:04:00"));
+        assert_eq!((hour_24(12, Some(false)), hour_24(12, Some(true)), hour_24(3, Some(true)), hour_24(15, None)), (0, 12, 15, 15));
+        assert_eq!(stem("exports/WhatsApp Chat with Team.txt"), "WhatsApp Chat with Team");
+    }
+}
@@ -0,0 +1,264 @@
+//! Slack 워크스페이스 내보내기 ZIP.
+//!
+//! `users.json`, `channels.json`(공개), `groups.json`(비공개)과 채널 이름 폴더 아래 날짜별 `YYYY-MM-DD.json`으로 되어 있다.
+//! 채널 하나가 방 하나가 되고, 보낸 사람의 외부 이름은 Slack 사용자 이름(handle)이다.
+//! 스레드 답글은 시간순으로 펼쳐 넣고, 첨부 파일은 이름만 본문에 남긴다.
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::sync::OnceLock;
+
+use chrono::DateTime;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use zip::ZipArchive;
+
+use super::{ImportedMessage, ImportedRoom, Parsed};
+
+/// ZIP 안 JSON 파일 하나의 최대 크기. 압축을 풀면 크게 부풀어 오르는 파일을 끝까지 읽지 않는다.
+const MAX_ENTRY_BYTES: u64 = 50 * 1024 * 1024;
+/// 내보내기 하나에서 풀어 읽는 JSON 전체의 최대 크기. 작은 파일을 아주 많이 넣은 ZIP도 막는다.
+const MAX_TOTAL_BYTES: u64 = 500 * 1024 * 1024;
+
+/// 채널 입장·주제 변경 같은 안내 메시지
+const NOTICE_SUBTYPES: [&str; 6] = ["channel_join", "channel_leave", "channel_topic", "channel_purpose", "channel_name", "channel_archive"];
+
+/// `<@U123>`, `<#C123|general>`, `<!here>`, `<https://a.b|이름>`
+fn token_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"<([@#!]?)([^<>|]+)(?:\|([^<>]*))?>").unwrap())
+}
+
+struct Users(HashMap<String, (String, String)>);
+
+impl Users {
+    /// 사용자 id → (handle, 보이는 이름)
+    fn load(value: Option<Value>) -> Self {
+        let mut users = HashMap::new();
+        for user in value.as_ref().and_then(Value::as_array).into_iter().flatten() {
+            let Some(id) = user["id"].as_str() else { continue };
+            let handle = user["name"].as_str().unwrap_or(id).to_string();
+            let shown = [&user["profile"]["display_name"], &user["profile"]["real_name"], &user["real_name"]]
+                .iter()
+                .filter_map(|v| v.as_str())
+                .find(|v| !v.trim().is_empty())
+                .unwrap_or(&handle)
+                .to_string();
+            users.insert(id.to_string(), (handle, shown));
+        }
+        Users(users)
+    }
+
+    fn handle<'a>(&'a self, id: &'a str) -> &'a str {
+        self.0.get(id).map(|(handle, _)| handle.as_str()).unwrap_or(id)
+    }
+
+    fn shown<'a>(&'a self, id: &'a str) -> &'a str {
+        self.0.get(id).map(|(_, shown)| shown.as_str()).unwrap_or(id)
+    }
+}
+
+/// Slack mrkdwn의 링크·멘션 표기를 읽을 수 있는 글로
+fn plain(text: &str, users: &Users) -> String {
+    let text = token_re().replace_all(text, |c: &Captures| {
+        let label = c.get(3).map(|m| m.as_str()).filter(|l| !l.is_empty());
+        match (&c[1], label) {
+            ("@", _) => format!("@{}", users.shown(&c[2])),
+            ("#", Some(label)) => format!("#{}", label),
+            ("!", _) => format!("@{}", label.unwrap_or(c[2].split('^').next().unwrap_or_default())),
+            (_, Some(label)) if label != &c[2] => format!("{} ({})", label, &c[2]),
+            _ => c[2].to_string(),
+        }
+    });
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
+/// 압축을 풀어 읽을 수 있는 남은 양
+struct Budget {
+    entry: u64,
+    left: u64,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget { entry: MAX_ENTRY_BYTES, left: MAX_TOTAL_BYTES }
+    }
+}
+
+// 머리글의 크기는 믿을 수 없으므로 실제로 풀린 바이트를 세어 가며 남은 양까지만 읽는다
+fn read_json<R: Read + std::io::Seek>(archive: &mut ZipArchive<R>, name: &str, budget: &mut Budget) -> Result<Option<Value>, String> {
+    let file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("{}을(를) 열 수 없습니다: {}", name, e)),
+    };
+    let limit = budget.entry.min(budget.left);
+    let mut text = String::new();
+    file.take(limit + 1).read_to_string(&mut text).map_err(|e| format!("{}을(를) 읽을 수 없습니다: {}", name, e))?;
+    if text.len() as u64 > budget.left {
+        return Err(format!("압축을 푼 내용이 너무 큽니다. (전체 {}MB까지)", MAX_TOTAL_BYTES / 1024 / 1024));
+    }
+    if text.len() as u64 > budget.entry {
+        return Err(format!("{}이(가) 너무 큽니다. (파일 하나에 {}MB까지)", name, MAX_ENTRY_BYTES / 1024 / 1024));
+    }
+    budget.left -= text.len() as u64;
+    serde_json::from_str(&text).map(Some).map_err(|e| format!("{}이(가) 올바른 JSON이 아닙니다: {}", name, e))
+}
+
+/// 메시지 하나. 읽을 수 없으면 None.
+fn message(value: &Value, users: &Users) -> Option<(f64, ImportedMessage)> {
+    if value["type"].as_str() != Some("message") {
+        return None;
+    }
+    let ts: f64 = value["ts"].as_str()?.parse().ok()?;
+    let sent_at = DateTime::from_timestamp_millis((ts * 1000.0) as i64)?.naive_utc();
+    let subtype = value["subtype"].as_str().unwrap_or_default();
+    let mut text = plain(value["text"].as_str().unwrap_or_default(), users);
+    for file in value["files"].as_array().into_iter().flatten() {
+        if let Some(name) = file["name"].as_str().or(file["title"].as_str()) {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&format!("📎 {}", name));
+        }
+    }
+    let sender = if NOTICE_SUBTYPES.contains(&subtype) {
+        None
+    } else if let Some(user) = value["user"].as_str() {
+        Some(users.handle(user).to_string())
+    } else {
+        // 봇·연동이 남긴 메시지
+        Some(value["username"].as_str().or(value["bot_profile"]["name"].as_str()).or(value["bot_id"].as_str())?.to_string())
+    };
+    Some((ts, ImportedMessage { sender, sent_at, text }))
+}
+
+pub fn parse(bytes: &[u8]) -> Result<Parsed, String> {
+    parse_within(bytes, Budget::default())
+}
+
+fn parse_within(bytes: &[u8], mut budget: Budget) -> Result<Parsed, String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("Slack 내보내기 ZIP을 열 수 없습니다: {}", e))?;
+    let users = Users::load(read_json(&mut archive, "users.json", &mut budget)?);
+    let mut channels: Vec<String> = Vec::new();
+    for list in ["channels.json", "groups.json"] {
+        for channel in read_json(&mut archive, list, &mut budget)?.as_ref().and_then(Value::as_array).into_iter().flatten() {
+            if let Some(name) = channel["name"].as_str() {
+                channels.push(name.to_string());
+            }
+        }
+    }
+    if channels.is_empty() {
+        return Err("channels.json에 채널이 없습니다. Slack 워크스페이스 내보내기 파일인지 확인하세요.".to_string());
+    }
+
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    let mut parsed = Parsed::default();
+    for channel in channels {
+        let prefix = format!("{}/", channel);
+        let mut days: Vec<&String> = names.iter().filter(|n| n.starts_with(&prefix) && n.ends_with(".json")).collect();
+        days.sort();
+        let mut messages: Vec<(f64, ImportedMessage)> = Vec::new();
+        for day in days {
+            let Some(Value::Array(items)) = read_json(&mut archive, day, &mut budget)? else {
+                parsed.skipped += 1;
+                continue;
+            };
+            for item in &items {
+                match message(item, &users) {
+                    Some(message) => messages.push(message),
+                    None => parsed.skipped += 1,
+                }
+            }
+        }
+        messages.sort_by(|a, b| a.0.total_cmp(&b.0));
+        parsed.rooms.push(ImportedRoom { name: channel, messages: messages.into_iter().map(|(_, m)| m).collect() });
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use chrono::NaiveDateTime;
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    fn archive(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, body) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(body.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn parses_channels_in_time_order() {
+        let bytes = archive(&[
+            ("users.json", r#"[{"id":"U1","name":"alice","profile":{"display_name":"Alice K"}},{"id":"U2","name":"bob","profile":{}}]"#),
+            ("channels.json", r#"[{"id":"C1","name":"general"}]"#),
+            (
+                "general/2024-01-06.json",
+                r#"[{"type":"message","user":"U2","text":"thanks &lt;3 <@U1>","ts":"1704502800.000100"}]"#,
+            ),
+            (
+                "general/2024-01-05.json",
+                r#"[
+                    {"type":"message","subtype":"channel_join","user":"U2","text":"<@U2> has joined the channel","ts":"1704416400.000100"},
+                    {"type":"message","user":"U1","text":"see <https://example.com|docs> in <#C1|general>","ts":"1704416460.000200","files":[{"name":"plan.pdf"}]},
+                    {"type":"message","subtype":"bot_message","username":"deploy","text":"deployed","ts":"1704416470.000000"},
+                    {"type":"not_a_message"}
+                ]"#,
+            ),
+        ]);
+        let parsed = parse(&bytes).unwrap();
+        assert_eq!(parsed.skipped, 1);
+        let room = &parsed.rooms[0];
+        assert_eq!(room.name, "general");
+        let got: Vec<(Option<&str>, &str)> = room.messages.iter().map(|m| (m.sender.as_deref(), m.text.as_str())).collect();
+        assert_eq!(
+            got,
+            vec![
+                (None, "@bob has joined the channel"),
+                (Some("alice"), "see docs (https://example.com) in #general\n📎 plan.pdf"),
+                (Some("deploy"), "deployed"),
+                (Some("bob"), "thanks <3 @Alice K"),
+            ]
+        );
+        assert_eq!(room.messages[0].sent_at, NaiveDateTime::parse_from_str("2024-01-05 01:00:00", "%Y-%m-%d %H:%M:%S").unwrap());
+    }
+
+    #[test]
+    fn stops_reading_oversized_entries() {
+        let bytes = archive(&[("users.json", &format!("[{}]", " ".repeat(4096)))]);
+        let mut zip = ZipArchive::new(Cursor::new(bytes.as_slice())).unwrap();
+        let small = || Budget { entry: 1024, left: 1 << 20 };
+        assert!(read_json(&mut zip, "users.json", &mut small()).unwrap_err().contains("파일 하나에"));
+        let mut budget = Budget { entry: 8192, left: 1 << 20 };
+        assert!(read_json(&mut zip, "users.json", &mut budget).unwrap().is_some());
+        assert_eq!(budget.left, (1 << 20) - 4098);
+    }
+
+    #[test]
+    fn stops_when_the_whole_export_is_too_large() {
+        let day = format!("[{}]", " ".repeat(1000));
+        let bytes = archive(&[
+            ("channels.json", r#"[{"id":"C1","name":"general"}]"#),
+            ("general/2024-01-05.json", &day),
+            ("general/2024-01-06.json", &day),
+            ("general/2024-01-07.json", &day),
+        ]);
+        // 파일 하나하나는 한도 안이지만 합이 넘는다
+        let budget = Budget { entry: 2048, left: 2500 };
+        assert!(parse_within(&bytes, budget).unwrap_err().contains("전체"));
+        assert!(parse_within(&bytes, Budget { entry: 2048, left: 8192 }).is_ok());
+    }
+
+    #[test]
+    fn rejects_other_zips() {
+        assert!(parse(&archive(&[("readme.txt", "hi")])).is_err());
+        assert!(parse(b"not a zip").is_err());
+    }
+}
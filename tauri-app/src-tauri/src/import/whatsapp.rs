@@ -0,0 +1,149 @@
+//! WhatsApp "채팅 내보내기" txt.
+//!
+//! Android: `05/01/2024, 15:04 - 이름: 내용`, iOS: `[05/01/2024, 15:04:12] 이름: 내용`.
+//! 날짜 순서는 기기 언어를 따르므로 파일 전체를 보고 정한다(일이 12보다 큰 줄이 있으면 그쪽, 없으면 일/월/년).
+//! 한국어 설정의 `2024. 1. 5. 오후 3:04 - 이름: 내용`과 `3:04 PM` 같은 12시간제도 읽는다.
+
+use std::sync::OnceLock;
+
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use regex::Regex;
+
+use super::{hour_24, stem, to_utc, ImportedMessage, ImportedRoom, Parsed};
+
+const DEFAULT_NAME: &str = "WhatsApp 대화";
+
+fn line_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^\[?(\d{1,4})[./-] ?(\d{1,2})[./-] ?(\d{1,4})\.?,? ",
+            r"(?:(오전|오후) )?(\d{1,2}):(\d{2})(?::(\d{2}))?(?: ?([AaPp])\.? ?[Mm]\.?)?",
+            r"(?:\] | - )(.*)$"
+        ))
+        .unwrap()
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Order {
+    Ymd,
+    Dmy,
+    Mdy,
+}
+
+/// 날짜 부분 세 숫자로 순서를 정한다
+fn detect_order(dates: &[(u32, u32, u32, usize)]) -> Order {
+    if dates.iter().any(|&(_, _, _, first_len)| first_len == 4) {
+        Order::Ymd
+    } else if dates.iter().any(|&(a, _, _, _)| a > 12) {
+        Order::Dmy
+    } else if dates.iter().any(|&(_, b, _, _)| b > 12) {
+        Order::Mdy
+    } else {
+        Order::Dmy
+    }
+}
+
+fn date_of(order: Order, (a, b, c): (u32, u32, u32)) -> Option<NaiveDate> {
+    let (year, month, day) = match order {
+        Order::Ymd => (a, b, c),
+        Order::Dmy => (c, b, a),
+        Order::Mdy => (c, a, b),
+    };
+    let year = if year < 100 { 2000 + year } else { year };
+    NaiveDate::from_ymd_opt(year as i32, month, day)
+}
+
+pub fn parse(text: &str, tz: Tz, name_hint: Option<&str>) -> Result<Parsed, String> {
+    // iOS 내보내기에는 방향 표시 문자와 좁은 공백이 섞여 있다
+    let text = text.replace(['\u{200e}', '\u{200f}'], "").replace(['\u{202f}', '\u{a0}'], " ");
+    let lines: Vec<(&str, Option<regex::Captures>)> = text.lines().map(|line| (line, line_re().captures(line))).collect();
+    let dates: Vec<(u32, u32, u32, usize)> = lines
+        .iter()
+        .filter_map(|(_, c)| c.as_ref())
+        .map(|c| (c[1].parse().unwrap_or(0), c[2].parse().unwrap_or(0), c[3].parse().unwrap_or(0), c[1].len()))
+        .collect();
+    let order = detect_order(&dates);
+
+    let mut messages: Vec<ImportedMessage> = Vec::new();
+    let mut skipped = 0;
+    for (line, captures) in &lines {
+        let Some(c) = captures else {
+            match messages.last_mut() {
+                Some(last) => {
+                    last.text.push('\n');
+                    last.text.push_str(line);
+                }
+                None if line.trim().is_empty() => {}
+                None => skipped += 1,
+            }
+            continue;
+        };
+        let pm = match (c.get(4).map(|m| m.as_str()), c.get(8).map(|m| m.as_str().to_ascii_lowercase())) {
+            (Some("오후"), _) => Some(true),
+            (Some(_), _) => Some(false),
+            (None, Some(p)) => Some(p == "p"),
+            (None, None) => None,
+        };
+        let date = date_of(order, (c[1].parse().unwrap_or(0), c[2].parse().unwrap_or(0), c[3].parse().unwrap_or(0)));
+        let second = c.get(7).and_then(|s| s.as_str().parse().ok()).unwrap_or(0);
+        let Some(at) = date.and_then(|d| d.and_hms_opt(hour_24(c[5].parse().unwrap_or(99), pm), c[6].parse().unwrap_or(99), second)) else {
+            skipped += 1;
+            continue;
+        };
+        let rest = &c[9];
+        let (sender, body) = match rest.split_once(": ") {
+            Some((sender, body)) => (Some(sender.trim().to_string()), body.to_string()),
+            None => (None, rest.to_string()),
+        };
+        messages.push(ImportedMessage { sender, sent_at: to_utc(tz, at), text: body });
+    }
+    let name = name_hint
+        .map(|n| {
+            let n = stem(n);
+            n.strip_prefix("WhatsApp Chat with ").or_else(|| n.strip_prefix("WhatsApp Chat - ")).unwrap_or(n).to_string()
+        })
+        .unwrap_or_else(|| DEFAULT_NAME.to_string());
+    Ok(Parsed { rooms: vec![ImportedRoom { name, messages }], skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn parses_android_and_ios_layouts() {
+        let android = "05/01/2024, 15:04 - Messages and calls are end-to-end encrypted.\n\
+05/01/2024, 15:04 - Alice: hello\n\
+second line\n\
+13/01/2024, 09:00 - Bob: see: this\n";
+        let room = &parse(android, Tz::UTC, Some("WhatsApp Chat with Team.txt")).unwrap().rooms[0];
+        assert_eq!(room.name, "Team");
+        assert_eq!(room.messages.len(), 3);
+        assert_eq!(room.messages[0].sender, None);
+        assert_eq!(room.messages[1].text, "hello\nsecond line");
+        assert_eq!(room.messages[1].sent_at, at("2024-01-05 15:04:00"));
+        assert_eq!((room.messages[2].sender.as_deref(), room.messages[2].text.as_str()), (Some("Bob"), "see: this"));
+
+        let ios = "[1/5/24, 3:04:12\u{202f}PM] Alice: hi\n[1/13/24, 12:30:00 AM] Bob: late\n";
+        let room = &parse(ios, Tz::UTC, None).unwrap().rooms[0];
+        assert_eq!(room.messages[0].sent_at, at("2024-01-05 15:04:12"));
+        assert_eq!(room.messages[1].sent_at, at("2024-01-13 00:30:00"));
+    }
+
+    #[test]
+    fn parses_korean_locale() {
+        let seoul: Tz = "Asia/Seoul".parse().unwrap();
+        let text = "2024. 1. 5. 오후 3:04 - 홍길동: 안녕\n";
+        let room = &parse(text, seoul, None).unwrap().rooms[0];
+        assert_eq!(room.messages[0].sender.as_deref(), Some("홍길동"));
+        assert_eq!(room.messages[0].sent_at, at("2024-01-05 06:04:00"));
+    }
+}
@@ -1,16 +1,26 @@
 // Removed inner attribute; windows_subsystem attribute stays in main.rs as required by Tauri
 
 mod api;
+mod bot;
+mod command;
+mod content;
 mod db;
-// mod migration; // temporarily disabled migrations
+mod migration;
 mod entities;
+mod highlight;
+mod import;
+mod markdown;
+mod media;
+mod preview;
+mod search;
+mod validation;
 
-use axum::{Router, routing::{get, post, put, delete}, extract::{Path, State, Query}};
+use axum::{Router, routing::{get, post, put, delete}, extract::{DefaultBodyLimit, Multipart, Path, State, Query}};
 use tower_http::cors::CorsLayer;
 use tower_http::services::{ServeDir, ServeFile};
 use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
 use crate::db::init::init_db;
-// use migration::Migrator; // temporarily disabled migrations
+use migration::Migrator;
 use tokio::sync::broadcast;
 use api::state::AppState;
 
@@ -39,10 +49,89 @@ fn build_axum(state: api::state::AppState) -> Router {
         .route("/chat/subscribe", get(|State(app): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
             api::chat::subscribe(State(app.queue.clone()), Query(params)).await
         }))
-        .route("/chat/send", post(|State(app): State<AppState>, axum::Json(payload): axum::Json<api::chat::NewMessage>| async move {
-            api::chat::send(State(app.conn.clone()), State(app.queue.clone()), axum::Json(payload)).await
+        .route("/chat/send", post(|State(app): State<AppState>, headers: axum::http::HeaderMap, axum::Json(payload): axum::Json<api::chat::RawNewMessage>| async move {
+            api::chat::send(State(app.conn.clone()), State(app.queue.clone()), headers, axum::Json(payload)).await
+        }))
+        .route("/chat/ack", post(|State(app): State<AppState>, axum::Json(payload): axum::Json<api::delivery::DeliveryAck>| async move {
+            api::delivery::ack(State(app.conn.clone()), State(app.queue.clone()), axum::Json(payload)).await
+        }))
+        .route("/chat/typing", post(|State(app): State<AppState>, axum::Json(payload): axum::Json<api::typing::TypingUpdate>| async move {
+            api::typing::typing(State(app.conn.clone()), State(app.queue.clone()), State(app.typing.clone()), axum::Json(payload)).await
+        }))
+        .route("/chat/schedule", get(|State(app): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::schedule::list(State(app.conn.clone()), Query(params)).await
+        }))
+        .route("/chat/schedule", post(|State(app): State<AppState>, headers: axum::http::HeaderMap, axum::Json(payload): axum::Json<api::schedule::NewSchedule>| async move {
+            api::schedule::create(State(app.conn.clone()), headers, axum::Json(payload)).await
+        }))
+        .route("/chat/schedule/{id}", put(|State(app): State<AppState>, headers: axum::http::HeaderMap, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::schedule::ScheduleEdit>| async move {
+            api::schedule::edit(State(app.conn.clone()), headers, Path(id), axum::Json(payload)).await
+        }))
+        .route("/chat/schedule/{id}", delete(|State(app): State<AppState>, Path(id): Path<i32>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::schedule::cancel(State(app.conn.clone()), Path(id), Query(params)).await
+        }))
+        .route("/chat/{id}/forward", post(|State(app): State<AppState>, headers: axum::http::HeaderMap, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::forward::ForwardRequest>| async move {
+            api::forward::forward(State(app.conn.clone()), State(app.queue.clone()), headers, Path(id), axum::Json(payload)).await
+        }))
+        .route("/chat/poll", post(|State(app): State<AppState>, headers: axum::http::HeaderMap, axum::Json(payload): axum::Json<api::poll::NewPoll>| async move {
+            api::poll::create_poll(State(app.conn.clone()), State(app.queue.clone()), headers, axum::Json(payload)).await
+        }))
+        .route("/chat/poll/{id}/vote", post(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::poll::PollVoteRequest>| async move {
+            api::poll::vote(State(app.conn.clone()), State(app.queue.clone()), Path(id), axum::Json(payload)).await
+        }))
+        .route("/chat/poll/{id}/vote", delete(|State(app): State<AppState>, Path(id): Path<i32>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::poll::retract(State(app.conn.clone()), State(app.queue.clone()), Path(id), Query(params)).await
+        }))
+        .route("/chat/code", post(|State(app): State<AppState>, headers: axum::http::HeaderMap, axum::Json(payload): axum::Json<api::code::NewCode>| async move {
+            api::code::send_code(State(app.conn.clone()), State(app.queue.clone()), headers, axum::Json(payload)).await
+        }))
+        .route("/chat/code/style.css", get(api::code::stylesheet))
+        .route("/chat/commands", get(|State(app): State<AppState>, Query(query): Query<api::command::CompleteQuery>| async move {
+            api::command::autocomplete(State(app.conn.clone()), Query(query)).await
+        }))
+        .route("/chat/search", get(|State(app): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::search::search(State(app.conn.clone()), Query(params)).await
+        }))
+        .route("/chat/voice", post(|State(app): State<AppState>, headers: axum::http::HeaderMap, multipart: Multipart| async move {
+            api::voice::send_voice(State(app.conn.clone()), State(app.queue.clone()), headers, multipart).await
+        }).layer(DefaultBodyLimit::max(media::audio::MAX_VOICE_BYTES)))
+        .route("/chat/media", post(|State(app): State<AppState>, headers: axum::http::HeaderMap, multipart: Multipart| async move {
+            api::media::send_media(State(app.conn.clone()), State(app.queue.clone()), headers, multipart).await
+        }).layer(DefaultBodyLimit::max(media::visual::MAX_MEDIA_BYTES)))
+        .route("/chat/attachment/{id}", get(|State(app): State<AppState>, Path(id): Path<i32>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::attachment::download(State(app.conn.clone()), State(app.queue.clone()), Path(id), Query(params)).await
+        }))
+        .route("/chat/attachment/{id}/open", post(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::media::OpenRequest>| async move {
+            api::media::open(State(app.conn.clone()), Path(id), axum::Json(payload)).await
         }))
         // room
+        .route("/bookmark", get(|State(app): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::bookmark::list_bookmarks(State(app.conn.clone()), Query(params)).await
+        }))
+        .route("/bookmark", post(|State(app): State<AppState>, axum::Json(payload): axum::Json<api::bookmark::NewBookmark>| async move {
+            api::bookmark::add_bookmark(State(app.conn.clone()), axum::Json(payload)).await
+        }))
+        .route("/bookmark/{id}", put(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::bookmark::BookmarkEdit>| async move {
+            api::bookmark::edit_bookmark(State(app.conn.clone()), Path(id), axum::Json(payload)).await
+        }))
+        .route("/bookmark/{id}", delete(|State(app): State<AppState>, Path(id): Path<i32>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::bookmark::delete_bookmark(State(app.conn.clone()), Path(id), Query(params)).await
+        }))
+        .route("/bot", get(|State(app): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::bot::list_bots(State(app.conn.clone()), Query(params)).await
+        }))
+        .route("/bot", post(|State(app): State<AppState>, axum::Json(payload): axum::Json<api::bot::NewBot>| async move {
+            api::bot::create_bot(State(app.conn.clone()), axum::Json(payload)).await
+        }))
+        .route("/bot/{id}", put(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::bot::BotEdit>| async move {
+            api::bot::edit_bot(State(app.conn.clone()), Path(id), axum::Json(payload)).await
+        }))
+        .route("/bot/{id}", delete(|State(app): State<AppState>, Path(id): Path<i32>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::bot::delete_bot(State(app.conn.clone()), Path(id), Query(params)).await
+        }))
+        .route("/bot/{id}/token", post(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::bot::BotOwner>| async move {
+            api::bot::rotate_token(State(app.conn.clone()), Path(id), axum::Json(payload)).await
+        }))
         .route("/room", get(|State(app): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
             api::chat_room::get_room(State(app.conn.clone()), Query(params)).await
         }))
@@ -53,16 +142,70 @@ fn build_axum(state: api::state::AppState) -> Router {
             api::chat_room::find_or_create_room(State(app.conn.clone()), axum::Json(payload)).await
         }))
         .route("/room", put(|State(app): State<AppState>, axum::Json(payload): axum::Json<api::chat_room::NewRoom>| async move {
-            api::chat_room::put_room(State(app.conn.clone()), axum::Json(payload)).await
+            api::chat_room::put_room(State(app.conn.clone()), State(app.queue.clone()), axum::Json(payload)).await
         }))
         .route("/room", delete(|State(app): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
             api::chat_room::delete_room(State(app.conn.clone()), Query(params)).await
         }))
+        .route("/room/{id}/ttl", put(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::retention::TtlUpdate>| async move {
+            api::retention::set_ttl(State(app.conn.clone()), Path(id), axum::Json(payload)).await
+        }))
+        .route("/room/{id}/draft", get(|State(app): State<AppState>, Path(id): Path<i32>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::draft::get_draft(State(app.conn.clone()), Path(id), Query(params)).await
+        }))
+        .route("/room/{id}/draft", put(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::draft::DraftUpdate>| async move {
+            api::draft::save_draft(State(app.conn.clone()), Path(id), axum::Json(payload)).await
+        }))
+        .route("/room/{id}/rules", put(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::validation::RulesUpdate>| async move {
+            api::validation::set_rules(State(app.conn.clone()), Path(id), axum::Json(payload)).await
+        }))
+        .route("/room/{id}/webhook", get(|State(app): State<AppState>, Path(id): Path<i32>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::incoming::list_hooks(State(app.conn.clone()), Path(id), Query(params)).await
+        }))
+        .route("/room/{id}/webhook", post(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::incoming::NewHook>| async move {
+            api::incoming::create_hook(State(app.conn.clone()), Path(id), axum::Json(payload)).await
+        }))
+        .route("/room/{id}/webhook/{hook_id}", delete(|State(app): State<AppState>, Path(ids): Path<(i32, i32)>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::incoming::delete_hook(State(app.conn.clone()), Path(ids), Query(params)).await
+        }))
+        .route("/room/{id}/webhook/{hook_id}/regenerate", post(|State(app): State<AppState>, Path(ids): Path<(i32, i32)>, axum::Json(payload): axum::Json<api::incoming::HookMember>| async move {
+            api::incoming::regenerate(State(app.conn.clone()), Path(ids), axum::Json(payload)).await
+        }))
+        .route("/import", post(|State(app): State<AppState>, multipart: Multipart| async move {
+            api::import::import(State(app.conn.clone()), multipart).await
+        }).layer(DefaultBodyLimit::max(api::import::MAX_IMPORT_BYTES)))
+        .route("/room/{id}/export", get(|State(app): State<AppState>, Path(id): Path<i32>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::export::export(State(app.conn.clone()), Path(id), Query(params)).await
+        }))
+        .route("/room/{id}/outgoing", get(|State(app): State<AppState>, Path(id): Path<i32>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::outgoing::list(State(app.conn.clone()), Path(id), Query(params)).await
+        }))
+        .route("/room/{id}/outgoing", post(|State(app): State<AppState>, Path(id): Path<i32>, axum::Json(payload): axum::Json<api::outgoing::NewOutgoing>| async move {
+            api::outgoing::create(State(app.conn.clone()), Path(id), axum::Json(payload)).await
+        }))
+        .route("/room/{id}/outgoing/{hook_id}", put(|State(app): State<AppState>, Path(ids): Path<(i32, i32)>, axum::Json(payload): axum::Json<api::outgoing::OutgoingEdit>| async move {
+            api::outgoing::edit(State(app.conn.clone()), Path(ids), axum::Json(payload)).await
+        }))
+        .route("/room/{id}/outgoing/{hook_id}", delete(|State(app): State<AppState>, Path(ids): Path<(i32, i32)>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::outgoing::delete(State(app.conn.clone()), Path(ids), Query(params)).await
+        }))
+        .route("/room/{id}/outgoing/{hook_id}/secret", post(|State(app): State<AppState>, Path(ids): Path<(i32, i32)>, axum::Json(payload): axum::Json<api::outgoing::OutgoingMember>| async move {
+            api::outgoing::rotate_secret(State(app.conn.clone()), Path(ids), axum::Json(payload)).await
+        }))
+        .route("/room/{id}/outgoing/{hook_id}/deliveries", get(|State(app): State<AppState>, Path(ids): Path<(i32, i32)>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
+            api::outgoing::deliveries(State(app.conn.clone()), Path(ids), Query(params)).await
+        }))
+        .route("/room/{id}/outgoing/{hook_id}/deliveries/{delivery_id}/retry", post(|State(app): State<AppState>, Path(ids): Path<(i32, i32, i32)>, axum::Json(payload): axum::Json<api::outgoing::OutgoingMember>| async move {
+            api::outgoing::retry(State(app.conn.clone()), Path(ids), axum::Json(payload)).await
+        }))
+        .route("/hooks/{token}", post(|State(app): State<AppState>, Path(token): Path<String>, axum::Json(payload): axum::Json<api::incoming::HookPayload>| async move {
+            api::incoming::receive(State(app.conn.clone()), State(app.queue.clone()), State(app.hooks.clone()), Path(token), axum::Json(payload)).await
+        }))
         .route("/room/list", get(|State(app): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
             api::chat_room::list_rooms_with_unread(Query(params), State(app.conn.clone())).await
         }))
         .route("/room/read/{room_id}", post(|State(app): State<AppState>, Path(room_id): Path<i32>, axum::Json(payload): axum::Json<api::chat_room::ReadUpdate>| async move {
-            api::chat_room::mark_read(State(app.conn.clone()), Path(room_id), axum::Json(payload)).await
+            api::chat_room::mark_read(State(app.conn.clone()), State(app.queue.clone()), Path(room_id), axum::Json(payload)).await
         }))
         // user
         .route("/user", get(|State(app): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>| async move {
@@ -95,6 +238,7 @@ fn build_axum(state: api::state::AppState) -> Router {
 
     Router::new()
         .nest("/api", api_router)
+        .merge(bot::hosted_router("http://127.0.0.1:3100/api"))
         .layer(CorsLayer::permissive())
         .fallback_service(
             ServeDir::new("static").not_found_service(ServeFile::new("static/index.html")),
@@ -127,16 +271,30 @@ async fn run_async() {
         eprintln!("DATABASE_URL is missing. Place it in .env (project root or src-tauri)");
     }
     let db: DatabaseConnection = init_db().await;
-    // 마이그레이션 실행 (임시 비활성화)
-    // {
-    //     use sea_orm_migration::MigratorTrait;
-    //     Migrator::up(&db, None).await.expect("DB migration failed");
-    // }
-    let queue = broadcast::channel(10).0;
+    // 마이그레이션 실행 (새 기능의 테이블과 컬럼은 모두 여기서 만들어진다)
+    {
+        use sea_orm_migration::MigratorTrait;
+        // 스키마가 어긋난 채로 서버를 띄우지 않는다
+        if let Err(e) = Migrator::up(&db, None).await {
+            eprintln!("DB migration failed: {e}");
+            return;
+        }
+    }
+    let queue = broadcast::channel(api::event::QUEUE_CAPACITY).0;
     let state = AppState {
         conn: db,
         queue,
+        typing: api::typing::TypingLimiter::default(),
+        hooks: api::incoming::HookLimiter::default(),
     };
+    // 예약 메시지 발송기
+    tokio::spawn(api::schedule::run_dispatcher(state.conn.clone(), state.queue.clone()));
+    // 사라지는 메시지 정리
+    tokio::spawn(api::retention::run_sweeper(state.conn.clone(), state.queue.clone()));
+    // 봇 webhook 전달
+    tokio::spawn(api::bot::run_dispatcher(state.conn.clone(), state.queue.clone()));
+    // 외부 webhook 전달
+    tokio::spawn(api::outgoing::run_worker(state.conn.clone()));
     tauri::Builder::default()
         .setup(move |_app| {
             let state = state.clone();
@@ -0,0 +1,87 @@
+//! `format: markdown` 메시지 렌더링.
+//!
+//! CommonMark로 파싱한 뒤 허용한 태그만 남긴 HTML을 만든다. 원문 HTML은 태그로 해석하지 않고 글자로
+//! 보여주며, 마지막에 ammonia로 한 번 더 걸러 스크립트나 javascript: 링크가 남지 않게 한다.
+
+use std::collections::HashSet;
+
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+
+// 채팅에서 쓰는 서식만 허용 (제목은 태그가 지워지고 글자만 남는다)
+const ALLOWED_TAGS: [&str; 12] = ["p", "br", "strong", "em", "del", "code", "pre", "ul", "ol", "li", "a", "blockquote"];
+const URL_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+fn options() -> Options {
+    Options::ENABLE_STRIKETHROUGH
+}
+
+fn sanitizer() -> ammonia::Builder<'static> {
+    let mut builder = ammonia::Builder::empty();
+    builder
+        .add_tags(ALLOWED_TAGS)
+        .add_tag_attributes("a", ["href"])
+        .add_tag_attributes("ol", ["start"])
+        .url_schemes(HashSet::from(URL_SCHEMES))
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .clean_content_tags(HashSet::from(["script", "style"]));
+    builder
+}
+
+/// 마크다운 원문을 안전한 HTML로 바꾼다.
+pub fn to_html(source: &str) -> String {
+    let events = Parser::new_ext(source, options()).filter_map(|event| match event {
+        // 원문 HTML은 글자 그대로 보여준다
+        Event::Html(raw) | Event::InlineHtml(raw) => Some(Event::Text(raw)),
+        // 이미지는 불러오지 않고 대체 텍스트만 남긴다
+        Event::Start(Tag::Image { .. }) | Event::End(TagEnd::Image) => None,
+        other => Some(other),
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    sanitizer().clean(&out).to_string()
+}
+
+/// 서식을 뺀 글자만 (알림, 검색, 미리보기용)
+pub fn plain_text(source: &str) -> String {
+    let mut out = String::new();
+    for event in Parser::new_ext(source, options()) {
+        match event {
+            Event::Text(t) | Event::Code(t) | Event::Html(t) | Event::InlineHtml(t) => out.push_str(&t),
+            Event::SoftBreak | Event::HardBreak => out.push(' '),
+            Event::End(TagEnd::Paragraph | TagEnd::Item | TagEnd::Heading(_) | TagEnd::CodeBlock) => out.push(' '),
+            _ => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_basic_formatting() {
+        let html = to_html("**굵게** _기울임_ `code` ~~취소~~\n\n- 하나\n- 둘\n\n[링크](https://example.com)");
+        assert!(html.contains("<strong>굵게</strong>"));
+        assert!(html.contains("<em>기울임</em>"));
+        assert!(html.contains("<code>code</code>"));
+        assert!(html.contains("<del>취소</del>"));
+        assert!(html.contains("<li>하나</li>"));
+        assert!(html.contains(r#"<a href="https://example.com" rel="noopener noreferrer nofollow">링크</a>"#));
+    }
+
+    #[test]
+    fn strips_dangerous_constructs() {
+        let html = to_html("<script>alert(1)</script>\n\n[x](javascript:alert(1)) <img src=x onerror=alert(1)> ![a](https://evil/x.png)");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<img"));
+        assert!(!html.contains("javascript:"));
+        // 원문 HTML은 글자로 보인다
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn plain_text_drops_markup() {
+        assert_eq!(plain_text("# 제목\n\n**굵게** 그리고 `x`\n\n- a\n- b"), "제목 굵게 그리고 x a b");
+    }
+}
@@ -0,0 +1,293 @@
+use std::fmt;
+use std::io::Cursor;
+
+use audiopus::{
+    coder::Decoder,
+    packet::Packet,
+    Channels, MutSignals, SampleRate,
+};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error as SymphoniaError,
+    formats::FormatOptions,
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+/// 음성 메시지 최대 길이 (5분)
+pub const MAX_VOICE_DURATION_MS: i64 = 5 * 60 * 1000;
+/// 업로드 최대 크기 (10MB)
+pub const MAX_VOICE_BYTES: usize = 10 * 1024 * 1024;
+/// 클라이언트가 그리는 파형 막대 개수
+pub const WAVEFORM_BARS: usize = 64;
+
+// Opus는 항상 48kHz 기준으로 granule position을 센다
+const OPUS_RATE: i64 = 48_000;
+// 120ms 프레임 (Opus 최대 프레임 길이)
+const OPUS_MAX_FRAME: usize = 5760;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioContainer {
+    OggOpus,
+    M4a,
+}
+
+impl AudioContainer {
+    pub fn mime(&self) -> &'static str {
+        match self {
+            AudioContainer::OggOpus => "audio/ogg",
+            AudioContainer::M4a => "audio/mp4",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioContainer::OggOpus => "ogg",
+            AudioContainer::M4a => "m4a",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AudioInfo {
+    pub container: AudioContainer,
+    pub duration_ms: i64,
+    pub waveform: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum AudioError {
+    Empty,
+    TooLarge,
+    UnsupportedContainer,
+    Malformed(String),
+    TooLong,
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioError::Empty => write!(f, "음성 파일이 비어 있습니다."),
+            AudioError::TooLarge => write!(f, "음성 파일은 {}MB 이하여야 합니다.", MAX_VOICE_BYTES / 1024 / 1024),
+            AudioError::UnsupportedContainer => write!(f, "Opus(OGG) 또는 M4A 파일만 보낼 수 있습니다."),
+            AudioError::Malformed(e) => write!(f, "음성 파일을 읽을 수 없습니다: {}", e),
+            AudioError::TooLong => write!(f, "음성 메시지는 {}초 이내여야 합니다.", MAX_VOICE_DURATION_MS / 1000),
+        }
+    }
+}
+
+/// 파일 앞부분의 시그니처로 컨테이너를 판별한다. (확장자/Content-Type은 믿지 않음)
+pub fn sniff(bytes: &[u8]) -> Option<AudioContainer> {
+    if bytes.starts_with(b"OggS") {
+        return Some(AudioContainer::OggOpus);
+    }
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        // major brand(8..12) 또는 호환 brand 목록(16..) 중에 오디오 brand가 있어야 한다.
+        // isom/mp42 같은 범용 brand만 있는 파일은 동영상일 수 있으므로 받지 않는다.
+        let size = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        let end = size.clamp(12, bytes.len());
+        let compatible = bytes.get(16..end).unwrap_or_default();
+        let is_audio = |b: &[u8]| matches!(b, b"M4A " | b"M4B ");
+        if is_audio(&bytes[8..12]) || compatible.chunks_exact(4).any(is_audio) {
+            return Some(AudioContainer::M4a);
+        }
+    }
+    None
+}
+
+/// 컨테이너를 검증하고 길이와 파형을 계산한다. 디코딩을 하므로 blocking 스레드에서 호출할 것.
+pub fn analyze(bytes: &[u8]) -> Result<AudioInfo, AudioError> {
+    if bytes.is_empty() {
+        return Err(AudioError::Empty);
+    }
+    if bytes.len() > MAX_VOICE_BYTES {
+        return Err(AudioError::TooLarge);
+    }
+    let container = sniff(bytes).ok_or(AudioError::UnsupportedContainer)?;
+    let (duration_ms, peaks) = match container {
+        AudioContainer::OggOpus => analyze_ogg_opus(bytes)?,
+        AudioContainer::M4a => analyze_m4a(bytes)?,
+    };
+    if duration_ms <= 0 {
+        return Err(AudioError::Malformed("재생 시간이 0입니다".to_string()));
+    }
+    if duration_ms > MAX_VOICE_DURATION_MS {
+        return Err(AudioError::TooLong);
+    }
+    Ok(AudioInfo {
+        container,
+        duration_ms,
+        waveform: downsample(&peaks, WAVEFORM_BARS),
+    })
+}
+
+fn analyze_ogg_opus(bytes: &[u8]) -> Result<(i64, Vec<f32>), AudioError> {
+    let mut reader = ogg::reading::PacketReader::new(Cursor::new(bytes));
+    let malformed = |e: ogg::OggReadError| AudioError::Malformed(e.to_string());
+
+    // 첫 패킷은 OpusHead 여야 함 (채널 수: 9 바이트, pre-skip: 10~11 바이트, little endian)
+    let head = reader.read_packet().map_err(malformed)?
+        .ok_or_else(|| AudioError::Malformed("빈 OGG 스트림".to_string()))?;
+    if head.data.len() < 19 || !head.data.starts_with(b"OpusHead") {
+        return Err(AudioError::UnsupportedContainer);
+    }
+    let serial = head.stream_serial();
+    let pre_skip = u16::from_le_bytes([head.data[10], head.data[11]]) as i64;
+    // 3채널 이상은 multistream 디코더가 필요하므로 받지 않는다
+    let (channels, width) = match head.data[9] {
+        1 => (Channels::Mono, 1),
+        2 => (Channels::Stereo, 2),
+        _ => return Err(AudioError::UnsupportedContainer),
+    };
+
+    let mut decoder = Decoder::new(SampleRate::Hz48000, channels)
+        .map_err(|e| AudioError::Malformed(e.to_string()))?;
+    let mut pcm = vec![0f32; OPUS_MAX_FRAME * width];
+    let mut peaks = Vec::new();
+    let mut decoded_samples: i64 = 0;
+    let mut last_granule: i64 = 0;
+    let mut seen_tags = false;
+
+    while let Some(packet) = reader.read_packet().map_err(malformed)? {
+        if packet.stream_serial() != serial {
+            continue;
+        }
+        // 두 번째 패킷은 OpusTags (메타데이터)
+        if !seen_tags {
+            seen_tags = true;
+            if packet.data.starts_with(b"OpusTags") {
+                continue;
+            }
+        }
+        // granule이 없는 페이지는 u64::MAX(-1)로 표시되므로 i64 범위를 넘는 값은 무시
+        if packet.last_in_page() {
+            if let Ok(granule) = i64::try_from(packet.absgp_page()) {
+                last_granule = last_granule.max(granule);
+            }
+        }
+        let input = Packet::try_from(&packet.data[..]).map_err(|e| AudioError::Malformed(e.to_string()))?;
+        let output = MutSignals::try_from(&mut pcm[..]).map_err(|e| AudioError::Malformed(e.to_string()))?;
+        let n = decoder
+            .decode_float(Some(input), output, false)
+            .map_err(|e| AudioError::Malformed(e.to_string()))?;
+        decoded_samples = decoded_samples.saturating_add(n as i64);
+        if samples_to_ms(decoded_samples - pre_skip, OPUS_RATE) > MAX_VOICE_DURATION_MS {
+            return Err(AudioError::TooLong);
+        }
+        peaks.push(peak(&pcm[..n * width]));
+    }
+
+    // granule이 있으면 그것이 정확한 길이(끝 패딩 제외), 없으면 디코딩한 샘플 수로 계산
+    let total = if last_granule > 0 { last_granule } else { decoded_samples };
+    Ok((samples_to_ms(total - pre_skip, OPUS_RATE), peaks))
+}
+
+fn analyze_m4a(bytes: &[u8]) -> Result<(i64, Vec<f32>), AudioError> {
+    let malformed = |e: SymphoniaError| AudioError::Malformed(e.to_string());
+    let mss = MediaSourceStream::new(Box::new(Cursor::new(bytes.to_vec())), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension("m4a");
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|_| AudioError::UnsupportedContainer)?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| AudioError::Malformed("오디오 트랙이 없습니다".to_string()))?;
+    let track_id = track.id;
+    let sample_rate = track.codec_params.sample_rate
+        .ok_or_else(|| AudioError::Malformed("샘플레이트를 알 수 없습니다".to_string()))? as i64;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|_| AudioError::UnsupportedContainer)?;
+
+    let mut peaks = Vec::new();
+    let mut frames: i64 = 0;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(p) => p,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(malformed(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(d) => d,
+            // 손상된 프레임 하나는 건너뛴다
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(malformed(e)),
+        };
+        let spec = *decoded.spec();
+        let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buf.copy_interleaved_ref(decoded);
+        frames = frames.saturating_add((buf.samples().len() / spec.channels.count().max(1)) as i64);
+        if samples_to_ms(frames, sample_rate) > MAX_VOICE_DURATION_MS {
+            return Err(AudioError::TooLong);
+        }
+        peaks.push(peak(buf.samples()));
+    }
+
+    Ok((samples_to_ms(frames, sample_rate), peaks))
+}
+
+/// 샘플 수를 ms로 바꾼다. 조작된 헤더의 큰 값에도 넘치지 않도록 포화 연산을 쓴다.
+fn samples_to_ms(samples: i64, rate: i64) -> i64 {
+    samples.max(0).saturating_mul(1000) / rate.max(1)
+}
+
+fn peak(samples: &[f32]) -> f32 {
+    samples.iter().fold(0f32, |m, s| m.max(s.abs()))
+}
+
+/// 프레임별 최대 진폭을 `bars`개 구간으로 묶어 0~100 정수로 정규화한다.
+pub fn downsample(peaks: &[f32], bars: usize) -> Vec<u8> {
+    if peaks.is_empty() || bars == 0 {
+        return vec![];
+    }
+    let bucket = peaks.len().div_ceil(bars);
+    let levels: Vec<f32> = peaks.chunks(bucket).map(peak).collect();
+    let max = levels.iter().cloned().fold(0f32, f32::max);
+    if max <= 0.0 {
+        return vec![0; levels.len()];
+    }
+    levels.iter().map(|l| ((l / max).clamp(0.0, 1.0) * 100.0).round() as u8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff_detects_containers() {
+        assert_eq!(sniff(b"OggS\0\x02rest"), Some(AudioContainer::OggOpus));
+        assert_eq!(sniff(b"\0\0\0\x20ftypM4A \0\0\0\0"), Some(AudioContainer::M4a));
+        // 범용 brand라도 호환 목록에 M4A가 있으면 오디오
+        assert_eq!(sniff(b"\0\0\0\x18ftypisom\0\0\0\0isomM4A "), Some(AudioContainer::M4a));
+        // mp4 동영상 브랜드나 임의의 바이트는 거부
+        assert_eq!(sniff(b"\0\0\0\x20ftypqt  \0\0\0\0"), None);
+        assert_eq!(sniff(b"\0\0\0\x18ftypisom\0\0\0\0isommp41"), None);
+        assert_eq!(sniff(b"RIFF....WAVE"), None);
+    }
+
+    #[test]
+    fn downsample_normalizes_to_bars() {
+        let peaks: Vec<f32> = (0..128).map(|i| i as f32 / 127.0 * 0.5).collect();
+        let bars = downsample(&peaks, WAVEFORM_BARS);
+        assert_eq!(bars.len(), WAVEFORM_BARS);
+        assert_eq!(*bars.last().unwrap(), 100);
+        assert!(bars.windows(2).all(|w| w[0] <= w[1]));
+        assert!(downsample(&[], WAVEFORM_BARS).is_empty());
+    }
+
+    #[test]
+    fn analyze_rejects_unknown_and_empty() {
+        assert!(matches!(analyze(b""), Err(AudioError::Empty)));
+        assert!(matches!(analyze(b"not audio at all"), Err(AudioError::UnsupportedContainer)));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod audio;
+pub mod store;
+pub mod visual;
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use rand_core::{OsRng, RngCore};
+
+/// 업로드된 파일을 보관하는 디렉터리. UPLOAD_DIR이 없으면 실행 위치의 uploads 폴더를 쓴다.
+pub fn upload_dir() -> PathBuf {
+    std::env::var("UPLOAD_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("uploads"))
+}
+
+// 저장 키는 우리가 만든 16진수 이름 + 확장자뿐이므로 경로 구분자가 섞이면 거부
+fn path_for(key: &str) -> std::io::Result<PathBuf> {
+    if key.is_empty() || key.contains('/') || key.contains('\\') || key.contains("..") {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid storage key"));
+    }
+    Ok(upload_dir().join(key))
+}
+
+pub fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut buf);
+    buf.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 파일을 저장하고 저장 키를 돌려준다.
+pub async fn save(bytes: &[u8], ext: &str) -> std::io::Result<String> {
+    tokio::fs::create_dir_all(upload_dir()).await?;
+    let key = format!("{}.{}", random_hex(16), ext);
+    tokio::fs::write(path_for(&key)?, bytes).await?;
+    Ok(key)
+}
+
+pub async fn load(key: &str) -> std::io::Result<Vec<u8>> {
+    tokio::fs::read(path_for(key)?).await
+}
+
+/// 큰 파일을 나눠 읽을 때 (내보내기 등)
+pub async fn open(key: &str) -> std::io::Result<tokio::fs::File> {
+    tokio::fs::File::open(path_for(key)?).await
+}
+
+/// 파일을 읽고 지운다. 먼저 임시 이름으로 옮기므로 동시에 불러도 한쪽만 내용을 얻는다.
+pub async fn take(key: &str) -> std::io::Result<Vec<u8>> {
+    let path = path_for(key)?;
+    let claimed = path.with_extension(format!("taken-{}", random_hex(8)));
+    tokio::fs::rename(&path, &claimed).await?;
+    let bytes = tokio::fs::read(&claimed).await;
+    let _ = tokio::fs::remove_file(&claimed).await;
+    bytes
+}
+
+pub async fn remove(key: &str) -> std::io::Result<()> {
+    match tokio::fs::remove_file(path_for(key)?).await {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        other => other,
+    }
+}
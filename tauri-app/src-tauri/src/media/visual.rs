@@ -0,0 +1,121 @@
+use std::fmt;
+
+/// 사진/동영상 업로드 최대 크기 (25MB)
+pub const MAX_MEDIA_BYTES: usize = 25 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisualFormat {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Mp4,
+    Mov,
+    Webm,
+}
+
+impl VisualFormat {
+    /// attachment.kind에 저장하는 값
+    pub fn kind(&self) -> &'static str {
+        match self {
+            VisualFormat::Jpeg | VisualFormat::Png | VisualFormat::Gif | VisualFormat::Webp => "image",
+            VisualFormat::Mp4 | VisualFormat::Mov | VisualFormat::Webm => "video",
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            VisualFormat::Jpeg => "image/jpeg",
+            VisualFormat::Png => "image/png",
+            VisualFormat::Gif => "image/gif",
+            VisualFormat::Webp => "image/webp",
+            VisualFormat::Mp4 => "video/mp4",
+            VisualFormat::Mov => "video/quicktime",
+            VisualFormat::Webm => "video/webm",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            VisualFormat::Jpeg => "jpg",
+            VisualFormat::Png => "png",
+            VisualFormat::Gif => "gif",
+            VisualFormat::Webp => "webp",
+            VisualFormat::Mp4 => "mp4",
+            VisualFormat::Mov => "mov",
+            VisualFormat::Webm => "webm",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum VisualError {
+    Empty,
+    TooLarge,
+    Unsupported,
+}
+
+impl fmt::Display for VisualError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VisualError::Empty => write!(f, "파일이 비어 있습니다."),
+            VisualError::TooLarge => write!(f, "사진/동영상은 {}MB 이하여야 합니다.", MAX_MEDIA_BYTES / 1024 / 1024),
+            VisualError::Unsupported => write!(f, "JPEG, PNG, GIF, WebP 사진이나 MP4, MOV, WebM 동영상만 보낼 수 있습니다."),
+        }
+    }
+}
+
+/// 파일 앞부분의 시그니처로 형식을 판별한다. (확장자/Content-Type은 믿지 않음)
+pub fn sniff(bytes: &[u8]) -> Option<VisualFormat> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some(VisualFormat::Jpeg);
+    }
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(VisualFormat::Png);
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some(VisualFormat::Gif);
+    }
+    if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        return Some(VisualFormat::Webp);
+    }
+    if bytes.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        return Some(VisualFormat::Webm);
+    }
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        return match &bytes[8..12] {
+            b"qt  " => Some(VisualFormat::Mov),
+            b"isom" | b"iso2" | b"mp41" | b"mp42" | b"avc1" | b"M4V " => Some(VisualFormat::Mp4),
+            _ => None,
+        };
+    }
+    None
+}
+
+pub fn validate(bytes: &[u8]) -> Result<VisualFormat, VisualError> {
+    if bytes.is_empty() {
+        return Err(VisualError::Empty);
+    }
+    if bytes.len() > MAX_MEDIA_BYTES {
+        return Err(VisualError::TooLarge);
+    }
+    sniff(bytes).ok_or(VisualError::Unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff_detects_images_and_videos() {
+        assert_eq!(sniff(b"\xFF\xD8\xFF\xE0\0\x10JFIF"), Some(VisualFormat::Jpeg));
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0"), Some(VisualFormat::Png));
+        assert_eq!(sniff(b"RIFF\0\0\0\0WEBPVP8 "), Some(VisualFormat::Webp));
+        assert_eq!(sniff(b"\0\0\0\x18ftypmp42\0\0\0\0"), Some(VisualFormat::Mp4));
+        assert_eq!(sniff(b"\0\0\0\x14ftypqt  \0\0\0\0"), Some(VisualFormat::Mov));
+        // 음성(M4A)이나 다른 RIFF 파일은 거부
+        assert_eq!(sniff(b"\0\0\0\x20ftypM4A \0\0\0\0"), None);
+        assert_eq!(sniff(b"RIFF\0\0\0\0WAVEfmt "), None);
+        assert!(matches!(validate(b""), Err(VisualError::Empty)));
+    }
+}
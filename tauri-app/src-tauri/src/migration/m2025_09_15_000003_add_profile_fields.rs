@@ -7,40 +7,39 @@ pub struct Migration;
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
         // Add display_name, status, avatar columns to users if not exist
-        // Using separate ALTERs for portability
         // display_name
-        let _ = manager
+        manager
             .alter_table(
                 Table::alter()
                     .table(Alias::new("users"))
-                    .add_column(
+                    .add_column_if_not_exists(
                         ColumnDef::new(Alias::new("display_name"))
                             .string()
                             .null(),
                     )
                     .to_owned(),
             )
-            .await;
+            .await?;
 
         // status
-        let _ = manager
+        manager
             .alter_table(
                 Table::alter()
                     .table(Alias::new("users"))
-                    .add_column(ColumnDef::new(Alias::new("status")).string().null())
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("status")).string().null())
                     .to_owned(),
             )
-            .await;
+            .await?;
 
         // avatar
-        let _ = manager
+        manager
             .alter_table(
                 Table::alter()
                     .table(Alias::new("users"))
-                    .add_column(ColumnDef::new(Alias::new("avatar")).string().null())
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("avatar")).string().null())
                     .to_owned(),
             )
-            .await;
+            .await?;
 
         Ok(())
     }
@@ -24,6 +24,7 @@ impl MigrationTrait for Migration {
         manager
             .create_index(
                 Index::create()
+                    .if_not_exists()
                     .name("idx_room_read_room_user")
                     .table(Alias::new("room_read"))
                     .col(Alias::new("room_id"))
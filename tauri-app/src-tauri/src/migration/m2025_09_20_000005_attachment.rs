@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("attachment"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("chat_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("kind")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("mime")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("storage_key")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("size")).big_integer().not_null())
+                    .col(ColumnDef::new(Alias::new("duration_ms")).integer().null())
+                    .col(ColumnDef::new(Alias::new("waveform")).text().null())
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_attachment_chat")
+                            .from(Alias::new("attachment"), Alias::new("chat_id"))
+                            .to(Alias::new("chat"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_attachment_chat")
+                    .table(Alias::new("attachment"))
+                    .col(Alias::new("chat_id"))
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("attachment")).to_owned())
+            .await
+    }
+}
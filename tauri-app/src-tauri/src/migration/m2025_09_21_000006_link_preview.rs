@@ -0,0 +1,31 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("link_preview"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("url")).text().not_null().unique_key())
+                    .col(ColumnDef::new(Alias::new("title")).text().null())
+                    .col(ColumnDef::new(Alias::new("description")).text().null())
+                    .col(ColumnDef::new(Alias::new("image")).text().null())
+                    .col(ColumnDef::new(Alias::new("site_name")).text().null())
+                    .col(ColumnDef::new(Alias::new("fetched_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("link_preview")).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, Statement};
+
+use crate::search::tokenize;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        // tsvector/GIN은 Postgres 전용이라 SQL로 직접 작성
+        db.execute_unprepared("ALTER TABLE chat ADD COLUMN IF NOT EXISTS search_vector tsvector")
+            .await?;
+        db.execute_unprepared("CREATE INDEX IF NOT EXISTS idx_chat_search ON chat USING GIN (search_vector)")
+            .await?;
+
+        // 기존 메시지 색인 (bigram 토큰화는 Rust 쪽에서 함)
+        let backend = manager.get_database_backend();
+        let rows = db
+            .query_all(Statement::from_string(backend, "SELECT id, message FROM chat WHERE search_vector IS NULL"))
+            .await?;
+        for row in rows {
+            let id: i32 = row.try_get("", "id")?;
+            let message: String = row.try_get("", "message")?;
+            db.execute(Statement::from_sql_and_values(
+                backend,
+                "UPDATE chat SET search_vector = to_tsvector('simple', $1) WHERE id = $2",
+                [tokenize::index_text(&message).into(), id.into()],
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_chat_search").await?;
+        db.execute_unprepared("ALTER TABLE chat DROP COLUMN IF EXISTS search_vector").await?;
+        Ok(())
+    }
+}
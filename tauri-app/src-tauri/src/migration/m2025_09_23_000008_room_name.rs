@@ -0,0 +1,30 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 검색의 in:"방 이름" 필터용 (1:1 방은 비워둠)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("room"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("name")).string().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("room"))
+                    .drop_column(Alias::new("name"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
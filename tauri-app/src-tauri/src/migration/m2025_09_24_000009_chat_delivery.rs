@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("chat_delivery"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("chat_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("username")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("delivered_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_delivery_chat")
+                            .from(Alias::new("chat_delivery"), Alias::new("chat_id"))
+                            .to(Alias::new("chat"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 같은 수신자의 중복 ack는 한 번만 기록
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_chat_delivery_chat_user")
+                    .table(Alias::new("chat_delivery"))
+                    .col(Alias::new("chat_id"))
+                    .col(Alias::new("username"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("chat_delivery")).to_owned())
+            .await
+    }
+}
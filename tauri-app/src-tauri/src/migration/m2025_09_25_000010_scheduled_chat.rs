@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("scheduled_chat"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("sender")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("room_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("message")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("send_at")).timestamp().not_null())
+                    .col(ColumnDef::new(Alias::new("status")).string().not_null().default("pending"))
+                    .col(ColumnDef::new(Alias::new("chat_id")).integer().null())
+                    .col(ColumnDef::new(Alias::new("error")).string().null())
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(Alias::new("updated_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_scheduled_chat_room")
+                            .from(Alias::new("scheduled_chat"), Alias::new("room_id"))
+                            .to(Alias::new("room"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 발송기가 매번 훑는 조건 (status = 'pending' AND send_at <= now)
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_scheduled_chat_status_send_at")
+                    .table(Alias::new("scheduled_chat"))
+                    .col(Alias::new("status"))
+                    .col(Alias::new("send_at"))
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("scheduled_chat")).to_owned())
+            .await
+    }
+}
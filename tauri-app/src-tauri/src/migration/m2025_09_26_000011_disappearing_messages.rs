@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 방별 보관 기간(초). NULL이면 메시지가 사라지지 않는다.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("room"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("message_ttl")).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("chat"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("expires_at")).timestamp().null())
+                    .to_owned(),
+            )
+            .await?;
+        // 정리 작업이 만료된 행만 빠르게 찾도록
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_chat_expires_at")
+                    .table(Alias::new("chat"))
+                    .col(Alias::new("expires_at"))
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_chat_expires_at").table(Alias::new("chat")).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("chat"))
+                    .drop_column(Alias::new("expires_at"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("room"))
+                    .drop_column(Alias::new("message_ttl"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("attachment"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("view_once")).boolean().not_null().default(false))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("viewed_at")).timestamp().null())
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("viewed_by")).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 한 번 보기 첨부를 여는 1회용 토큰
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("attachment_token"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("token")).string().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("attachment_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("username")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("expires_at")).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_attachment_token_attachment")
+                            .from(Alias::new("attachment_token"), Alias::new("attachment_id"))
+                            .to(Alias::new("attachment"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("attachment_token")).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("attachment"))
+                    .drop_column(Alias::new("view_once"))
+                    .drop_column(Alias::new("viewed_at"))
+                    .drop_column(Alias::new("viewed_by"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
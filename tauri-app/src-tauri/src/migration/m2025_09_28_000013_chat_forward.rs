@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 전달된 메시지는 원본을 가리키고, 원본이 지워져도 보낸 사람/시각은 남긴다
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("chat"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("forwarded_from_id")).integer().null())
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("forwarded_sender")).string().null())
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("forwarded_at")).timestamp().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_chat_forwarded_from")
+                    .from(Alias::new("chat"), Alias::new("forwarded_from_id"))
+                    .to(Alias::new("chat"), Alias::new("id"))
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(ForeignKey::drop().name("fk_chat_forwarded_from").table(Alias::new("chat")).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("chat"))
+                    .drop_column(Alias::new("forwarded_from_id"))
+                    .drop_column(Alias::new("forwarded_sender"))
+                    .drop_column(Alias::new("forwarded_at"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,107 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("poll"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("chat_id")).integer().not_null().unique_key())
+                    .col(ColumnDef::new(Alias::new("question")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("multiple")).boolean().not_null().default(false))
+                    .col(ColumnDef::new(Alias::new("anonymous")).boolean().not_null().default(false))
+                    .col(ColumnDef::new(Alias::new("closes_at")).timestamp().null())
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_poll_chat")
+                            .from(Alias::new("poll"), Alias::new("chat_id"))
+                            .to(Alias::new("chat"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("poll_option"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("poll_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("position")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("label")).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_poll_option_poll")
+                            .from(Alias::new("poll_option"), Alias::new("poll_id"))
+                            .to(Alias::new("poll"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("poll_vote"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("poll_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("option_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("username")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_poll_vote_poll")
+                            .from(Alias::new("poll_vote"), Alias::new("poll_id"))
+                            .to(Alias::new("poll"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_poll_vote_option")
+                            .from(Alias::new("poll_vote"), Alias::new("option_id"))
+                            .to(Alias::new("poll_option"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 한 사람이 같은 선택지에 두 번 투표하지 않도록
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_poll_vote_option_user")
+                    .table(Alias::new("poll_vote"))
+                    .col(Alias::new("option_id"))
+                    .col(Alias::new("username"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("poll_vote")).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("poll_option")).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("poll")).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        // 기존 메시지는 모두 text로 옮긴다
+        db.execute_unprepared("ALTER TABLE chat ADD COLUMN IF NOT EXISTS content jsonb").await?;
+        db.execute_unprepared(
+            "UPDATE chat SET content = jsonb_build_object('kind', 'text', 'content', jsonb_build_object('text', message)) \
+             WHERE content IS NULL",
+        )
+        .await?;
+        db.execute_unprepared("ALTER TABLE chat ALTER COLUMN content SET NOT NULL").await?;
+        // 종류별 조회용. content에서 계산되므로 둘이 어긋날 수 없다.
+        db.execute_unprepared(
+            "ALTER TABLE chat ADD COLUMN IF NOT EXISTS kind varchar GENERATED ALWAYS AS (content->>'kind') STORED",
+        )
+        .await?;
+        db.execute_unprepared("CREATE INDEX IF NOT EXISTS idx_chat_room_kind ON chat (room_id, kind)").await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_chat_room_kind").await?;
+        db.execute_unprepared("ALTER TABLE chat DROP COLUMN IF EXISTS kind").await?;
+        db.execute_unprepared("ALTER TABLE chat DROP COLUMN IF EXISTS content").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 클라이언트가 만든 메시지 id. 재전송이 중복 저장되지 않도록 보낸 사람별로 유일하다. (NULL은 겹쳐도 됨)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("chat"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("client_msg_id")).string().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("uq_chat_sender_client_msg_id")
+                    .table(Alias::new("chat"))
+                    .col(Alias::new("sender"))
+                    .col(Alias::new("client_msg_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("uq_chat_sender_client_msg_id").table(Alias::new("chat")).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("chat"))
+                    .drop_column(Alias::new("client_msg_id"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
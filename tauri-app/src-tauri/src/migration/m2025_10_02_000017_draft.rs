@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 사용자·방마다 쓰다 만 메시지 하나. 기기 간에 이어 쓸 수 있게 서버에 둔다.
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("draft"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("username")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("room_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("text")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("updated_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_draft_room")
+                            .from(Alias::new("draft"), Alias::new("room_id"))
+                            .to(Alias::new("room"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_draft_user_room")
+                    .table(Alias::new("draft"))
+                    .col(Alias::new("username"))
+                    .col(Alias::new("room_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("draft")).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 메시지가 지워져도 북마크는 남기고 "삭제된 메시지"로 보여준다 (chat_id만 NULL이 됨)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("bookmark"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("username")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("chat_id")).integer().null())
+                    .col(ColumnDef::new(Alias::new("room_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("sender")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("note")).text().null())
+                    .col(ColumnDef::new(Alias::new("tags")).text().not_null().default("[]"))
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(Alias::new("updated_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_bookmark_chat")
+                            .from(Alias::new("bookmark"), Alias::new("chat_id"))
+                            .to(Alias::new("chat"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_bookmark_user_chat")
+                    .table(Alias::new("bookmark"))
+                    .col(Alias::new("username"))
+                    .col(Alias::new("chat_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("bookmark")).to_owned())
+            .await
+    }
+}
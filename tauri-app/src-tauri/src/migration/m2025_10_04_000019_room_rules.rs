@@ -0,0 +1,30 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 방별 메시지 검증 규칙 (validation::ValidationRules). NULL이면 서버 규칙만 적용.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("room"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("rules")).json_binary().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("room"))
+                    .drop_column(Alias::new("rules"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
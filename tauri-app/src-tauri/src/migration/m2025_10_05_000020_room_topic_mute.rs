@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // /topic으로 정하는 방 주제
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("room"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("topic")).string().null())
+                    .to_owned(),
+            )
+            .await?;
+        // /mute: 사용자별 방 알림 끄기 (읽음 위치와 같은 사용자·방 단위라 room_read에 둔다)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("room_read"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("muted_until")).timestamp().null())
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(Table::alter().table(Alias::new("room_read")).drop_column(Alias::new("muted_until")).to_owned())
+            .await?;
+        manager
+            .alter_table(Table::alter().table(Alias::new("room")).drop_column(Alias::new("topic")).to_owned())
+            .await
+    }
+}
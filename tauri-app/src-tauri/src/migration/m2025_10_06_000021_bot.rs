@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 봇도 일반 사용자처럼 방에 참여하고 메시지를 보내므로 users에 함께 두고 표시만 한다
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("users"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("is_bot")).boolean().not_null().default(false))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("owner")).string().null())
+                    .to_owned(),
+            )
+            .await?;
+        // 토큰은 해시만 저장한다 (원문은 발급할 때 한 번만 보여줌)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("bot"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("username")).string().not_null().unique_key())
+                    .col(ColumnDef::new(Alias::new("owner")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("token_hash")).string().not_null().unique_key())
+                    .col(ColumnDef::new(Alias::new("webhook_url")).string().null())
+                    .col(ColumnDef::new(Alias::new("events")).text().not_null().default("[\"message\"]"))
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("bot")).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("users"))
+                    .drop_column(Alias::new("is_bot"))
+                    .drop_column(Alias::new("owner"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
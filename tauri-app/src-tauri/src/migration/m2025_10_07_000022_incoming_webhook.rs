@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 방별 수신 webhook. 주소에 들어가는 토큰은 해시만 저장한다.
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("incoming_webhook"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("room_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("name")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("token_hash")).string().not_null().unique_key())
+                    .col(ColumnDef::new(Alias::new("created_by")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(Alias::new("last_used_at")).timestamp().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_incoming_webhook_room")
+                            .from(Alias::new("incoming_webhook"), Alias::new("room_id"))
+                            .to(Alias::new("room"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // webhook이 남긴 메시지 표시 (sender는 연동 이름이라 사용자와 겹칠 수 있다)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("chat"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("webhook_id")).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_chat_webhook")
+                    .from(Alias::new("chat"), Alias::new("webhook_id"))
+                    .to(Alias::new("incoming_webhook"), Alias::new("id"))
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(ForeignKey::drop().name("fk_chat_webhook").table(Alias::new("chat")).to_owned())
+            .await?;
+        manager
+            .alter_table(Table::alter().table(Alias::new("chat")).drop_column(Alias::new("webhook_id")).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("incoming_webhook")).to_owned())
+            .await
+    }
+}
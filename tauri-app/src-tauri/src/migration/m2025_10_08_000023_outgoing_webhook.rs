@@ -0,0 +1,106 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 방 이벤트를 받는 외부 주소. secret은 서명에 써야 하므로 원문으로 둔다.
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("outgoing_webhook"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("room_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("url")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("secret")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("events")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("active")).boolean().not_null().default(true))
+                    .col(ColumnDef::new(Alias::new("created_by")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_outgoing_webhook_room")
+                            .from(Alias::new("outgoing_webhook"), Alias::new("room_id"))
+                            .to(Alias::new("room"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // 보낼 이벤트 큐이자 전달 기록. pending → delivered 또는 재시도를 다 쓰면 dead.
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("webhook_delivery"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("webhook_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("event")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("payload")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("status")).string().not_null().default("pending"))
+                    .col(ColumnDef::new(Alias::new("attempts")).integer().not_null().default(0))
+                    .col(ColumnDef::new(Alias::new("next_attempt_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(Alias::new("delivered_at")).timestamp().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_webhook_delivery_webhook")
+                            .from(Alias::new("webhook_delivery"), Alias::new("webhook_id"))
+                            .to(Alias::new("outgoing_webhook"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_webhook_delivery_due")
+                    .table(Alias::new("webhook_delivery"))
+                    .col(Alias::new("status"))
+                    .col(Alias::new("next_attempt_at"))
+                    .to_owned(),
+            )
+            .await?;
+        // 시도마다 한 줄 (응답 코드, 오류, 걸린 시간)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("webhook_attempt"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Alias::new("delivery_id")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("attempt")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("status_code")).integer().null())
+                    .col(ColumnDef::new(Alias::new("error")).text().null())
+                    .col(ColumnDef::new(Alias::new("duration_ms")).big_integer().not_null())
+                    .col(ColumnDef::new(Alias::new("attempted_at")).timestamp().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_webhook_attempt_delivery")
+                            .from(Alias::new("webhook_attempt"), Alias::new("delivery_id"))
+                            .to(Alias::new("webhook_delivery"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("webhook_attempt")).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("webhook_delivery")).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("outgoing_webhook")).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // /remind처럼 예약을 만드는 요청도 재전송이 중복 예약되지 않도록 chat.client_msg_id와 같은 규칙을 둔다
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("scheduled_chat"))
+                    .add_column_if_not_exists(ColumnDef::new(Alias::new("client_msg_id")).string().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("uq_scheduled_chat_sender_client_msg_id")
+                    .table(Alias::new("scheduled_chat"))
+                    .col(Alias::new("sender"))
+                    .col(Alias::new("client_msg_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("uq_scheduled_chat_sender_client_msg_id")
+                    .table(Alias::new("scheduled_chat"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("scheduled_chat"))
+                    .drop_column(Alias::new("client_msg_id"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -4,6 +4,26 @@ mod m2025_09_15_000001_init;
 mod m2025_09_15_000002_recreate_users;
 mod m2025_09_15_000003_add_profile_fields;
 mod m2025_09_16_000004_room_read;
+mod m2025_09_20_000005_attachment;
+mod m2025_09_21_000006_link_preview;
+mod m2025_09_22_000007_chat_search;
+mod m2025_09_23_000008_room_name;
+mod m2025_09_24_000009_chat_delivery;
+mod m2025_09_25_000010_scheduled_chat;
+mod m2025_09_26_000011_disappearing_messages;
+mod m2025_09_27_000012_view_once;
+mod m2025_09_28_000013_chat_forward;
+mod m2025_09_29_000014_poll;
+mod m2025_09_30_000015_chat_content;
+mod m2025_10_01_000016_chat_client_msg_id;
+mod m2025_10_02_000017_draft;
+mod m2025_10_03_000018_bookmark;
+mod m2025_10_04_000019_room_rules;
+mod m2025_10_05_000020_room_topic_mute;
+mod m2025_10_06_000021_bot;
+mod m2025_10_07_000022_incoming_webhook;
+mod m2025_10_08_000023_outgoing_webhook;
+mod m2025_10_09_000024_scheduled_client_msg_id;
 
 #[derive(DeriveMigrationName)]
 pub struct Migrator;
@@ -16,6 +36,26 @@ impl MigratorTrait for Migrator {
             Box::new(m2025_09_15_000002_recreate_users::Migration),
             Box::new(m2025_09_15_000003_add_profile_fields::Migration),
             Box::new(m2025_09_16_000004_room_read::Migration),
+            Box::new(m2025_09_20_000005_attachment::Migration),
+            Box::new(m2025_09_21_000006_link_preview::Migration),
+            Box::new(m2025_09_22_000007_chat_search::Migration),
+            Box::new(m2025_09_23_000008_room_name::Migration),
+            Box::new(m2025_09_24_000009_chat_delivery::Migration),
+            Box::new(m2025_09_25_000010_scheduled_chat::Migration),
+            Box::new(m2025_09_26_000011_disappearing_messages::Migration),
+            Box::new(m2025_09_27_000012_view_once::Migration),
+            Box::new(m2025_09_28_000013_chat_forward::Migration),
+            Box::new(m2025_09_29_000014_poll::Migration),
+            Box::new(m2025_09_30_000015_chat_content::Migration),
+            Box::new(m2025_10_01_000016_chat_client_msg_id::Migration),
+            Box::new(m2025_10_02_000017_draft::Migration),
+            Box::new(m2025_10_03_000018_bookmark::Migration),
+            Box::new(m2025_10_04_000019_room_rules::Migration),
+            Box::new(m2025_10_05_000020_room_topic_mute::Migration),
+            Box::new(m2025_10_06_000021_bot::Migration),
+            Box::new(m2025_10_07_000022_incoming_webhook::Migration),
+            Box::new(m2025_10_08_000023_outgoing_webhook::Migration),
+            Box::new(m2025_10_09_000024_scheduled_client_msg_id::Migration),
         ]
     }
 }
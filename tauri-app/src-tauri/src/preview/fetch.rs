@@ -0,0 +1,317 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use reqwest::{header, redirect, StatusCode};
+use url::{Host, Url};
+
+/// 미리보기 요청 제한. 기본값은 내부망/루프백 접근을 모두 막는다.
+#[derive(Debug, Clone)]
+pub struct FetchPolicy {
+    /// 사설 대역이어도 허용할 호스트 (소문자, 포트 제외)
+    pub allow_hosts: Vec<String>,
+    pub max_bytes: usize,
+    pub timeout: Duration,
+    pub max_redirects: usize,
+}
+
+impl Default for FetchPolicy {
+    fn default() -> Self {
+        FetchPolicy {
+            allow_hosts: vec![],
+            max_bytes: 512 * 1024,
+            timeout: Duration::from_secs(5),
+            max_redirects: 3,
+        }
+    }
+}
+
+impl FetchPolicy {
+    /// LINK_PREVIEW_ALLOWLIST=intranet.example.com,10.0.0.5 형식으로 허용 호스트를 받는다.
+    pub fn from_env() -> Self {
+        Self::from_env_var("LINK_PREVIEW_ALLOWLIST")
+    }
+
+    /// 허용 호스트를 쉼표로 구분한 환경 변수 name에서 받는다 (webhook은 WEBHOOK_ALLOWLIST)
+    pub fn from_env_var(name: &str) -> Self {
+        let allow_hosts = std::env::var(name)
+            .unwrap_or_default()
+            .split(',')
+            .map(|h| h.trim().to_ascii_lowercase())
+            .filter(|h| !h.is_empty())
+            .collect();
+        FetchPolicy { allow_hosts, ..Default::default() }
+    }
+
+    fn allows(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        self.allow_hosts.iter().any(|h| h.eq_ignore_ascii_case(host))
+    }
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    InvalidUrl,
+    Blocked(String),
+    Timeout,
+    NotHtml,
+    Http(String),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::InvalidUrl => write!(f, "invalid url"),
+            FetchError::Blocked(host) => write!(f, "blocked address: {}", host),
+            FetchError::Timeout => write!(f, "timed out"),
+            FetchError::NotHtml => write!(f, "not an html document"),
+            FetchError::Http(e) => write!(f, "http error: {}", e),
+        }
+    }
+}
+
+pub struct FetchedPage {
+    /// 리다이렉트를 따라간 최종 URL (상대 경로 이미지 해석용)
+    pub url: Url,
+    pub body: String,
+}
+
+/// 내부망, 루프백, 링크로컬, 멀티캐스트 등 외부 서비스가 아닌 주소인지 판단한다.
+pub fn is_blocked_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_blocked_v4(v4),
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_blocked_v4(v4);
+            }
+            let seg = v6.segments();
+            // 6to4는 안에 든 IPv4 주소로 판단한다
+            if seg[0] == 0x2002 {
+                let [a, b] = seg[1].to_be_bytes();
+                let [c, d] = seg[2].to_be_bytes();
+                return is_blocked_v4(Ipv4Addr::new(a, b, c, d));
+            }
+            v6.is_unspecified()
+                || v6.is_loopback()
+                || v6.is_multicast()
+                || seg[..6].iter().all(|s| *s == 0) // ::/96 IPv4 호환 (폐기됨)
+                || (seg[0] & 0xfe00) == 0xfc00 // fc00::/7 unique local
+                || (seg[0] & 0xffc0) == 0xfe80 // fe80::/10 link local
+                || (seg[0] & 0xffc0) == 0xfec0 // fec0::/10 site local (폐기됨)
+                || (seg[0] == 0x64 && seg[1] == 0xff9b) // 64:ff9b::/96 NAT64
+                || (seg[0] == 0x2001 && seg[1] == 0) // 2001::/32 Teredo
+        }
+    }
+}
+
+fn is_blocked_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)) // 100.64.0.0/10 CGNAT
+        || (a == 192 && b == 0 && c == 0) // 192.0.0.0/24
+        || (a == 198 && (b == 18 || b == 19)) // 198.18.0.0/15 벤치마크
+        || a >= 240 // 240.0.0.0/4 예약
+}
+
+// 호스트를 직접 해석해 차단 대역을 걸러낸다. 허용 목록에 있으면 검사하지 않는다.
+async fn resolve_checked(url: &Url, policy: &FetchPolicy) -> Result<Vec<SocketAddr>, FetchError> {
+    let host = url.host().ok_or(FetchError::InvalidUrl)?;
+    let port = url.port_or_known_default().ok_or(FetchError::InvalidUrl)?;
+    let host_str = url.host_str().unwrap_or_default().to_string();
+    let addrs: Vec<SocketAddr> = match host {
+        Host::Ipv4(ip) => vec![SocketAddr::new(IpAddr::V4(ip), port)],
+        Host::Ipv6(ip) => vec![SocketAddr::new(IpAddr::V6(ip), port)],
+        Host::Domain(domain) => tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|e| FetchError::Http(e.to_string()))?
+            .collect(),
+    };
+    if addrs.is_empty() {
+        return Err(FetchError::InvalidUrl);
+    }
+    if !policy.allows(&host_str) && addrs.iter().any(|a| is_blocked_ip(a.ip())) {
+        return Err(FetchError::Blocked(host_str));
+    }
+    Ok(addrs)
+}
+
+/// webhook처럼 사용자가 정한 주소로 요청을 보낼 클라이언트.
+/// 주소를 검사한 결과로 DNS를 고정하고, 리다이렉트는 따라가지 않는다(3xx는 실패로 본다).
+pub async fn pinned_client(url: &str, policy: &FetchPolicy) -> Result<(reqwest::Client, Url), FetchError> {
+    let url = Url::parse(url).map_err(|_| FetchError::InvalidUrl)?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(FetchError::InvalidUrl);
+    }
+    let addrs = resolve_checked(&url, policy).await?;
+    let mut builder = reqwest::Client::builder().redirect(redirect::Policy::none()).timeout(policy.timeout);
+    if let Some(Host::Domain(domain)) = url.host() {
+        builder = builder.resolve_to_addrs(domain, &addrs);
+    }
+    let client = builder.build().map_err(|e| FetchError::Http(e.to_string()))?;
+    Ok((client, url))
+}
+
+/// SSRF 방어를 거쳐 HTML 문서를 가져온다. 리다이렉트는 매 단계마다 주소를 다시 검사한다.
+pub async fn fetch_html(url: &str, policy: &FetchPolicy) -> Result<FetchedPage, FetchError> {
+    match tokio::time::timeout(policy.timeout, fetch_inner(url, policy)).await {
+        Ok(result) => result,
+        Err(_) => Err(FetchError::Timeout),
+    }
+}
+
+fn http_error(e: reqwest::Error) -> FetchError {
+    if e.is_timeout() {
+        FetchError::Timeout
+    } else {
+        FetchError::Http(e.to_string())
+    }
+}
+
+async fn fetch_inner(url: &str, policy: &FetchPolicy) -> Result<FetchedPage, FetchError> {
+    let mut current = Url::parse(url).map_err(|_| FetchError::InvalidUrl)?;
+    for _ in 0..=policy.max_redirects {
+        if current.scheme() != "http" && current.scheme() != "https" {
+            return Err(FetchError::InvalidUrl);
+        }
+        let addrs = resolve_checked(&current, policy).await?;
+
+        // 검사한 주소로만 연결되도록 DNS 결과를 고정 (DNS rebinding 방지)
+        let mut builder = reqwest::Client::builder()
+            .redirect(redirect::Policy::none())
+            .timeout(policy.timeout)
+            .user_agent("chatapp-link-preview/0.1");
+        if let Some(Host::Domain(domain)) = current.host() {
+            builder = builder.resolve_to_addrs(domain, &addrs);
+        }
+        let client = builder.build().map_err(|e| FetchError::Http(e.to_string()))?;
+
+        let mut resp = client
+            .get(current.clone())
+            .header(header::ACCEPT, "text/html,application/xhtml+xml")
+            .send()
+            .await
+            .map_err(http_error)?;
+
+        if resp.status().is_redirection() {
+            let location = resp
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| FetchError::Http("redirect without location".to_string()))?;
+            current = current.join(location).map_err(|_| FetchError::InvalidUrl)?;
+            continue;
+        }
+        if resp.status() != StatusCode::OK {
+            return Err(FetchError::Http(resp.status().to_string()));
+        }
+        let is_html = resp
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|ct| ct.starts_with("text/html") || ct.starts_with("application/xhtml+xml"))
+            .unwrap_or(false);
+        if !is_html {
+            return Err(FetchError::NotHtml);
+        }
+
+        // 메타 태그는 문서 앞부분에 있으므로 상한까지만 읽고 끊는다
+        let mut body = Vec::new();
+        while let Some(chunk) = resp.chunk().await.map_err(http_error)? {
+            let remaining = policy.max_bytes - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+            if body.len() >= policy.max_bytes {
+                break;
+            }
+        }
+        return Ok(FetchedPage {
+            url: current,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        });
+    }
+    Err(FetchError::Http("too many redirects".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{response::Redirect, routing::get, Router};
+
+    async fn stand_in() -> SocketAddr {
+        let app = Router::new()
+            .route("/page", get(|| async {
+                (
+                    [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                    r#"<html><head><meta property="og:title" content="Stand-in"></head></html>"#,
+                )
+            }))
+            .route("/big", get(|| async {
+                ([(header::CONTENT_TYPE, "text/html")], "a".repeat(64 * 1024))
+            }))
+            .route("/image", get(|| async { ([(header::CONTENT_TYPE, "image/png")], "png") }))
+            .route("/hop", get(|| async { Redirect::temporary("/page") }))
+            .route("/slow", get(|| async {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                ([(header::CONTENT_TYPE, "text/html")], "late")
+            }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        addr
+    }
+
+    fn local_policy() -> FetchPolicy {
+        FetchPolicy { allow_hosts: vec!["127.0.0.1".to_string()], ..Default::default() }
+    }
+
+    #[test]
+    fn blocks_private_ranges() {
+        for ip in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1", "::127.0.0.1", "::a9fe:a9fe", "2002:7f00:1::1", "2002:a9fe:a9fe::", "fec0::1", "2001:0:4136:e378::1"] {
+            assert!(is_blocked_ip(ip.parse().unwrap()), "{} should be blocked", ip);
+        }
+        for ip in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111", "2002:808:808::1"] {
+            assert!(!is_blocked_ip(ip.parse().unwrap()), "{} should be allowed", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn loopback_is_blocked_without_allowlist() {
+        let addr = stand_in().await;
+        let result = fetch_html(&format!("http://{}/page", addr), &FetchPolicy::default()).await;
+        assert!(matches!(result, Err(FetchError::Blocked(_))));
+    }
+
+    #[tokio::test]
+    async fn allowlisted_host_is_fetched_and_redirect_followed() {
+        let addr = stand_in().await;
+        let page = fetch_html(&format!("http://{}/hop", addr), &local_policy()).await.unwrap();
+        assert_eq!(page.url.path(), "/page");
+        assert!(page.body.contains("Stand-in"));
+    }
+
+    #[tokio::test]
+    async fn pinned_client_checks_address_and_keeps_redirects() {
+        let addr = stand_in().await;
+        let blocked = pinned_client(&format!("http://{}/hop", addr), &FetchPolicy::default()).await;
+        assert!(matches!(blocked, Err(FetchError::Blocked(_))));
+        let (client, url) = pinned_client(&format!("http://{}/hop", addr), &local_policy()).await.unwrap();
+        assert!(client.get(url).send().await.unwrap().status().is_redirection());
+    }
+
+    #[tokio::test]
+    async fn caps_size_rejects_non_html_and_times_out() {
+        let addr = stand_in().await;
+        let policy = FetchPolicy { max_bytes: 1024, timeout: Duration::from_millis(500), ..local_policy() };
+        let page = fetch_html(&format!("http://{}/big", addr), &policy).await.unwrap();
+        assert_eq!(page.body.len(), 1024);
+        let image = fetch_html(&format!("http://{}/image", addr), &policy).await;
+        assert!(matches!(image, Err(FetchError::NotHtml)));
+        let slow = fetch_html(&format!("http://{}/slow", addr), &policy).await;
+        assert!(matches!(slow, Err(FetchError::Timeout)));
+    }
+}
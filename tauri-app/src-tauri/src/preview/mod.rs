@@ -0,0 +1,180 @@
+pub mod fetch;
+pub mod og;
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+};
+use tokio::sync::broadcast;
+use url::Url;
+
+use crate::api::event::ChatEvent;
+use crate::content::MessageContent;
+use crate::entities::{
+    chat::Model as Chat,
+    link_preview::{ActiveModel, Column, Entity as LinkPreviewEntity, Model as LinkPreview},
+};
+use fetch::FetchPolicy;
+
+/// 메시지 하나에서 미리보기를 만드는 최대 링크 수
+const MAX_LINKS_PER_MESSAGE: usize = 3;
+/// 캐시된 미리보기를 다시 가져오기까지의 시간
+const CACHE_TTL_HOURS: i64 = 24;
+
+fn url_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?i)\bhttps?://[^\s<>"']+"#).unwrap())
+}
+
+fn policy() -> &'static FetchPolicy {
+    static POLICY: OnceLock<FetchPolicy> = OnceLock::new();
+    POLICY.get_or_init(FetchPolicy::from_env)
+}
+
+/// 캐시 키로 쓰는 URL 정규화: fragment와 utm_* 추적 파라미터를 지운다.
+pub fn normalize_url(raw: &str) -> Option<String> {
+    let mut url = Url::parse(raw).ok()?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return None;
+    }
+    url.host_str()?;
+    url.set_fragment(None);
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_"))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    Some(url.to_string())
+}
+
+/// 메시지 본문에서 링크를 찾아 정규화한다. (문장 끝 구두점은 제외)
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    for m in url_re().find_iter(text) {
+        let raw = m.as_str().trim_end_matches(['.', ',', '!', '?', ')', ']', ';', ':']);
+        if let Some(url) = normalize_url(raw) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        if urls.len() >= MAX_LINKS_PER_MESSAGE {
+            break;
+        }
+    }
+    urls
+}
+
+// 링크를 찾을 본문. markdown은 [글자](주소) 안의 주소가 요약에서 빠지므로 원문을 본다.
+fn link_source(chat: &Chat) -> &str {
+    match &chat.content {
+        MessageContent::Text { text, .. } => text,
+        _ => &chat.message,
+    }
+}
+
+/// 저장된 메시지의 링크 미리보기를 백그라운드에서 만들고 link_preview 이벤트로 알린다.
+pub fn spawn_for_message(conn: DatabaseConnection, queue: broadcast::Sender<ChatEvent>, chat: Chat) {
+    let urls = extract_urls(link_source(&chat));
+    if urls.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        for url in urls {
+            if let Some(preview) = preview_for(&conn, &url).await {
+                let _ = queue.send(ChatEvent::LinkPreview {
+                    room_id: chat.room_id,
+                    chat_id: chat.id,
+                    preview,
+                });
+            }
+        }
+    });
+}
+
+// 캐시가 신선하면 그대로 쓰고, 아니면 가져와서 upsert
+async fn preview_for(conn: &DatabaseConnection, url: &str) -> Option<LinkPreview> {
+    let cached = LinkPreviewEntity::find()
+        .filter(Column::Url.eq(url))
+        .one(conn)
+        .await
+        .ok()
+        .flatten();
+    let stale_before = chrono::Utc::now().naive_utc() - chrono::Duration::hours(CACHE_TTL_HOURS);
+    if let Some(cached) = cached {
+        if cached.fetched_at > stale_before {
+            return cached.title.is_some().then_some(cached);
+        }
+    }
+
+    // 실패한 링크도 빈 행으로 남겨 TTL 동안 다시 두드리지 않는다
+    let meta = match fetch::fetch_html(url, policy()).await {
+        Ok(page) => og::parse(&page.body, &page.url),
+        Err(e) => {
+            eprintln!("link preview fetch failed for {url}: {e}");
+            og::PageMeta::default()
+        }
+    };
+    let row = ActiveModel {
+        id: ActiveValue::NotSet,
+        url: ActiveValue::Set(url.to_string()),
+        title: ActiveValue::Set(meta.title),
+        description: ActiveValue::Set(meta.description),
+        image: ActiveValue::Set(meta.image),
+        site_name: ActiveValue::Set(meta.site_name),
+        fetched_at: ActiveValue::Set(chrono::Utc::now().naive_utc()),
+    };
+    let upsert = LinkPreviewEntity::insert(row)
+        .on_conflict(
+            OnConflict::column(Column::Url)
+                .update_columns([Column::Title, Column::Description, Column::Image, Column::SiteName, Column::FetchedAt])
+                .to_owned(),
+        )
+        .exec_with_returning(conn)
+        .await;
+    match upsert {
+        Ok(preview) if preview.title.is_some() => Some(preview),
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("link preview save failed for {url}: {e}");
+            None
+        }
+    }
+}
+
+/// get_chat에서 쓰는 메시지별 미리보기 (아직 가져오지 못한 링크는 빠진다)
+pub async fn load_for_chats(conn: &DatabaseConnection, chats: &[Chat]) -> HashMap<i32, Vec<LinkPreview>> {
+    let per_chat: Vec<(i32, Vec<String>)> = chats
+        .iter()
+        .map(|c| (c.id, extract_urls(link_source(c))))
+        .filter(|(_, urls)| !urls.is_empty())
+        .collect();
+    let mut all: Vec<String> = per_chat.iter().flat_map(|(_, urls)| urls.clone()).collect();
+    all.sort();
+    all.dedup();
+    if all.is_empty() {
+        return HashMap::new();
+    }
+
+    let found: HashMap<String, LinkPreview> = LinkPreviewEntity::find()
+        .filter(Column::Url.is_in(all))
+        .filter(Column::Title.is_not_null())
+        .all(conn)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| (p.url.clone(), p))
+        .collect();
+
+    per_chat
+        .into_iter()
+        .map(|(id, urls)| (id, urls.iter().filter_map(|u| found.get(u).cloned()).collect()))
+        .collect()
+}
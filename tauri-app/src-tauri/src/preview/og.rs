@@ -0,0 +1,120 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+use url::Url;
+
+const MAX_TITLE_CHARS: usize = 200;
+const MAX_DESCRIPTION_CHARS: usize = 400;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PageMeta {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub site_name: Option<String>,
+}
+
+fn meta_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?is)<meta\s[^>]*>").unwrap())
+}
+
+fn attr_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?is)([a-z:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap())
+}
+
+fn title_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap())
+}
+
+// 미리보기에 필요한 정도의 엔티티만 풀어준다
+fn decode_entities(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn clean(s: &str, max_chars: usize) -> Option<String> {
+    let text = decode_entities(s).split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    Some(text.chars().take(max_chars).collect())
+}
+
+/// OpenGraph 메타 태그를 우선 쓰고, 없으면 `<title>`/description 메타로 대신한다.
+pub fn parse(html: &str, base: &Url) -> PageMeta {
+    let mut og = PageMeta::default();
+    let mut fallback_description = None;
+
+    for tag in meta_re().find_iter(html) {
+        let mut key = None;
+        let mut content = None;
+        for cap in attr_re().captures_iter(tag.as_str()) {
+            let value = cap.get(2).or(cap.get(3)).or(cap.get(4)).map(|m| m.as_str());
+            match cap[1].to_ascii_lowercase().as_str() {
+                "property" | "name" => key = value.map(|v| v.to_ascii_lowercase()),
+                "content" => content = value,
+                _ => {}
+            }
+        }
+        let (Some(key), Some(content)) = (key, content) else { continue };
+        match key.as_str() {
+            "og:title" if og.title.is_none() => og.title = clean(content, MAX_TITLE_CHARS),
+            "og:description" if og.description.is_none() => og.description = clean(content, MAX_DESCRIPTION_CHARS),
+            "og:site_name" if og.site_name.is_none() => og.site_name = clean(content, MAX_TITLE_CHARS),
+            "og:image" | "og:image:url" if og.image.is_none() => {
+                // 상대 경로는 최종 URL 기준으로 풀고, http(s)만 남긴다
+                og.image = base
+                    .join(decode_entities(content.trim()).as_str())
+                    .ok()
+                    .filter(|u| u.scheme() == "http" || u.scheme() == "https")
+                    .map(|u| u.to_string());
+            }
+            "description" if fallback_description.is_none() => fallback_description = clean(content, MAX_DESCRIPTION_CHARS),
+            _ => {}
+        }
+    }
+
+    if og.title.is_none() {
+        og.title = title_re().captures(html).and_then(|c| clean(&c[1], MAX_TITLE_CHARS));
+    }
+    if og.description.is_none() {
+        og.description = fallback_description;
+    }
+    og
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_open_graph_and_resolves_image() {
+        let html = r#"<html><head><title>Fallback</title>
+            <meta property="og:title" content="Tom &amp; Jerry">
+            <meta content='/img/cover.png' property='og:image'>
+            <meta name="description" content="plain description">
+            </head></html>"#;
+        let base = Url::parse("https://example.com/post/1").unwrap();
+        let meta = parse(html, &base);
+        assert_eq!(meta.title.as_deref(), Some("Tom & Jerry"));
+        assert_eq!(meta.image.as_deref(), Some("https://example.com/img/cover.png"));
+        assert_eq!(meta.description.as_deref(), Some("plain description"));
+    }
+
+    #[test]
+    fn falls_back_to_title_and_drops_script_images() {
+        let html = r#"<title>  제목
+            입니다 </title><meta property="og:image" content="javascript:alert(1)">"#;
+        let meta = parse(html, &Url::parse("https://example.com").unwrap());
+        assert_eq!(meta.title.as_deref(), Some("제목 입니다"));
+        assert_eq!(meta.image, None);
+    }
+}
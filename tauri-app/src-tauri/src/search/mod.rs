@@ -0,0 +1,35 @@
+pub mod query;
+pub mod snippet;
+pub mod tokenize;
+
+use sea_orm::{ConnectionTrait, DbBackend, DbErr, Statement};
+
+/// 메시지의 검색 색인(chat.search_vector)을 갱신한다. 엔티티에는 없는 Postgres 전용 컬럼.
+pub async fn index_message<C: ConnectionTrait>(conn: &C, chat_id: i32, message: &str) -> Result<(), DbErr> {
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "UPDATE chat SET search_vector = to_tsvector('simple', $1) WHERE id = $2",
+        [tokenize::index_text(message).into(), chat_id.into()],
+    ))
+    .await
+    .map(|_| ())
+}
+
+/// 여러 메시지의 색인을 한 번에 갱신한다 (가져오기처럼 한꺼번에 넣을 때)
+pub async fn index_messages<C: ConnectionTrait>(conn: &C, messages: &[(i32, &str)]) -> Result<(), DbErr> {
+    if messages.is_empty() {
+        return Ok(());
+    }
+    let mut rows = Vec::with_capacity(messages.len());
+    let mut values: Vec<sea_orm::Value> = Vec::with_capacity(messages.len() * 2);
+    for (i, (chat_id, message)) in messages.iter().enumerate() {
+        rows.push(format!("(${}::int, ${}::text)", i * 2 + 1, i * 2 + 2));
+        values.push((*chat_id).into());
+        values.push(tokenize::index_text(message).into());
+    }
+    let sql = format!(
+        "UPDATE chat SET search_vector = to_tsvector('simple', v.body) FROM (VALUES {}) AS v(id, body) WHERE chat.id = v.id",
+        rows.join(", ")
+    );
+    conn.execute(Statement::from_sql_and_values(DbBackend::Postgres, sql, values)).await.map(|_| ())
+}
@@ -0,0 +1,312 @@
+//! 검색어 문법: `from:alice in:"project room" has:file has:link before:2025-09-01 after:2025-08-01 "exact phrase" 단어`
+//!
+//! 필터는 chat/room/attachment에 대한 SeaORM `Condition`으로, 남은 단어는 전문 검색어로 쓴다.
+
+use std::fmt;
+
+use chrono::NaiveDate;
+use sea_orm::{
+    sea_query::{Expr, Query},
+    ColumnTrait, Condition,
+};
+
+use crate::entities::{attachment, chat, room};
+
+const OPERATORS: [&str; 5] = ["from", "in", "has", "before", "after"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Has {
+    /// 첨부가 하나라도 있는 메시지
+    File,
+    /// 음성 첨부
+    Voice,
+    /// http(s) 링크가 들어간 메시지
+    Link,
+}
+
+impl Has {
+    const ALL: [(&'static str, Has); 3] = [("file", Has::File), ("voice", Has::Voice), ("link", Has::Link)];
+
+    fn parse(value: &str) -> Option<Has> {
+        Has::ALL.iter().find(|(name, _)| name.eq_ignore_ascii_case(value)).map(|(_, has)| *has)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub from: Vec<String>,
+    pub rooms: Vec<String>,
+    pub has: Vec<Has>,
+    pub before: Option<NaiveDate>,
+    pub after: Option<NaiveDate>,
+    pub phrases: Vec<String>,
+    pub words: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 문제가 된 위치 (0부터 세는 글자 위치)
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "검색어 {}번째 글자: {}", self.position + 1, self.message)
+    }
+}
+
+fn error<T>(position: usize, message: impl Into<String>) -> Result<T, ParseError> {
+    Err(ParseError { position, message: message.into() })
+}
+
+// 오타 안내용 (form: -> from:). 인접 글자 뒤바뀜도 한 번으로 센다.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    // 여는 따옴표 위치에서 호출. 닫는 따옴표까지 읽는다.
+    fn quoted(&mut self) -> Result<String, ParseError> {
+        let open = self.pos;
+        self.pos += 1;
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c == '"' {
+                let value: String = self.chars[start..self.pos].iter().collect();
+                self.pos += 1;
+                return Ok(value);
+            }
+            self.pos += 1;
+        }
+        error(open, "따옴표가 닫히지 않았습니다.")
+    }
+
+    fn bare(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| !c.is_whitespace()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+}
+
+fn parse_date(value: &str, position: usize) -> Result<NaiveDate, ParseError> {
+    match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => Ok(date),
+        Err(_) => error(position, format!("날짜 '{}'를 읽을 수 없습니다. YYYY-MM-DD 형식으로 입력하세요.", value)),
+    }
+}
+
+pub fn parse(input: &str) -> Result<SearchQuery, ParseError> {
+    let mut query = SearchQuery::default();
+    let mut cur = Cursor { chars: input.chars().collect(), pos: 0 };
+
+    loop {
+        cur.skip_spaces();
+        let Some(c) = cur.peek() else { break };
+        let start = cur.pos;
+
+        if c == '"' {
+            let phrase = cur.quoted()?;
+            if !phrase.trim().is_empty() {
+                query.phrases.push(phrase.trim().to_string());
+            }
+            continue;
+        }
+
+        // key: 형태인지 확인
+        let key_len = cur.chars[start..]
+            .iter()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .count();
+        let key: String = cur.chars[start..start + key_len].iter().collect::<String>().to_ascii_lowercase();
+        let has_colon = cur.chars.get(start + key_len) == Some(&':');
+
+        if !has_colon || key.is_empty() {
+            query.words.push(cur.bare());
+            continue;
+        }
+        if !OPERATORS.contains(&key.as_str()) {
+            // 링크(https://...) 같은 일반 단어는 그대로 두고, 연산자 오타만 알려준다
+            if let Some(op) = OPERATORS.iter().find(|op| edit_distance(&key, op) <= 1 && key.len() >= 2) {
+                return error(start, format!("알 수 없는 필터 '{}:' 입니다. '{}:'을(를) 말씀하신 건가요?", key, op));
+            }
+            query.words.push(cur.bare());
+            continue;
+        }
+
+        cur.pos = start + key_len + 1;
+        let value_pos = cur.pos;
+        let value = match cur.peek() {
+            Some('"') => cur.quoted()?,
+            Some(c) if !c.is_whitespace() => cur.bare(),
+            _ => return error(value_pos, format!("'{}:' 뒤에 값이 필요합니다.", key)),
+        };
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            return error(value_pos, format!("'{}:' 뒤에 값이 필요합니다.", key));
+        }
+
+        match key.as_str() {
+            "from" => query.from.push(value.trim_start_matches('@').to_string()),
+            "in" => query.rooms.push(value),
+            "has" => match Has::parse(&value) {
+                Some(has) => query.has.push(has),
+                None => {
+                    let allowed: Vec<&str> = Has::ALL.iter().map(|(name, _)| *name).collect();
+                    return error(value_pos, format!("has:{} 는 지원하지 않습니다. ({} 중 하나)", value, allowed.join(", ")));
+                }
+            },
+            "before" => query.before = Some(parse_date(&value, value_pos)?),
+            "after" => query.after = Some(parse_date(&value, value_pos)?),
+            _ => unreachable!(),
+        }
+    }
+
+    // 두 경계 모두 그 날짜를 제외하므로 사이에 하루 이상 있어야 한다
+    if let (Some(after), Some(before)) = (query.after, query.before) {
+        if after.succ_opt().is_none_or(|next| next >= before) {
+            return error(0, "after:와 before: 사이에 검색할 날짜가 없습니다. (두 날짜는 제외됩니다)");
+        }
+    }
+    Ok(query)
+}
+
+impl SearchQuery {
+    /// 전문 검색(tsquery)에 넘길 텍스트. 구절도 포함해 색인을 탈 수 있게 한다.
+    pub fn fulltext(&self) -> String {
+        self.words.iter().chain(self.phrases.iter()).cloned().collect::<Vec<_>>().join(" ")
+    }
+
+    /// 필터를 chat 기준 조건으로 변환한다. (단어 검색은 포함하지 않음)
+    pub fn condition(&self) -> Condition {
+        let mut cond = Condition::all();
+
+        if !self.from.is_empty() {
+            cond = cond.add(chat::Column::Sender.is_in(self.from.clone()));
+        }
+
+        // 방 이름(부분 일치), 방 번호, 참가자 이름으로 방을 찾는다
+        if !self.rooms.is_empty() {
+            let mut rooms = Condition::any();
+            for name in &self.rooms {
+                rooms = rooms.add(Expr::cust_with_values("strpos(lower(coalesce(room.name, '')), lower($1)) > 0", [name.clone()]));
+                rooms = rooms.add(room::Column::Participants.contains(format!("\"{}\"", name)));
+                if let Ok(id) = name.parse::<i32>() {
+                    rooms = rooms.add(room::Column::Id.eq(id));
+                }
+            }
+            cond = cond.add(
+                chat::Column::RoomId.in_subquery(
+                    Query::select().column(room::Column::Id).from(room::Entity).cond_where(rooms).to_owned(),
+                ),
+            );
+        }
+
+        for has in &self.has {
+            cond = cond.add(match has {
+                Has::File => chat::Column::Id.in_subquery(
+                    Query::select().column(attachment::Column::ChatId).from(attachment::Entity).to_owned(),
+                ),
+                Has::Voice => chat::Column::Id.in_subquery(
+                    Query::select()
+                        .column(attachment::Column::ChatId)
+                        .from(attachment::Entity)
+                        .and_where(attachment::Column::Kind.eq("voice"))
+                        .to_owned(),
+                ),
+                // 요약(message)에서는 마크다운 링크 주소가 빠지므로 원본 content에서 찾는다
+                Has::Link => Condition::any()
+                    .add(Expr::cust("strpos(chat.content::text, 'http://') > 0"))
+                    .add(Expr::cust("strpos(chat.content::text, 'https://') > 0"))
+                    .into(),
+            });
+        }
+
+        if let Some(before) = self.before {
+            cond = cond.add(chat::Column::Timestamp.lt(before.and_hms_opt(0, 0, 0).unwrap()));
+        }
+        if let Some(after) = self.after {
+            // after:는 그 날짜를 제외 (다음 날 0시부터)
+            let next = after.succ_opt().unwrap_or(after);
+            cond = cond.add(chat::Column::Timestamp.gte(next.and_hms_opt(0, 0, 0).unwrap()));
+        }
+
+        for phrase in &self.phrases {
+            cond = cond.add(Expr::cust_with_values("strpos(lower(chat.message), lower($1)) > 0", [phrase.clone()]));
+        }
+        cond
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_operators_phrases_and_words() {
+        let q = parse(r#"from:alice in:"project room" has:file has:link before:2025-09-01 after:2025-08-01 "exact phrase" 회의록"#).unwrap();
+        assert_eq!(q.from, vec!["alice"]);
+        assert_eq!(q.rooms, vec!["project room"]);
+        assert_eq!(q.has, vec![Has::File, Has::Link]);
+        assert_eq!(q.before, NaiveDate::from_ymd_opt(2025, 9, 1));
+        assert_eq!(q.after, NaiveDate::from_ymd_opt(2025, 8, 1));
+        assert_eq!(q.phrases, vec!["exact phrase"]);
+        assert_eq!(q.words, vec!["회의록"]);
+    }
+
+    #[test]
+    fn urls_are_plain_words() {
+        let q = parse("https://example.com/a?b=c").unwrap();
+        assert_eq!(q.words, vec!["https://example.com/a?b=c"]);
+    }
+
+    #[test]
+    fn reports_helpful_errors() {
+        let e = parse("hello form:bob").unwrap_err();
+        assert_eq!(e.position, 6);
+        assert!(e.message.contains("from:"));
+        assert!(parse("has:gif").unwrap_err().message.contains("file"));
+        assert!(parse("before:2025-13-01").unwrap_err().message.contains("YYYY-MM-DD"));
+        assert_eq!(parse(r#"in:"unclosed"#).unwrap_err().position, 3);
+        assert!(parse("from: bob").is_err());
+        assert!(parse("after:2025-09-02 before:2025-09-01").is_err());
+        // 경계 날짜는 제외되므로 이어진 두 날짜는 빈 범위
+        assert!(parse("after:2025-09-01 before:2025-09-02").is_err());
+        assert!(parse("after:2025-09-01 before:2025-09-03").is_ok());
+    }
+}
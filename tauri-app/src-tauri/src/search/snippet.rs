@@ -0,0 +1,79 @@
+/// 검색 결과에 보여줄 앞뒤 문맥 길이 (글자 수)
+const CONTEXT_BEFORE: usize = 30;
+const SNIPPET_CHARS: usize = 120;
+
+fn escape_html(c: char, out: &mut String) {
+    match c {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        '\'' => out.push_str("&#39;"),
+        _ => out.push(c),
+    }
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// 메시지에서 검색어가 처음 나오는 곳 주변을 잘라 `<mark>`로 감싼다. 나머지 텍스트는 HTML 이스케이프.
+pub fn highlight(message: &str, query: &str) -> String {
+    let chars: Vec<char> = message.chars().collect();
+    let folded: Vec<char> = chars.iter().map(|c| fold(*c)).collect();
+    let terms: Vec<Vec<char>> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.chars().map(fold).collect())
+        .collect();
+
+    let mut marked = vec![false; chars.len()];
+    for term in &terms {
+        if term.is_empty() || term.len() > folded.len() {
+            continue;
+        }
+        for i in 0..=folded.len() - term.len() {
+            if folded[i..i + term.len()] == term[..] {
+                marked[i..i + term.len()].iter_mut().for_each(|m| *m = true);
+            }
+        }
+    }
+
+    let first = marked.iter().position(|m| *m).unwrap_or(0);
+    let start = first.saturating_sub(CONTEXT_BEFORE);
+    let end = (start + SNIPPET_CHARS).min(chars.len());
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    let mut open = false;
+    for i in start..end {
+        if marked[i] && !open {
+            out.push_str("<mark>");
+            open = true;
+        } else if !marked[i] && open {
+            out.push_str("</mark>");
+            open = false;
+        }
+        escape_html(chars[i], &mut out);
+    }
+    if open {
+        out.push_str("</mark>");
+    }
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_matches_and_escapes() {
+        assert_eq!(highlight("<b>내일 회의</b> 10시", "회의"), "&lt;b&gt;내일 <mark>회의</mark>&lt;/b&gt; 10시");
+        assert_eq!(highlight("Deploy done", "deploy"), "<mark>Deploy</mark> done");
+    }
+}
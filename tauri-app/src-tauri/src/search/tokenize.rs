@@ -0,0 +1,85 @@
+//! 한글은 띄어쓰기 단위가 검색 단위와 맞지 않으므로(조사, 어미) 글자 bigram으로 색인한다.
+//! 라틴 문자/숫자는 단어 그대로 둔다. 결과는 `to_tsvector('simple', ..)`에 그대로 넣을 수 있다.
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{AC00}'..='\u{D7A3}'   // 한글 음절
+        | '\u{1100}'..='\u{11FF}' // 한글 자모
+        | '\u{3130}'..='\u{318F}' // 호환용 자모
+        | '\u{3040}'..='\u{30FF}' // 히라가나/가타카나
+        | '\u{4E00}'..='\u{9FFF}' // CJK 한자
+    )
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// 색인: 한 글자 검색도 되도록 unigram + bigram
+    Index,
+    /// 검색: bigram만 (한 글자 단어는 unigram)
+    Query,
+}
+
+fn push_cjk_run(run: &[char], mode: Mode, out: &mut Vec<String>) {
+    if run.len() == 1 || mode == Mode::Index {
+        out.extend(run.iter().map(|c| c.to_string()));
+    }
+    out.extend(run.windows(2).map(|w| w.iter().collect::<String>()));
+}
+
+fn tokens(text: &str, mode: Mode) -> Vec<String> {
+    let mut out = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        let chars: Vec<char> = word.to_lowercase().chars().collect();
+        let mut start = 0;
+        while start < chars.len() {
+            let cjk = is_cjk(chars[start]);
+            let end = chars[start..]
+                .iter()
+                .position(|c| is_cjk(*c) != cjk)
+                .map(|p| start + p)
+                .unwrap_or(chars.len());
+            if cjk {
+                push_cjk_run(&chars[start..end], mode, &mut out);
+            } else {
+                out.push(chars[start..end].iter().collect());
+            }
+            start = end;
+        }
+    }
+    out
+}
+
+/// chat.search_vector에 넣을 색인용 토큰 문자열
+pub fn index_text(text: &str) -> String {
+    let mut toks = tokens(text, Mode::Index);
+    toks.sort();
+    toks.dedup();
+    toks.join(" ")
+}
+
+/// `plainto_tsquery('simple', ..)`에 넣을 검색어 토큰 문자열 (모든 토큰 AND)
+pub fn query_text(text: &str) -> String {
+    let mut toks = tokens(text, Mode::Query);
+    toks.dedup();
+    toks.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn korean_is_split_into_bigrams() {
+        assert_eq!(query_text("회의록"), "회의 의록");
+        assert_eq!(query_text("밥"), "밥");
+        // 조사가 붙어도 "회의"가 색인에 들어가야 검색된다
+        let indexed = index_text("내일 회의를 합니다");
+        assert!(indexed.split(' ').any(|t| t == "회의"));
+    }
+
+    #[test]
+    fn latin_words_and_mixed_runs() {
+        assert_eq!(query_text("Deploy v2, OK?"), "deploy v2 ok");
+        assert_eq!(query_text("API서버"), "api 서버");
+    }
+}
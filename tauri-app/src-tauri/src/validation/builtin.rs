@@ -0,0 +1,166 @@
+//! 기본 검증기: 글자 수, 금지 패턴, 링크 허용 목록, 첨부 제한
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+use url::Url;
+
+use super::{Candidate, MessageValidator, Span, Violation};
+use crate::content::MessageContent;
+
+fn url_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?i)\bhttps?://[^\s<>"']+"#).unwrap())
+}
+
+/// 일반 메시지 본문의 글자(grapheme) 수 한도. 이모지 조합이나 한글 한 글자를 한 글자로 센다.
+pub struct GraphemeLimit {
+    pub max: usize,
+}
+
+impl MessageValidator for GraphemeLimit {
+    fn check(&self, candidate: &Candidate) -> Vec<Violation> {
+        let MessageContent::Text { text, .. } = candidate.content else {
+            return vec![];
+        };
+        // 한도를 넘는 첫 글자의 위치부터 끝까지가 문제 구간
+        match text.grapheme_indices(true).nth(self.max) {
+            Some((cut, _)) => vec![Violation {
+                code: "too_long",
+                message: format!("메시지는 {}자 이내여야 합니다.", self.max),
+                field: "content.text".to_string(),
+                span: Some(Span::from_bytes(text, cut, text.len())),
+            }],
+            None => vec![],
+        }
+    }
+}
+
+/// 정규식에 걸리는 구간이 있으면 막는다.
+pub struct ForbiddenPatterns {
+    patterns: Vec<Regex>,
+}
+
+impl ForbiddenPatterns {
+    pub fn new(patterns: &[String]) -> Result<Self, String> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| format!("금지 패턴 '{}'이(가) 올바르지 않습니다: {}", p, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ForbiddenPatterns { patterns })
+    }
+}
+
+impl MessageValidator for ForbiddenPatterns {
+    fn check(&self, candidate: &Candidate) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for (field, text) in candidate.texts() {
+            for pattern in &self.patterns {
+                if let Some(m) = pattern.find(text) {
+                    violations.push(Violation {
+                        code: "forbidden_pattern",
+                        message: "보낼 수 없는 표현이 들어 있습니다.".to_string(),
+                        field: field.to_string(),
+                        span: Some(Span::from_bytes(text, m.start(), m.end())),
+                    });
+                }
+            }
+        }
+        violations
+    }
+}
+
+/// 허용한 도메인(과 그 하위 도메인)으로 가는 링크만 보낼 수 있다.
+pub struct LinkAllowlist {
+    domains: Vec<String>,
+}
+
+impl LinkAllowlist {
+    pub fn new(domains: &[String]) -> Self {
+        let domains = domains
+            .iter()
+            .map(|d| d.trim().trim_start_matches("*.").trim_end_matches('.').to_ascii_lowercase())
+            .filter(|d| !d.is_empty())
+            .collect();
+        LinkAllowlist { domains }
+    }
+
+    fn allows(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.domains
+            .iter()
+            .any(|d| host == *d || host.strip_suffix(d.as_str()).is_some_and(|rest| rest.ends_with('.')))
+    }
+}
+
+impl MessageValidator for LinkAllowlist {
+    fn check(&self, candidate: &Candidate) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for (field, text) in candidate.texts() {
+            // 코드 조각 안의 주소는 링크로 보이지 않으므로 검사하지 않는다
+            if field == "content.code" {
+                continue;
+            }
+            for m in url_re().find_iter(text) {
+                let host = Url::parse(m.as_str()).ok().and_then(|u| u.host_str().map(str::to_string));
+                if host.as_deref().is_some_and(|h| self.allows(h)) {
+                    continue;
+                }
+                violations.push(Violation {
+                    code: "link_not_allowed",
+                    message: format!("허용되지 않은 링크입니다. ({} 만 가능)", self.domains.join(", ")),
+                    field: field.to_string(),
+                    span: Some(Span::from_bytes(text, m.start(), m.end())),
+                });
+            }
+        }
+        violations
+    }
+}
+
+/// 첨부 개수, 크기, 종류 제한
+pub struct AttachmentLimit {
+    pub max_bytes: Option<u64>,
+    pub max_count: Option<usize>,
+    pub kinds: Option<Vec<String>>,
+}
+
+impl MessageValidator for AttachmentLimit {
+    fn check(&self, candidate: &Candidate) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if let Some(max) = self.max_count {
+            if candidate.attachments.len() > max {
+                violations.push(Violation {
+                    code: "too_many_attachments",
+                    message: format!("첨부는 {}개까지 보낼 수 있습니다.", max),
+                    field: "attachments".to_string(),
+                    span: None,
+                });
+            }
+        }
+        for (i, attachment) in candidate.attachments.iter().enumerate() {
+            if let Some(kinds) = &self.kinds {
+                if !kinds.contains(&attachment.kind) {
+                    violations.push(Violation {
+                        code: "attachment_kind_not_allowed",
+                        message: format!("이 방에는 {} 첨부를 보낼 수 없습니다.", attachment.kind),
+                        field: format!("attachments[{}]", i),
+                        span: None,
+                    });
+                }
+            }
+            if let Some(max) = self.max_bytes {
+                if attachment.size > max {
+                    violations.push(Violation {
+                        code: "attachment_too_large",
+                        message: format!("첨부 파일은 {}KB 이내여야 합니다.", max / 1024),
+                        field: format!("attachments[{}]", i),
+                        span: None,
+                    });
+                }
+            }
+        }
+        violations
+    }
+}
@@ -0,0 +1,219 @@
+//! 메시지 검증 파이프라인.
+//!
+//! 서버 설정(환경 변수)과 방 설정(room.rules)에서 각각 검증기 목록을 만들고, 보내기 전에 둘 다 통과해야 한다.
+//! 방 설정은 서버 설정을 느슨하게 할 수 없고 더 조일 수만 있다. 실패는 필드와 위치를 담은 `Violation`으로
+//! 돌려줘서 클라이언트가 입력창에서 문제 구간을 표시할 수 있게 한다.
+
+pub mod builtin;
+
+use std::sync::OnceLock;
+
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize};
+
+use crate::content::MessageContent;
+use builtin::{AttachmentLimit, ForbiddenPatterns, GraphemeLimit, LinkAllowlist};
+
+/// 서버 기본 글자 수 한도 (바이트가 아니라 사용자가 보는 글자 기준)
+const DEFAULT_MAX_GRAPHEMES: usize = 500;
+
+/// 검증 실패 하나
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// 기계가 읽는 이유 (too_long, forbidden_pattern, link_not_allowed, attachment_too_large, ...)
+    pub code: &'static str,
+    pub message: String,
+    /// 문제가 된 필드 (content.text, attachments[0] 등)
+    pub field: String,
+    /// 본문에서 문제가 된 구간. JS 문자열 인덱스와 맞도록 UTF-16 단위, end는 미포함.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// 바이트 구간을 UTF-16 구간으로
+    pub fn from_bytes(text: &str, start: usize, end: usize) -> Span {
+        let start16 = text[..start].encode_utf16().count();
+        Span { start: start16, end: start16 + text[start..end].encode_utf16().count() }
+    }
+}
+
+/// 첨부 정보 (저장 전에 알 수 있는 것만)
+#[derive(Debug, Clone)]
+pub struct AttachmentMeta {
+    pub kind: String,
+    pub size: u64,
+}
+
+/// 검증할 메시지
+pub struct Candidate<'a> {
+    pub content: &'a MessageContent,
+    pub attachments: &'a [AttachmentMeta],
+}
+
+impl Candidate<'_> {
+    /// 사용자가 쓴 글이 들어가는 필드와 본문
+    pub fn texts(&self) -> Vec<(&'static str, &str)> {
+        match self.content {
+            MessageContent::Text { text, .. } => vec![("content.text", text)],
+            MessageContent::Poll { question } => vec![("content.question", question)],
+            MessageContent::Code { code, .. } => vec![("content.code", code)],
+            MessageContent::Location { label: Some(label), .. } => vec![("content.label", label)],
+            _ => vec![],
+        }
+    }
+}
+
+/// 검증기 하나. 문제가 없으면 빈 Vec을 돌려준다.
+pub trait MessageValidator: Send + Sync {
+    fn check(&self, candidate: &Candidate) -> Vec<Violation>;
+}
+
+/// 검증 규칙. 서버는 환경 변수에서, 방은 room.rules(jsonb)에서 읽는다.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, Hash, FromJsonQueryResult)]
+pub struct ValidationRules {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_graphemes: Option<usize>,
+    /// 정규식 목록
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forbidden_patterns: Vec<String>,
+    /// 허용할 링크 도메인 (하위 도메인 포함). 없으면 모든 링크 허용.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_allowlist: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attachment_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attachments: Option<usize>,
+    /// 허용할 첨부 종류 (voice, image, video, code)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_kinds: Option<Vec<String>>,
+}
+
+fn env_list(name: &str, sep: char) -> Option<Vec<String>> {
+    let value = std::env::var(name).ok()?;
+    Some(value.split(sep).map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+}
+
+fn env_num<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|v| v.trim().parse().ok())
+}
+
+impl ValidationRules {
+    /// CHAT_MAX_GRAPHEMES, CHAT_FORBIDDEN_PATTERNS(줄바꿈 구분), CHAT_LINK_ALLOWLIST(쉼표 구분),
+    /// CHAT_MAX_ATTACHMENT_BYTES, CHAT_MAX_ATTACHMENTS, CHAT_ATTACHMENT_KINDS(쉼표 구분)
+    pub fn from_env() -> Self {
+        ValidationRules {
+            max_graphemes: Some(env_num("CHAT_MAX_GRAPHEMES").unwrap_or(DEFAULT_MAX_GRAPHEMES)),
+            forbidden_patterns: env_list("CHAT_FORBIDDEN_PATTERNS", '\n').unwrap_or_default(),
+            link_allowlist: env_list("CHAT_LINK_ALLOWLIST", ','),
+            max_attachment_bytes: env_num("CHAT_MAX_ATTACHMENT_BYTES"),
+            max_attachments: env_num("CHAT_MAX_ATTACHMENTS"),
+            attachment_kinds: env_list("CHAT_ATTACHMENT_KINDS", ','),
+        }
+    }
+}
+
+/// 순서대로 실행하는 검증기 목록
+#[derive(Default)]
+pub struct Pipeline {
+    validators: Vec<Box<dyn MessageValidator>>,
+}
+
+impl Pipeline {
+    /// 규칙에서 기본 검증기들을 만든다. 정규식이 틀리면 어느 것이 틀렸는지 알려준다.
+    pub fn from_rules(rules: &ValidationRules) -> Result<Pipeline, String> {
+        let mut pipeline = Pipeline::default();
+        if let Some(max) = rules.max_graphemes {
+            pipeline = pipeline.with(GraphemeLimit { max });
+        }
+        if !rules.forbidden_patterns.is_empty() {
+            pipeline = pipeline.with(ForbiddenPatterns::new(&rules.forbidden_patterns)?);
+        }
+        if let Some(domains) = &rules.link_allowlist {
+            pipeline = pipeline.with(LinkAllowlist::new(domains));
+        }
+        if rules.max_attachment_bytes.is_some() || rules.max_attachments.is_some() || rules.attachment_kinds.is_some() {
+            pipeline = pipeline.with(AttachmentLimit {
+                max_bytes: rules.max_attachment_bytes,
+                max_count: rules.max_attachments,
+                kinds: rules.attachment_kinds.clone(),
+            });
+        }
+        Ok(pipeline)
+    }
+
+    /// 검증기를 뒤에 붙인다 (기본 검증기 외의 것을 끼울 때)
+    pub fn with(mut self, validator: impl MessageValidator + 'static) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// 모든 검증기를 돌려 실패를 모은다. 한 번에 모두 보여줄 수 있게 중간에 멈추지 않는다.
+    pub fn run(&self, candidate: &Candidate) -> Vec<Violation> {
+        self.validators.iter().flat_map(|v| v.check(candidate)).collect()
+    }
+}
+
+/// 서버 전체에 적용하는 파이프라인 (처음 쓸 때 한 번 만든다)
+pub fn server_pipeline() -> &'static Pipeline {
+    static PIPELINE: OnceLock<Pipeline> = OnceLock::new();
+    PIPELINE.get_or_init(|| {
+        let mut rules = ValidationRules::from_env();
+        Pipeline::from_rules(&rules).unwrap_or_else(|e| {
+            eprintln!("invalid CHAT_FORBIDDEN_PATTERNS, ignoring: {e}");
+            rules.forbidden_patterns.clear();
+            Pipeline::from_rules(&rules).unwrap_or_default()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_graphemes_not_bytes() {
+        let rules = ValidationRules { max_graphemes: Some(5), ..Default::default() };
+        let pipeline = Pipeline::from_rules(&rules).unwrap();
+        let check = |text: &str| {
+            let content = MessageContent::text(text);
+            pipeline.run(&Candidate { content: &content, attachments: &[] })
+        };
+        // 한글 다섯 글자는 15바이트지만 통과
+        assert!(check("안녕하세요").is_empty());
+        // 가족 이모지는 여러 코드포인트지만 한 글자
+        assert!(check("👨‍👩‍👧ab").is_empty());
+        let violations = check("안녕하세요!!");
+        assert_eq!(violations[0].code, "too_long");
+        assert_eq!(violations[0].span, Some(Span { start: 5, end: 7 }));
+    }
+
+    #[test]
+    fn collects_every_violation_with_spans() {
+        let rules = ValidationRules {
+            forbidden_patterns: vec!["(?i)spam".to_string()],
+            link_allowlist: Some(vec!["example.com".to_string()]),
+            max_attachments: Some(1),
+            ..Default::default()
+        };
+        let pipeline = Pipeline::from_rules(&rules).unwrap();
+        let content = MessageContent::text("가 SPAM https://docs.example.com/a https://evil.test/x");
+        let attachments = vec![
+            AttachmentMeta { kind: "image".to_string(), size: 1 },
+            AttachmentMeta { kind: "image".to_string(), size: 1 },
+        ];
+        let violations = pipeline.run(&Candidate { content: &content, attachments: &attachments });
+        let codes: Vec<&str> = violations.iter().map(|v| v.code).collect();
+        assert_eq!(codes, vec!["forbidden_pattern", "link_not_allowed", "too_many_attachments"]);
+        assert_eq!(violations[0].span, Some(Span { start: 2, end: 6 }));
+        assert_eq!(violations[1].field, "content.text");
+
+        assert!(Pipeline::from_rules(&ValidationRules { forbidden_patterns: vec!["(".to_string()], ..Default::default() }).is_err());
+    }
+}
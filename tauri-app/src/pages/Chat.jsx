@@ -4,12 +4,39 @@
 import React, { useState, useRef, useEffect } from "react";
 import "@/styles/chat.css";
 import { useParams, useNavigate } from "react-router-dom";
-import postJson, { defaultApiInstance as api } from "@/utils/api";
+import { defaultApiInstance as api } from "@/utils/api";
 import { subscribeChat } from "@/utils/chatSse";
 import { getProfile } from "@/utils/profileApi";
 import { getRoom } from "@/utils/roomApi";
 import { findOrCreateDmRoom } from "@/utils/roomJoin";
 
+const DRAFT_DEBOUNCE_MS = 800;
+
+// 네트워크 오류(응답 없음)일 때만 같은 본문으로 다시 보낸다
+async function sendWithRetry(body, attempts = 3) {
+  for (let i = 0; i < attempts; i++) {
+    try {
+      return (await api.post("/chat/send", body)).data;
+    } catch (error) {
+      if (error.response || i === attempts - 1) {
+        return { success: 0, error: error?.message ?? "network error" };
+      }
+      await new Promise(resolve => setTimeout(resolve, 500 * (i + 1)));
+    }
+  }
+}
+
+// 먼저 그려 둔 메시지(client_msg_id가 같은 것)를 서버가 저장한 메시지로 바꾼다
+function reconcile(messages, incoming) {
+  const idx = incoming.client_msg_id
+    ? messages.findIndex(m => m.client_msg_id === incoming.client_msg_id)
+    : messages.findIndex(m => m.id === incoming.id);
+  if (idx < 0) return [...messages, incoming];
+  const next = [...messages];
+  next[idx] = { ...next[idx], ...incoming, pending: false };
+  return next;
+}
+
 const fallbackMyAvatar = "https://mdbcdn.b-cdn.net/img/Photos/Avatars/avatar-6.webp";
 
 // room 정보를 기반으로 상대 사용자 표시 정보를 구성
@@ -43,6 +70,7 @@ function Chat() {
   const [roomId, setRoomId] = useState(null);
   const messagesEndRef = useRef(null);
   const eventSourceRef = useRef(null);
+  const draftTimerRef = useRef(null);
 
   function LOCAL_getUsername() {
     return localStorage.getItem("username") || "";
@@ -152,13 +180,40 @@ function Chat() {
     return () => { ignore = true; };
   }, [roomId, friend?.name, meName]);
 
+  // 다른 기기에서 쓰다 만 메시지 불러오기
+  useEffect(() => {
+    if (!roomId || !meName) return;
+    let ignore = false;
+    api.get(`/room/${roomId}/draft`, { params: { username: meName } })
+      .then(res => {
+        if (!ignore && res.data?.success === 1 && res.data.data) setInput(res.data.data.text);
+      })
+      .catch(error => console.error("Failed to load draft:", error));
+    return () => {
+      ignore = true;
+      clearTimeout(draftTimerRef.current);
+    };
+  }, [roomId, meName]);
+
+  // 입력이 멈추고 잠시 뒤에 한 번만 임시 저장
+  const handleInputChange = (value) => {
+    setInput(value);
+    clearTimeout(draftTimerRef.current);
+    // 고친 시각을 함께 보내, 전송 뒤에 늦게 도착한 저장이 보낸 내용을 되살리지 않게 한다
+    const editedAt = new Date().toISOString();
+    draftTimerRef.current = setTimeout(() => {
+      api.put(`/room/${roomId}/draft`, { username: meName, text: value, edited_at: editedAt })
+        .catch(error => console.error("Failed to save draft:", error));
+    }, DRAFT_DEBOUNCE_MS);
+  };
+
   // SSE 실시간 메시지 구독
   useEffect(() => {
     if (!roomId || !friend) return;
     if (eventSourceRef.current) eventSourceRef.current.close();
     eventSourceRef.current = subscribeChat(Number(roomId), async (msg) => {
       const newMessage = { ...msg, from: msg.sender === meName ? "me" : "other", text: msg.message };
-      setMessages(prev => ([...prev, newMessage]));
+      setMessages(prev => reconcile(prev, newMessage));
       
       // 새 메시지 도착시 읽음 상태 업데이트 (본인이 보낸 메시지가 아닌 경우에도 읽음 처리)
       try {
@@ -181,16 +236,31 @@ function Chat() {
   const handleSend = async () => {
     const text = input.trim();
     if (!text || !friend || !roomId) return;
-    const res = await postJson("/chat/send", {
+    // 재시도해도 같은 id를 보내므로 서버에는 한 번만 저장된다
+    const clientMsgId = crypto.randomUUID();
+    // 서버가 전송과 함께 임시 저장을 지우므로 대기 중인 저장은 취소
+    clearTimeout(draftTimerRef.current);
+    setMessages(prev => ([...prev, { client_msg_id: clientMsgId, from: "me", text, pending: true }]));
+    setInput("");
+    const res = await sendWithRetry({
       sender: meName,
       message: text,
-      room_id: Number(roomId)
+      room_id: Number(roomId),
+      client_msg_id: clientMsgId
     });
     if (res.success !== 1) {
+      setMessages(prev => prev.filter(m => m.client_msg_id !== clientMsgId));
+      setInput(text);
       alert(res.error || "메시지 전송 실패");
       return;
     }
-    setInput("");
+    // 명령어는 입력한 글 대신 다른 메시지(투표, 주제 안내)를 남기거나 나에게만 답한다
+    if (res.chat?.client_msg_id !== clientMsgId) {
+      setMessages(prev => prev.filter(m => m.client_msg_id !== clientMsgId));
+    }
+    if (res.ephemeral) alert(res.ephemeral);
+    if (!res.chat) return;
+    setMessages(prev => reconcile(prev, { ...res.chat, from: "me", text: res.chat.message }));
   };
 
   if (!friend) {
@@ -232,7 +302,7 @@ function Chat() {
           type="text"
           placeholder={friend.name + "에게 메시지 보내기"}
           value={input}
-          onChange={e => setInput(e.target.value)}
+          onChange={e => handleInputChange(e.target.value)}
           onKeyDown={e => { if (e.key === "Enter") handleSend(); }}
           className="chat-input"
         />
@@ -179,6 +179,13 @@ function Chats() {
                     const other = getOtherParticipants(room.participants);
                     const p = profileMap[other];
                     const status = p?.status || "";
+                    if (room.draft?.text) {
+                      return (
+                        <span className="chats-ellipsis">
+                          <span style={{ color: "#c63a46" }}>[임시저장] </span>{room.draft.text}
+                        </span>
+                      );
+                    }
                     return (
                       <span className="chats-ellipsis">
                         {status || (room.last_message || "메시지를 시작해보세요")}
@@ -1,15 +1,44 @@
-export function subscribeChat(roomId, onMessage) {
-    const url = `http://localhost:3100/api/chat/subscribe?room_id=${encodeURIComponent(roomId)}`;
+import { defaultApiInstance as api } from "./api";
+
+// 받은 메시지의 도착을 서버에 알려 보낸 사람에게 "전달됨" 상태를 보여준다
+function ackDelivery(data) {
+    const me = localStorage.getItem("username") || "";
+    if (!me || !data || data.sender === me || typeof data.id !== "number") return;
+    api.post("/chat/ack", { username: me, chat_ids: [data.id] }).catch(() => {});
+}
+
+// 입력 중 상태 알림. 서버가 사용자별로 빈도를 제한하므로 키 입력마다 불러도 된다.
+export function sendTyping(roomId, active = true) {
+    const me = localStorage.getItem("username") || "";
+    if (!me) return;
+    api.post("/chat/typing", { username: me, room_id: roomId, active }).catch(() => {});
+}
+
+export function subscribeChat(roomId, onMessage, onTyping) {
+    // username을 붙여야 명령어 답처럼 나에게만 오는 이벤트도 받는다
+    const me = localStorage.getItem("username") || "";
+    const url = `http://localhost:3100/api/chat/subscribe?room_id=${encodeURIComponent(roomId)}&username=${encodeURIComponent(me)}`;
     const eventSource = new EventSource(url);
     eventSource.onmessage = (event) => {
         try {
             const data = JSON.parse(event.data);
             if (data.room_id === roomId) {
                 onMessage(data);
+                ackDelivery(data);
             }
         } catch (e) {
             // ignore
         }
     };
+    if (onTyping) {
+        eventSource.addEventListener("typing", (event) => {
+            try {
+                const data = JSON.parse(event.data);
+                if (data.room_id === roomId) onTyping(data);
+            } catch (e) {
+                // ignore
+            }
+        });
+    }
     return eventSource;
 }